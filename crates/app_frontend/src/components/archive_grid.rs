@@ -0,0 +1,58 @@
+use leptos::*;
+use sim_engine::archive::{Elite, EliteArchive};
+
+/// The elite archive for the running simulation: one thumbnail per occupied
+/// behaviour cell (first descriptor left to right, second bottom to top).
+/// Clicking one reloads that configuration into a live session (view-only
+/// elites, which their params can't rebuild, are dimmed).
+#[component]
+pub fn ArchiveGrid(
+    archive: ReadSignal<EliteArchive>,
+    /// Registry key of the running simulation
+    sim: ReadSignal<&'static str>,
+    /// Called with the elite whose thumbnail was clicked
+    #[prop(into)]
+    on_select: Callback<Elite>,
+) -> impl IntoView {
+    view! {
+        <div style="padding: 1rem 1.5rem; border-bottom: 1px solid #444;">
+            <h2 style="color: #fa0; font-weight: 300; font-size: 1rem; margin: 0 0 0.75rem 0;">
+                {move || format!("Archive ({} elites)", archive.with(|a| a.for_sim(sim.get()).count()))}
+            </h2>
+            {move || {
+                let key = sim.get();
+                archive.with(|archive| {
+                    let bins = archive.bins;
+                    let cells = (0..bins).rev().flat_map(|row| (0..bins).map(move |col| (row, col)));
+                    view! {
+                        <div style=format!("display: grid; grid-template-columns: repeat({}, 1fr); gap: 1px; background: #111;", bins)>
+                            {cells.map(|cell| match archive.get(key, cell).cloned() {
+                                Some(elite) => {
+                                    let title = format!(
+                                        "fitness {:.3}, found by {} at step {}{}\n{}",
+                                        elite.fitness,
+                                        elite.source,
+                                        elite.step,
+                                        if elite.view_only { " (view only)" } else { "" },
+                                        elite.params.iter().map(|(k, v)| format!("{}={:?}", k, v)).collect::<Vec<_>>().join(", ")
+                                    );
+                                    let src = elite.thumbnail.clone().unwrap_or_default();
+                                    let opacity = if elite.view_only { 0.5 } else { 1.0 };
+                                    view! {
+                                        <img
+                                            src=src
+                                            title=title
+                                            on:click=move |_| on_select.call(elite.clone())
+                                            style=format!("width: 100%; aspect-ratio: 1; image-rendering: pixelated; cursor: pointer; background: #333; opacity: {};", opacity)
+                                        />
+                                    }.into_view()
+                                }
+                                None => view! { <div style="aspect-ratio: 1; background: #1a1a1a;"></div> }.into_view(),
+                            }).collect_view()}
+                        </div>
+                    }
+                })
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,32 @@
+use leptos::*;
+use inference_engine::BRAINS;
+
+/// Which brain drives the running simulation. Picking one restarts the
+/// simulation with it, keeping the sidebar picks.
+#[component]
+pub fn BrainPicker(
+    /// Name of the brain in charge now
+    brain: ReadSignal<&'static str>,
+    /// Called with the brain name when a button is clicked
+    #[prop(into)]
+    on_select: Callback<&'static str>,
+) -> impl IntoView {
+    view! {
+        <div style="padding: 1rem 1.5rem; border-bottom: 1px solid #444;">
+            <h2 style="color: #0af; font-weight: 300; font-size: 1rem; margin: 0 0 0.75rem 0;">
+                "Brain"
+            </h2>
+            {BRAINS.iter().map(|&(name, label, description)| {
+                let style = move || {
+                    let border = if brain.get() == name { "#0af" } else { "transparent" };
+                    format!("font-size: 0.7rem; margin: 0 0.25rem 0.25rem 0; border: 1px solid {};", border)
+                };
+                view! {
+                    <button on:click=move |_| on_select.call(name) title=description style=style>
+                        {label}
+                    </button>
+                }
+            }).collect_view()}
+        </div>
+    }
+}
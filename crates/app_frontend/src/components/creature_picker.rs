@@ -0,0 +1,30 @@
+use leptos::*;
+use sim_engine::lenia::CREATURES;
+
+/// Lenia creature library.
+#[component]
+pub fn CreaturePicker(
+    /// Called with the creature name when a button is clicked
+    #[prop(into)]
+    on_select: Callback<&'static str>,
+) -> impl IntoView {
+    view! {
+        <div style="padding: 1rem 1.5rem; border-bottom: 1px solid #444;">
+            <h2 style="color: #a0f; font-weight: 300; font-size: 1rem; margin: 0 0 0.75rem 0;">
+                "Lenia Creatures"
+            </h2>
+            {CREATURES.iter().map(|creature| {
+                let name = creature.name;
+                view! {
+                    <button
+                        on:click=move |_| on_select.call(name)
+                        title=creature.description
+                        style="font-size: 0.7rem; margin: 0 0.25rem 0.25rem 0;"
+                    >
+                        {name}
+                    </button>
+                }
+            }).collect_view()}
+        </div>
+    }
+}
@@ -5,3 +5,10 @@
 pub mod simulation_viewport;
 pub mod discovery_feed;
 pub mod control_bar;
+pub mod preset_gallery;
+pub mod rd_model_picker;
+pub mod creature_picker;
+pub mod rule_picker;
+pub mod reward_picker;
+pub mod archive_grid;
+pub mod brain_picker;
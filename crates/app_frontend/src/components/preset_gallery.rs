@@ -0,0 +1,43 @@
+use leptos::*;
+use sim_engine::gray_scott_presets::PRESETS;
+
+/// Grid of Gray-Scott preset thumbnails.
+/// Images are pre-rendered by `headless thumbnails --out assets/gallery`.
+#[component]
+pub fn PresetGallery(
+    /// Called with the preset name when a thumbnail is clicked
+    #[prop(into)]
+    on_select: Callback<&'static str>,
+) -> impl IntoView {
+    view! {
+        <div style="padding: 1rem 1.5rem; border-bottom: 1px solid #444;">
+            <h2 style="color: #a0f; font-weight: 300; font-size: 1rem; margin: 0 0 0.75rem 0;">
+                "Presets"
+            </h2>
+            <div style="display: grid; grid-template-columns: repeat(auto-fill, minmax(64px, 1fr)); gap: 0.5rem;">
+                {PRESETS.iter().map(|preset| {
+                    let name = preset.name;
+                    let label = if preset.class.is_empty() {
+                        name.to_string()
+                    } else {
+                        format!("{} {}", preset.class, name)
+                    };
+                    view! {
+                        <div
+                            on:click=move |_| on_select.call(name)
+                            title=format!("{} (f={}, k={})", preset.description, preset.f, preset.k)
+                            style="cursor: pointer; text-align: center; font-size: 0.7rem; color: #ccc;"
+                        >
+                            <img
+                                src=format!("gallery/{}.png", name)
+                                alt=name
+                                style="width: 100%; image-rendering: pixelated; border: 1px solid #333;"
+                            />
+                            <div>{label}</div>
+                        </div>
+                    }
+                }).collect_view()}
+            </div>
+        </div>
+    }
+}
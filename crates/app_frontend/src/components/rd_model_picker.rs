@@ -0,0 +1,38 @@
+use leptos::*;
+use sim_engine::rd_models::MODELS;
+
+/// Reaction-diffusion models (other than Gray-Scott) and their presets.
+#[component]
+pub fn RdModelPicker(
+    /// Called with (model key, preset name) when a preset is clicked
+    #[prop(into)]
+    on_select: Callback<(&'static str, &'static str)>,
+) -> impl IntoView {
+    view! {
+        <div style="padding: 1rem 1.5rem; border-bottom: 1px solid #444;">
+            <h2 style="color: #a0f; font-weight: 300; font-size: 1rem; margin: 0 0 0.75rem 0;">
+                "Reaction-Diffusion Models"
+            </h2>
+            {MODELS.iter().map(|model| {
+                let key = model.key;
+                view! {
+                    <div style="margin-bottom: 0.75rem;">
+                        <div style="color: #ccc; font-size: 0.8rem; margin-bottom: 0.25rem;">{model.name}</div>
+                        {model.presets.iter().map(|preset| {
+                            let name = preset.name;
+                            view! {
+                                <button
+                                    on:click=move |_| on_select.call((key, name))
+                                    title=preset.description
+                                    style="font-size: 0.7rem; margin: 0 0.25rem 0.25rem 0;"
+                                >
+                                    {name}
+                                </button>
+                            }
+                        }).collect_view()}
+                    </div>
+                }
+            }).collect_view()}
+        </div>
+    }
+}
@@ -0,0 +1,41 @@
+use leptos::*;
+use sim_engine::reward::PRESETS;
+
+/// Which reward the agent learns from. Custom specs (weighted sums, targets)
+/// go in the text box, e.g. `0.7*coverage:0.3 + 0.3*stability`.
+#[component]
+pub fn RewardPicker(
+    /// Called with a reward spec when a preset is clicked or a custom one entered
+    #[prop(into)]
+    on_select: Callback<String>,
+) -> impl IntoView {
+    view! {
+        <div style="padding: 1rem 1.5rem; border-bottom: 1px solid #444;">
+            <h2 style="color: #0a6; font-weight: 300; font-size: 1rem; margin: 0 0 0.75rem 0;">
+                "Reward"
+            </h2>
+            {PRESETS.iter().map(|&(spec, label)| {
+                view! {
+                    <button
+                        on:click=move |_| on_select.call(spec.to_string())
+                        title=spec
+                        style="font-size: 0.7rem; margin: 0 0.25rem 0.25rem 0;"
+                    >
+                        {label}
+                    </button>
+                }
+            }).collect_view()}
+            <input
+                type="text"
+                placeholder="custom, e.g. target:x=0;z=25"
+                on:change=move |ev| {
+                    let spec = event_target_value(&ev);
+                    if !spec.trim().is_empty() {
+                        on_select.call(spec);
+                    }
+                }
+                style="width: 100%; margin-top: 0.25rem; font-size: 0.75rem;"
+            />
+        </div>
+    }
+}
@@ -0,0 +1,40 @@
+use leptos::*;
+use sim_engine::elementary::{CaRule, NOTABLE_RULES};
+
+/// 1D cellular automaton rules.
+#[component]
+pub fn RulePicker(
+    /// Called with the chosen rule, from the list or the number box
+    #[prop(into)]
+    on_select: Callback<CaRule>,
+) -> impl IntoView {
+    let on_input = move |ev| {
+        if let Ok(rule) = event_target_value(&ev).trim().parse::<u8>() {
+            on_select.call(CaRule::Elementary(rule));
+        }
+    };
+
+    view! {
+        <div style="padding: 1rem 1.5rem; border-bottom: 1px solid #444;">
+            <h2 style="color: #a0f; font-weight: 300; font-size: 1rem; margin: 0 0 0.75rem 0;">
+                "1D Rules"
+            </h2>
+            <label style="color: #ccc; font-size: 0.8rem; display: block; margin-bottom: 0.5rem;">
+                "Elementary rule (0-255): "
+                <input type="number" min="0" max="255" value="110" on:change=on_input style="width: 4rem;" />
+            </label>
+            {NOTABLE_RULES.iter().map(|notable| {
+                let rule = notable.rule;
+                view! {
+                    <button
+                        on:click=move |_| on_select.call(rule)
+                        title=notable.description
+                        style="font-size: 0.7rem; margin: 0 0.25rem 0.25rem 0;"
+                    >
+                        {notable.name}
+                    </button>
+                }
+            }).collect_view()}
+        </div>
+    }
+}
@@ -1,6 +1,8 @@
 use leptos::*;
 use crate::session::Session;
 use sim_engine::SimState;
+use sim_engine::archive::Elite;
+use sim_engine::thumbnail::species_rgb;
 use inference_engine::DiscoveryEvent;
 use wasm_bindgen::JsCast;
 use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
@@ -14,6 +16,9 @@ pub fn SimulationViewport(
     set_tick_count: WriteSignal<u64>,  
     #[prop(into)]
     on_discovery: Callback<DiscoveryEvent>,
+    /// Archive snapshots the session took this frame
+    #[prop(into)]
+    on_elite: Callback<Elite>,
 ) -> impl IntoView {
     let canvas_ref = create_node_ref::<HtmlCanvasElement>();
     
@@ -65,6 +70,10 @@ pub fn SimulationViewport(
                 accumulator.set(new_acc);
             }
 
+            for elite in session.take_elites() {
+                on_elite.call(elite);
+            }
+
             // Update UI counter
             set_tick_count.set(session.step_count);
 
@@ -112,6 +121,9 @@ fn draw_simulation(ctx: &CanvasRenderingContext2d, canvas: &HtmlCanvasElement, s
         SimState::Points(points) => draw_points(ctx, w, h, &points),
         // NEW: Draw the Chemical Soup
         SimState::FloatGrid { width, height, values } => draw_heatmap(ctx, w, h, width, height, &values),
+        SimState::Particles { width, height, positions, species, .. } => {
+            draw_particles(ctx, w, h, width, height, &positions, &species)
+        }
     }
 }
 fn draw_grid(ctx: &CanvasRenderingContext2d, w: f64, h: f64, gw: u32, gh: u32, cells: &Vec<bool>) {
@@ -139,6 +151,23 @@ fn draw_points(ctx: &CanvasRenderingContext2d, w: f64, h: f64, points: &Vec<(f64
         ctx.fill_rect(((x - min_x)/sx)*w, ((y - min_y)/sy)*h, 1.5, 1.5);
     }
 }
+fn draw_particles(ctx: &CanvasRenderingContext2d, w: f64, h: f64, bw: f64, bh: f64, positions: &[(f64, f64)], species: &[u8]) {
+    if bw <= 0.0 || bh <= 0.0 { return; }
+    // Keep the box square on screen
+    let scale = (w / bw).min(h / bh);
+    let (ox, oy) = ((w - bw * scale) / 2.0, (h - bh * scale) / 2.0);
+    ctx.set_stroke_style(&"#333".into());
+    ctx.stroke_rect(ox, oy, bw * scale, bh * scale);
+    // One fill style change per species rather than per particle
+    let max_species = species.iter().copied().max().unwrap_or(0);
+    for s in 0..=max_species {
+        let [r, g, b] = species_rgb(s);
+        ctx.set_fill_style(&format!("rgb({}, {}, {})", r, g, b).into());
+        for (&(x, y), _) in positions.iter().zip(species).filter(|(_, &sp)| sp == s) {
+            ctx.fill_rect(ox + x * scale - 1.5, oy + y * scale - 1.5, 3.0, 3.0);
+        }
+    }
+}
 fn draw_heatmap(ctx: &CanvasRenderingContext2d, w: f64, h: f64, gw: u32, gh: u32, values: &Vec<f64>) {
     if gw == 0 || gh == 0 { return; }
     
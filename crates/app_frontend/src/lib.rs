@@ -1,16 +1,28 @@
 use leptos::*;
-use sim_engine::gol::GameOfLife;
-use sim_engine::ode::ODESim;
-use sim_engine::gray_scott::GrayScott;
+use sim_engine::elementary::CaRule;
+use sim_engine::rd_models;
+use sim_engine::archive::Elite;
+use sim_engine::registry;
+use sim_engine::soup::SoupSearch;
+use sim_engine::{ParamValue, Simulation};
+use std::rc::Rc;
 // UPDATED IMPORTS: Added create_brain and BrainType
-use inference_engine::{DiscoveryEvent, create_brain, BrainType};
+use inference_engine::{DiscoveryEvent, create_brain_for, BrainType, MockExperimenter, BRAINS};
 
 mod components;
 pub mod session;
+pub mod storage;
 
+use crate::components::archive_grid::ArchiveGrid;
+use crate::components::brain_picker::BrainPicker;
 use crate::components::discovery_feed::DiscoveryFeed;
 use crate::components::simulation_viewport::SimulationViewport;
 use crate::components::control_bar::ControlBar;
+use crate::components::creature_picker::CreaturePicker;
+use crate::components::rule_picker::RulePicker;
+use crate::components::preset_gallery::PresetGallery;
+use crate::components::rd_model_picker::RdModelPicker;
+use crate::components::reward_picker::RewardPicker;
 use crate::session::Session;
 
 #[component]
@@ -24,58 +36,159 @@ pub fn App() -> impl IntoView {
     let speed = create_rw_signal(10.0);       
     let tick_count = create_rw_signal(0);
 
-    // Helper to store "which" sim is loaded so we can reset it
+    // Registry key of the loaded sim, so we can reset it
     let (current_sim_type, set_sim_type) = create_signal("none");
+    // Brain driving it: the registry default until the brain picker says otherwise
+    let (current_brain, set_brain) = create_signal("mock");
+    // Sidebar picks (preset, creature, rule...) to replay after a reset
+    let applied_params: RwSignal<Vec<(String, ParamValue)>> = create_rw_signal(Vec::new());
+    // Elites from every session, kept across reloads
+    let archive = create_rw_signal(storage::load_archive());
 
     // --- Loaders ---
-    let load_gol = {
-        let active_session = active_session.clone();
-        move |_| {
-            let sim = Box::new(GameOfLife::new());
-            // UPDATED: Now uses the Gardener Brain
-            let agent = create_brain(BrainType::Gardener); 
-            active_session.set(Some(Session::new(sim, agent)));
-            set_sim_type.set("gol");
-            tick_count.set(0);
-            is_playing.set(true); // Auto-play on load
+    // Soup search is the one sim built here: its census lives in localStorage
+    // and new finds go to the feed
+    let build_soup = move || -> Box<dyn Simulation> {
+        let sim = SoupSearch::new()
+            .with_census(storage::load_census())
+            .on_soup_complete(move |result, census| {
+                storage::save_census(census);
+                for key in &result.new_objects {
+                    let name = census.objects.get(key).and_then(|e| e.name.clone());
+                    history.update(|h| {
+                        h.push(DiscoveryEvent::Insight {
+                            topic: "Soup Search".into(),
+                            content: format!(
+                                "New object {} ({}) in soup {:#018x}, after {} soups",
+                                name.as_deref().unwrap_or("unnamed"), key, result.soup_seed, census.soups
+                            ),
+                        });
+                        if h.len() > 50 { h.remove(0); }
+                    });
+                }
+            });
+        Box::new(sim)
+    };
+
+    // Everything else comes straight from the registry, driven by `brain`
+    let start = move |key: &'static str, brain: &'static str| {
+        let Some(info) = registry::find(key) else { return };
+        let sim = if info.key == "soup" { build_soup() } else { info.build() };
+        let kind = BrainType::from_name(brain).unwrap_or(BrainType::Mock);
+        // A brain that can't run here is reported and stood in for by the mock
+        let agent = create_brain_for(kind, info.key).unwrap_or_else(|error| {
+            history.update(|h| h.push(DiscoveryEvent::Insight { topic: "Brain".into(), content: error }));
+            Box::new(MockExperimenter::new())
+        });
+        // Brains that run copies get the registry build (soup's census hooks stay with the live run)
+        let session = Session::new(sim, agent).with_archive(info.key, brain).with_build(Rc::new(move || info.build()));
+        active_session.set(Some(session));
+        applied_params.set(Vec::new());
+        set_sim_type.set(info.key);
+        set_brain.set(brain);
+        tick_count.set(0);
+        is_playing.set(true); // Auto-play on load
+    };
+
+    // A fresh pick from the menu gets the registry's default brain
+    let load = move |key: &'static str| {
+        if let Some(info) = registry::find(key) {
+            start(info.key, info.default_brain);
         }
     };
 
-    let load_lorenz = {
-        let active_session = active_session.clone();
-        move |_| {
-            let sim = Box::new(ODESim::new());
-            // UPDATED: Now uses the Q-Learning Brain
-            let agent = create_brain(BrainType::QLearner); 
-            active_session.set(Some(Session::new(sim, agent)));
-            set_sim_type.set("ode");
-            tick_count.set(0);
-            is_playing.set(true);
+    // Sidebar pickers change the running sim through the param API
+    let apply_param = move |key: &str, value: ParamValue| {
+        active_session.update(|session| {
+            if let Some(session) = session.as_mut() {
+                session.set_param(key, value.clone());
+            }
+        });
+        applied_params.update(|params| {
+            params.retain(|(k, _)| k != key);
+            params.push((key.to_string(), value));
+        });
+    };
+
+    // Gallery click: switch the running Gray-Scott in place
+    let on_preset = move |name: &'static str| {
+        if current_sim_type.get_untracked() != "gray-scott" {
+            load("gray-scott");
         }
+        apply_param("preset", ParamValue::String(name.into()));
     };
-    
-    let load_gs = {
-        let active_session = active_session.clone();
-        move |_| {
-            let sim = Box::new(GrayScott::init(100, 100));
-            // Use the Gardener agent (it likes to water/feed things)
-            let agent = create_brain(BrainType::Gardener); 
-            active_session.set(Some(Session::new(sim, agent)));
-            set_sim_type.set("gs");
-            tick_count.set(0);
-            is_playing.set(true);
+
+    let on_rd = move |(key, preset): (&'static str, &'static str)| {
+        load(key);
+        apply_param("preset", ParamValue::String(preset.into()));
+    };
+
+    let on_creature = move |name: &'static str| {
+        apply_param("creature", ParamValue::String(name.into()));
+        tick_count.set(0);
+    };
+
+    let on_rule = move |rule: CaRule| match rule {
+        CaRule::Elementary(r) => apply_param("rule", ParamValue::Int(r as i64)),
+        CaRule::Totalistic { colors, code } => {
+            apply_param("colors", ParamValue::Int(colors as i64));
+            apply_param("code", ParamValue::Int(code as i64));
         }
     };
-    
+
+    // Reward specs ride the param API too, so a reset keeps them;
+    // a spec that doesn't parse is reported in the feed and ignored
+    let on_reward = move |spec: String| {
+        apply_param("reward", ParamValue::String(spec));
+        let error = active_session.with_untracked(|s| s.as_ref().and_then(|s| s.sim.error.clone()));
+        if let Some(error) = error {
+            history.update(|h| {
+                h.push(DiscoveryEvent::Insight { topic: "Reward".into(), content: error });
+                if h.len() > 50 { h.remove(0); }
+            });
+        }
+    };
+
     // --- Handlers ---
-    let on_reset = move |_| {
-        match current_sim_type.get() {
-            "gol" => load_gol(()),
-            "ode" => load_lorenz(()),
-            _ => {}
+    // Restart the running sim with `brain`, replaying the sidebar picks
+    let restart = move |brain: &'static str| {
+        let replay = applied_params.get_untracked();
+        start(current_sim_type.get_untracked(), brain);
+        for (key, value) in replay {
+            apply_param(&key, value);
         }
     };
 
+    let on_reset = move |_| restart(current_brain.get_untracked());
+
+    // Archive: keep the fittest snapshot per behaviour cell; a click rebuilds it
+    let on_elite = move |elite: Elite| {
+        archive.update(|archive| {
+            if archive.insert(elite) {
+                storage::save_archive(archive);
+            }
+        });
+    };
+
+    // The elite comes back under the brain that found it (the default if that's unknown)
+    let on_load_elite = move |elite: Elite| {
+        let Some(info) = registry::find(&elite.sim) else { return };
+        let content = if elite.view_only {
+            format!("This {} elite was shaped by cell edits or kicks its params can't replay; view only", info.name)
+        } else {
+            let brain = BRAINS.iter().find(|(name, _, _)| *name == elite.source).map_or(info.default_brain, |(name, _, _)| *name);
+            start(info.key, brain);
+            for (key, value) in elite.params {
+                apply_param(&key, value);
+            }
+            format!("Reloaded a {} elite found by {} (fitness {:.3})", info.name, elite.source, elite.fitness)
+        };
+        history.update(|h| {
+            h.push(DiscoveryEvent::Insight { topic: "Archive".into(), content });
+            if h.len() > 50 { h.remove(0); }
+        });
+    };
+
     // We use a simple counter signal to trigger single steps in the Viewport
     let (step_trigger, set_step_trigger) = create_signal(0); 
     let on_step = move |_| set_step_trigger.update(|n| *n += 1);
@@ -90,9 +203,17 @@ pub fn App() -> impl IntoView {
                     <div style="padding: 1rem; border-bottom: 1px solid #444;">
                         <h1 style="margin: 0 0 1rem 0; font-size: 1.5rem; color: #00aaff;">"Aletheia-Phenom"</h1>
                         <div>
-                            <button on:click=load_gol>"Game of Life"</button>
-                            <button on:click=load_lorenz>"Lorenz"</button>
-                            <button on:click=load_gs style="background-color: #8800ff;">"Gray-Scott"</button>
+                            {registry::categories().into_iter().map(|category| {
+                                let style = category_colour(category).map(|c| format!("background-color: {};", c)).unwrap_or_default();
+                                registry::in_category(category).map(|info| {
+                                    let key = info.key;
+                                    view! {
+                                        <button on:click=move |_| load(key) title=info.description style=style.clone()>
+                                            {info.name}
+                                        </button>
+                                    }
+                                }).collect_view()
+                            }).collect_view()}
                         </div>
                     </div>
 
@@ -110,6 +231,7 @@ pub fn App() -> impl IntoView {
                                     if h.len() > 50 { h.remove(0); }
                                 });
                             }
+                            on_elite=on_elite
                         />
                     </div>
 
@@ -118,7 +240,7 @@ pub fn App() -> impl IntoView {
                         is_playing=is_playing.read_only()
                         set_playing=is_playing.write_only()
                         speed=speed.read_only()
-                        set_speed=set_speed.write_only()
+                        set_speed=speed.write_only()
                         on_reset=on_reset
                         on_step=on_step
                         tick_count=tick_count.read_only()
@@ -127,9 +249,36 @@ pub fn App() -> impl IntoView {
 
                 // --- RIGHT COLUMN (Sidebar) ---
                 <div class="sidebar" style="flex: 1; background-color: #2a2a2a; overflow-y: auto; border-left: 1px solid #444;">
+                    <Show when=move || current_sim_type.get() == "gray-scott">
+                        <PresetGallery on_select=on_preset />
+                    </Show>
+                    <Show when=move || rd_models::find(current_sim_type.get()).is_some()>
+                        <RdModelPicker on_select=on_rd />
+                    </Show>
+                    <Show when=move || current_sim_type.get() == "lenia">
+                        <CreaturePicker on_select=on_creature />
+                    </Show>
+                    <Show when=move || current_sim_type.get() == "eca">
+                        <RulePicker on_select=on_rule />
+                    </Show>
+                    <Show when=move || current_sim_type.get() != "none">
+                        <BrainPicker brain=current_brain on_select=restart />
+                        <RewardPicker on_select=on_reward />
+                        <ArchiveGrid archive=archive.read_only() sim=current_sim_type on_select=on_load_elite />
+                    </Show>
                     <DiscoveryFeed history=history.read_only() />
                 </div>
             </div>
         </main>
     }
 }
+
+/// Button colour for a registry category (None keeps the default).
+fn category_colour(category: &str) -> Option<&'static str> {
+    match category {
+        "Reaction-diffusion" => Some("#8800ff"),
+        "Continuous CA" => Some("#cc6600"),
+        "Particles" => Some("#aa2266"),
+        _ => None,
+    }
+}
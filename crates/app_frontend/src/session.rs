@@ -1,16 +1,104 @@
-use sim_engine::{Simulation, Experimentable, SimState, Action, Observation};
-use inference_engine::{Experimenter, AgentAction, AgentObservation, DiscoveryEvent};
+use sim_engine::archive::{self, Elite, PeriodDetector, MAX_PERIOD};
+use sim_engine::evolution::Setup;
+use sim_engine::registry;
+use sim_engine::reward::Rewarded;
+use sim_engine::{Simulation, Experimentable, SimState, Action, ActionError, ActionSpace, ParamValue};
+use inference_engine::{Experimenter, AgentObservation, DiscoveryEvent};
+use std::rc::Rc;
+
+/// Refused actions kept in `Session::rejected`
+const MAX_REJECTED: usize = 50;
+
+/// Ticks between archive snapshots
+const ARCHIVE_EVERY: u64 = 200;
 
 /// A Session holds the World (Simulation) and the Scientist (Experimenter).
 pub struct Session {
-    pub sim: Box<dyn Simulation>,
+    /// Wrapped so the reward can be swapped (`set_param("reward", ...)`)
+    pub sim: Rewarded,
     pub agent: Box<dyn Experimenter>,
     pub step_count: u64,
+    /// Recent actions the simulation refused: (step, action, why)
+    pub rejected: Vec<(u64, Action, ActionError)>,
+    /// What the agent was last told it may do
+    action_space: ActionSpace,
+    /// Registry key and brain name, once `with_archive` turns snapshots on
+    archive_as: Option<(&'static str, String)>,
+    /// How the sim was built, once `with_build` shares the setup with the agent
+    build: Option<Rc<dyn Fn() -> Box<dyn Simulation>>>,
+    /// Everything set since the build (sidebar picks and the agent's SetParams), to reproduce it
+    params: Vec<(String, ParamValue)>,
+    /// False once a FlipCell or Perturb lands: `params` no longer rebuild this run,
+    /// so its snapshots are archived as view only
+    reproducible: bool,
+    reward_sum: f64,
+    reward_count: u64,
+    periods: PeriodDetector,
+    period: Option<usize>,
+    /// Snapshots waiting for `take_elites`
+    elites: Vec<Elite>,
 }
 
 impl Session {
-    pub fn new(sim: Box<dyn Simulation>, agent: Box<dyn Experimenter>) -> Self {
-        Self { sim, agent, step_count: 0 }
+    pub fn new(sim: Box<dyn Simulation>, mut agent: Box<dyn Experimenter>) -> Self {
+        let mut sim = Rewarded::wrap(sim);
+        // Let the agent size itself before the first observation arrives
+        let mut action_space = ActionSpace::new();
+        if let Some(exp_sim) = sim.as_experimentable() {
+            agent.set_observation_space(&(&exp_sim.observation_space()).into());
+            action_space = exp_sim.action_space();
+            agent.set_action_space(&action_space);
+        }
+        Self {
+            sim,
+            agent,
+            step_count: 0,
+            rejected: Vec::new(),
+            action_space,
+            archive_as: None,
+            build: None,
+            params: Vec::new(),
+            reproducible: true,
+            reward_sum: 0.0,
+            reward_count: 0,
+            periods: PeriodDetector::default(),
+            period: None,
+            elites: Vec::new(),
+        }
+    }
+
+    /// Snapshot the run for the elite archive every `ARCHIVE_EVERY` ticks,
+    /// credited to `brain`. `sim_key` is the registry key it was built from.
+    pub fn with_archive(mut self, sim_key: &'static str, brain: &str) -> Self {
+        self.archive_as = Some((sim_key, brain.to_string()));
+        self
+    }
+
+    /// Tell the agent how the sim is built (and, on every `set_param`, what
+    /// was set), so brains that run copies of it run the same world.
+    pub fn with_build(mut self, build: Rc<dyn Fn() -> Box<dyn Simulation>>) -> Self {
+        self.build = Some(build);
+        self.share_setup();
+        self
+    }
+
+    /// `set_param` on the sim, remembered so archive snapshots can be reloaded.
+    pub fn set_param(&mut self, key: &str, value: ParamValue) {
+        self.sim.set_param(key, value.clone());
+        self.params.retain(|(k, _)| k != key);
+        self.params.push((key.to_string(), value));
+        self.share_setup();
+    }
+
+    fn share_setup(&mut self) {
+        if let Some(build) = &self.build {
+            self.agent.set_setup(&Setup { build: build.clone(), params: self.params.clone() });
+        }
+    }
+
+    /// Snapshots taken since the last call, and the agent's own finds
+    pub fn take_elites(&mut self) -> Vec<Elite> {
+        std::mem::take(&mut self.elites)
     }
 
     /// The main loop: Observe -> Think -> Act -> Step
@@ -19,45 +107,90 @@ impl Session {
         let mut discovery = None;
 
         if let Some(exp_sim) = self.sim.as_experimentable() {
-            let obs = exp_sim.observe();
-            let agent_obs = self.map_obs(obs);
+            let agent_obs: AgentObservation = exp_sim.observe().into();
             
             // --- THE FEEDBACK LOOP ---
             let reward = exp_sim.reward(); // (The "Order" signal)
+            self.reward_sum += reward;
+            self.reward_count += 1;
 
             // The Scientist thinks... (Applying the Novelty Multiplier internally)
-            let (agent_action, event) = self.agent.act(&agent_obs, reward, self.step_count);
+            let (sim_action, event) = self.agent.act(&agent_obs, reward, self.step_count);
             discovery = event;
 
-            let sim_action = self.map_act(agent_action);
-            exp_sim.apply_action(sim_action);
+            let applied = exp_sim.try_apply(sim_action.clone());
+            if applied.is_ok() {
+                match (&sim_action, self.archive_as.as_ref()) {
+                    (Action::SetParam { name, value }, Some((key, _))) => {
+                        let schema = registry::find(key).map_or(&[][..], |info| info.schema);
+                        let value = sim_engine::param_value(schema, name, *value);
+                        self.params.retain(|(k, _)| k != name);
+                        self.params.push((name.clone(), value));
+                    }
+                    (Action::FlipCell { .. } | Action::Perturb { .. }, _) => self.reproducible = false,
+                    _ => {}
+                }
+            }
+            if let Err(err) = applied {
+                // Tell the feed the first time each kind of refusal shows up
+                let repeat = self.rejected.last().is_some_and(|(_, _, last)| last.to_string() == err.to_string());
+                if !repeat && discovery.is_none() {
+                    discovery = Some(DiscoveryEvent::Insight { topic: "Action refused".into(), content: err.to_string() });
+                }
+                self.rejected.push((self.step_count, sim_action, err));
+                if self.rejected.len() > MAX_REJECTED { self.rejected.remove(0); }
+            }
+
+            // Species counts, rules etc. change what's allowed; keep the agent current
+            let space = exp_sim.action_space();
+            if space != self.action_space {
+                self.agent.set_action_space(&space);
+                self.action_space = space;
+            }
         }
 
         self.sim.step();
         self.step_count += 1;
+        if self.archive_as.is_some() {
+            self.elites.extend(self.agent.take_elites());
+            self.record();
+        }
 
         discovery
     }
 
-    pub fn get_state(&self) -> SimState {
-        self.sim.get_state()
-    }
-
-    // --- Mapping Helpers (The Bridge) ---
-    fn map_obs(&self, obs: Observation) -> AgentObservation {
-        match obs {
-            Observation::GridSummary { width, height, .. } => AgentObservation::GridSummary { width, height },
-            Observation::StateVec(v) => AgentObservation::StateVec(v),
-            _ => AgentObservation::None,
+    /// Watch for a period over the last ticks before each snapshot, then take it.
+    fn record(&mut self) {
+        let phase = self.step_count % ARCHIVE_EVERY;
+        if phase < ARCHIVE_EVERY - MAX_PERIOD as u64 && phase != 0 {
+            return;
         }
+        let state = self.sim.get_state();
+        if let Some(period) = self.periods.push(&state) {
+            self.period = Some(self.period.map_or(period, |p| p.min(period)));
+        }
+        if phase != 0 {
+            return;
+        }
+        if let (Some((key, brain)), Some(descriptor)) = (&self.archive_as, archive::descriptor(&state, self.period)) {
+            self.elites.push(Elite {
+                sim: key.to_string(),
+                params: self.params.clone(),
+                fitness: self.reward_sum / self.reward_count.max(1) as f64,
+                descriptor,
+                source: brain.clone(),
+                step: self.step_count,
+                thumbnail: archive::thumbnail(&state),
+                view_only: !self.reproducible,
+            });
+        }
+        self.reward_sum = 0.0;
+        self.reward_count = 0;
+        self.periods = PeriodDetector::default();
+        self.period = None;
     }
 
-    fn map_act(&self, act: AgentAction) -> Action {
-        match act {
-            AgentAction::FlipCell { r, c } => Action::FlipCell { r, c },
-            AgentAction::Perturb { which, delta } => Action::Perturb { which, delta },
-            AgentAction::SetParam { name, val } => Action::SetParam { name, value: val },
-            AgentAction::Noop => Action::Noop,
-        }
+    pub fn get_state(&self) -> SimState {
+        self.sim.get_state()
     }
 }
@@ -0,0 +1,44 @@
+//! Browser persistence (localStorage) for things that should survive a reload.
+
+use sim_engine::archive::EliteArchive;
+use sim_engine::soup::SoupCensus;
+
+const CENSUS_KEY: &str = "aletheia.soup_census";
+const ARCHIVE_KEY: &str = "aletheia.elite_archive";
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Previously saved soup census, or an empty one on first run / parse failure.
+pub fn load_census() -> SoupCensus {
+    local_storage()
+        .and_then(|storage| storage.get_item(CENSUS_KEY).ok().flatten())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_census(census: &SoupCensus) {
+    if let (Some(storage), Ok(text)) = (local_storage(), serde_json::to_string(census)) {
+        let _ = storage.set_item(CENSUS_KEY, &text);
+    }
+}
+
+/// Previously saved elite archive, or an empty one.
+pub fn load_archive() -> EliteArchive {
+    local_storage()
+        .and_then(|storage| storage.get_item(ARCHIVE_KEY).ok().flatten())
+        .and_then(|text| serde_json::from_str::<EliteArchive>(&text).ok())
+        // Stale or hand-edited storage can hold any grid size; zero bins has no cells
+        .map(|mut archive| {
+            archive.bins = archive.bins.max(1);
+            archive
+        })
+        .unwrap_or_default()
+}
+
+pub fn save_archive(archive: &EliteArchive) {
+    if let (Some(storage), Ok(text)) = (local_storage(), serde_json::to_string(archive)) {
+        let _ = storage.set_item(ARCHIVE_KEY, &text);
+    }
+}
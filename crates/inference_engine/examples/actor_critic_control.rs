@@ -0,0 +1,63 @@
+//! Actor-critic on the same toy task as `dqn_control`, with a continuous push.
+//!
+//! ```text
+//! cargo run --release --example actor_critic_control
+//! ```
+//!
+//! State (x, v); the action is Noop or a push anywhere in [-1, 1]; reward
+//! 1 - |x|. Prints the mean reward per block of episodes alongside the last
+//! update's diagnostics.
+
+use inference_engine::{ActorCriticAgent, AgentObservation, Experimenter};
+use sim_engine::{Action, ActionSpace};
+
+const EPISODES: usize = 200;
+const STEPS: usize = 100;
+const BLOCK: usize = 20;
+
+fn main() {
+    let mut agent = ActorCriticAgent::new();
+    agent.set_action_space(&ActionSpace::new().with_perturb("push", 1, -1.0, 1.0));
+
+    let mut seed = 0x2545_F491_4F6C_DD1Du64;
+    let mut uniform = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed >> 11) as f64 / (1u64 << 53) as f64
+    };
+
+    let mut block = 0.0;
+    let mut step = 0;
+    for episode in 1..=EPISODES {
+        let (mut x, mut v) = (4.0 * uniform() - 2.0, 0.0);
+        let mut reward = 0.0;
+        let mut total = 0.0;
+        for _ in 0..STEPS {
+            let obs = AgentObservation::Vector(vec![x, v]);
+            let (action, _) = agent.act(&obs, reward, step);
+            let push = match action {
+                Action::Perturb { delta, .. } => delta,
+                _ => 0.0,
+            };
+            v = 0.9 * v + 0.1 * push;
+            x = (x + v).clamp(-3.0, 3.0);
+            reward = 1.0 - x.abs();
+            total += reward;
+            step += 1;
+        }
+        block += total / STEPS as f64;
+        if episode % BLOCK == 0 {
+            let (entropy, std) = agent.stats().map_or((0.0, 0.0), |s| (s.entropy, s.mean_std));
+            println!(
+                "episodes {:>3}-{:<3}  mean reward {:>6.3}  entropy {:.2}  σ {:.2}",
+                episode + 1 - BLOCK,
+                episode,
+                block / BLOCK as f64,
+                entropy,
+                std
+            );
+            block = 0.0;
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! DQN on a toy control task: push a point on a line back to the origin.
+//!
+//! ```text
+//! cargo run --release --example dqn_control
+//! ```
+//!
+//! State (x, v); actions Noop or a push of ±0.5; reward 1 - |x|. Prints the
+//! mean reward per block of episodes, which should climb from around 0 (a
+//! drifting point) towards 1 (parked at the origin).
+
+use inference_engine::{AgentObservation, DqnAgent, Experimenter};
+use sim_engine::{Action, ActionSpace};
+
+const EPISODES: usize = 200;
+const STEPS: usize = 100;
+const BLOCK: usize = 20;
+
+fn main() {
+    let mut agent = DqnAgent::new();
+    agent.set_action_space(&ActionSpace::new().with_perturb("push", 1, -1.0, 1.0));
+
+    let mut seed = 0x2545_F491_4F6C_DD1Du64;
+    let mut uniform = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed >> 11) as f64 / (1u64 << 53) as f64
+    };
+
+    let mut block = 0.0;
+    let mut step = 0;
+    for episode in 1..=EPISODES {
+        let (mut x, mut v) = (4.0 * uniform() - 2.0, 0.0);
+        let mut reward = 0.0;
+        let mut total = 0.0;
+        for _ in 0..STEPS {
+            let obs = AgentObservation::Vector(vec![x, v]);
+            let (action, _) = agent.act(&obs, reward, step);
+            let push = match action {
+                Action::Perturb { delta, .. } => delta,
+                _ => 0.0,
+            };
+            v = 0.9 * v + 0.1 * push;
+            x = (x + v).clamp(-3.0, 3.0);
+            reward = 1.0 - x.abs();
+            total += reward;
+            step += 1;
+        }
+        block += total / STEPS as f64;
+        if episode % BLOCK == 0 {
+            println!("episodes {:>3}-{:<3}  mean reward {:>6.3}  ε {:.2}", episode + 1 - BLOCK, episode, block / BLOCK as f64, agent.epsilon());
+            block = 0.0;
+        }
+    }
+}
@@ -0,0 +1,343 @@
+//! State abstractions for the tabular Q-learner: each turns a continuous
+//! feature vector into one or more compact `u64` cell keys.
+//!
+//! - `Foveated`: the original logarithmic buckets, fine near zero and coarse
+//!   far away. One key.
+//! - `TileCoding`: several grids over standardised features, each shifted by
+//!   a fraction of a tile, so nearby states share most of their keys and
+//!   learning generalises. One key per tiling.
+//! - `KdTree`: starts as a single cell and splits a cell in two (at the mean
+//!   of its widest feature) once the values seen there vary a lot. One key.
+//!
+//! With several keys the Q-value is the mean of the keys' entries. Tile
+//! coding is the Q-learner's default; `AbstractionKind` picks one by name.
+
+use crate::nn::Normalizer;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+pub trait StateAbstraction {
+    /// Keys of the cells `state` falls in (never empty)
+    fn keys(&mut self, state: &[f64]) -> Vec<u64>;
+
+    /// False while the cells are still moving (scales being learned); the
+    /// learner keeps its table empty until then, so no row outlives its cell.
+    fn ready(&self) -> bool {
+        true
+    }
+
+    /// The TD target just computed for `state`. Returns `(old, new)` pairs when
+    /// a cell was refined, so the table can seed `new` with `old`'s values.
+    fn observe(&mut self, _state: &[f64], _target: f64) -> Vec<(u64, u64)> {
+        Vec::new()
+    }
+
+    /// Short description for the discovery feed
+    fn describe(&self) -> String;
+}
+
+/// Which abstraction a Q-learner uses, by name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AbstractionKind {
+    Foveated,
+    #[default]
+    TileCoding,
+    KdTree,
+}
+
+impl AbstractionKind {
+    /// "foveated", "tiles" or "kdtree"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "foveated" => Some(AbstractionKind::Foveated),
+            "tiles" | "tile-coding" => Some(AbstractionKind::TileCoding),
+            "kdtree" | "kd-tree" => Some(AbstractionKind::KdTree),
+            _ => None,
+        }
+    }
+
+    /// A fresh abstraction of this kind, with default settings
+    pub fn build(self) -> Box<dyn StateAbstraction> {
+        match self {
+            AbstractionKind::Foveated => Box::new(Foveated),
+            AbstractionKind::TileCoding => Box::new(TileCoding::default()),
+            AbstractionKind::KdTree => Box::new(KdTree::default()),
+        }
+    }
+}
+
+fn hash_cell(tiling: usize, coords: impl Iterator<Item = i64>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    tiling.hash(&mut hasher);
+    coords.for_each(|c| c.hash(&mut hasher));
+    hasher.finish()
+}
+
+/// log(1 + |x|) buckets, four per e-fold.
+#[derive(Clone, Debug, Default)]
+pub struct Foveated;
+
+impl StateAbstraction for Foveated {
+    fn keys(&mut self, state: &[f64]) -> Vec<u64> {
+        let foveate = |v: f64| (v.signum() * (v.abs() + 1.0).ln() * 4.0) as i64;
+        vec![hash_cell(0, state.iter().map(|&v| foveate(v)))]
+    }
+
+    fn describe(&self) -> String {
+        "foveated buckets".into()
+    }
+}
+
+/// `tilings` offset grids of `width` standard deviations per tile.
+#[derive(Clone, Debug)]
+pub struct TileCoding {
+    pub tilings: usize,
+    pub width: f64,
+    /// Observations used to learn the feature scales; after that tiles stay
+    /// put, and only then does the learner start filling its table
+    pub warmup: usize,
+    norm: Normalizer,
+    seen: usize,
+}
+
+impl TileCoding {
+    pub fn new(tilings: usize, width: f64) -> Self {
+        Self { tilings: tilings.max(1), width: width.max(1e-6), warmup: 1000, norm: Normalizer::default(), seen: 0 }
+    }
+}
+
+impl Default for TileCoding {
+    fn default() -> Self {
+        Self::new(8, 0.5)
+    }
+}
+
+impl StateAbstraction for TileCoding {
+    fn keys(&mut self, state: &[f64]) -> Vec<u64> {
+        if self.seen < self.warmup {
+            self.norm.update(state);
+            self.seen += 1;
+        }
+        let z = self.norm.apply(state);
+        (0..self.tilings)
+            .map(|t| {
+                // Tiling t is shifted by t/n of a tile, a little differently per feature
+                let coords = z.iter().enumerate().map(|(i, v)| {
+                    let offset = ((t * (2 * i + 1)) % self.tilings) as f64 / self.tilings as f64;
+                    (v / self.width + offset).floor() as i64
+                });
+                hash_cell(t, coords)
+            })
+            .collect()
+    }
+
+    fn ready(&self) -> bool {
+        self.seen >= self.warmup
+    }
+
+    fn describe(&self) -> String {
+        format!("{} tilings of {:.2}σ", self.tilings, self.width)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct LeafStats {
+    count: f64,
+    mean: f64,
+    m2: f64,
+    /// Per-feature sums of the states seen, for choosing a split
+    sum: Vec<f64>,
+    sum_sq: Vec<f64>,
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Leaf(LeafStats),
+    Split { dim: usize, at: f64, low: usize, high: usize },
+}
+
+/// Adaptive partition: a leaf splits once it has `min_samples` targets whose
+/// variance exceeds `split_ratio` times the variance over all states.
+#[derive(Clone, Debug)]
+pub struct KdTree {
+    pub min_samples: usize,
+    pub split_ratio: f64,
+    pub max_leaves: usize,
+    nodes: Vec<Node>,
+    leaves: usize,
+    overall: LeafStats,
+}
+
+impl KdTree {
+    pub fn new(min_samples: usize, split_ratio: f64, max_leaves: usize) -> Self {
+        Self {
+            min_samples: min_samples.max(2),
+            split_ratio,
+            max_leaves: max_leaves.max(1),
+            nodes: vec![Node::Leaf(LeafStats::default())],
+            leaves: 1,
+            overall: LeafStats::default(),
+        }
+    }
+
+    pub fn leaves(&self) -> usize {
+        self.leaves
+    }
+
+    fn leaf(&self, state: &[f64]) -> usize {
+        let mut i = 0;
+        while let Node::Split { dim, at, low, high } = &self.nodes[i] {
+            i = if state.get(*dim).copied().unwrap_or(0.0) < *at { *low } else { *high };
+        }
+        i
+    }
+}
+
+impl Default for KdTree {
+    fn default() -> Self {
+        Self::new(50, 0.5, 4096)
+    }
+}
+
+impl LeafStats {
+    fn add(&mut self, state: &[f64], target: f64) {
+        if self.sum.len() != state.len() {
+            self.sum = vec![0.0; state.len()];
+            self.sum_sq = vec![0.0; state.len()];
+        }
+        self.count += 1.0;
+        let d = target - self.mean;
+        self.mean += d / self.count;
+        self.m2 += d * (target - self.mean);
+        for (i, &v) in state.iter().enumerate() {
+            self.sum[i] += v;
+            self.sum_sq[i] += v * v;
+        }
+    }
+
+    fn variance(&self) -> f64 {
+        if self.count > 1.0 { self.m2 / self.count } else { 0.0 }
+    }
+
+    /// Feature with the widest spread, and its mean
+    fn widest(&self) -> Option<(usize, f64)> {
+        (0..self.sum.len())
+            .map(|i| {
+                let mean = self.sum[i] / self.count;
+                (i, mean, self.sum_sq[i] / self.count - mean * mean)
+            })
+            .filter(|(_, _, var)| *var > 1e-12)
+            .max_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(i, mean, _)| (i, mean))
+    }
+}
+
+impl StateAbstraction for KdTree {
+    fn keys(&mut self, state: &[f64]) -> Vec<u64> {
+        vec![self.leaf(state) as u64]
+    }
+
+    fn observe(&mut self, state: &[f64], target: f64) -> Vec<(u64, u64)> {
+        self.overall.add(&[], target);
+        let i = self.leaf(state);
+        let Node::Leaf(stats) = &mut self.nodes[i] else { return Vec::new() };
+        stats.add(state, target);
+        if self.leaves >= self.max_leaves
+            || stats.count < self.min_samples as f64
+            || stats.variance() <= self.split_ratio * self.overall.variance()
+        {
+            return Vec::new();
+        }
+        let Some((dim, at)) = stats.widest() else { return Vec::new() };
+        let (low, high) = (self.nodes.len(), self.nodes.len() + 1);
+        self.nodes[i] = Node::Split { dim, at, low, high };
+        self.nodes.push(Node::Leaf(LeafStats::default()));
+        self.nodes.push(Node::Leaf(LeafStats::default()));
+        self.leaves += 1;
+        vec![(i as u64, low as u64), (i as u64, high as u64)]
+    }
+
+    fn describe(&self) -> String {
+        format!("k-d tree with {} cells", self.leaves)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QLearningAgent;
+
+    /// Spread-out x in [0, 1) with a constant second feature
+    fn state(i: usize) -> [f64; 2] {
+        [((i * 7) % 20) as f64 / 20.0, 0.0]
+    }
+
+    #[test]
+    fn kd_tree_splits_once_the_targets_vary_enough() {
+        let step = |x: f64| if x >= 0.5 { 1.0 } else { 0.0 };
+
+        // A constant target never varies, and a ratio of 1 can't be exceeded by the only leaf
+        for (split_ratio, varies) in [(0.5, false), (1.0, true)] {
+            let mut tree = KdTree::new(10, split_ratio, 8);
+            for i in 0..200 {
+                let s = state(i);
+                let t = if varies { step(s[0]) } else { 3.0 };
+                assert!(tree.observe(&s, t).is_empty(), "split at observation {}", i);
+            }
+            assert_eq!(tree.leaves(), 1);
+        }
+
+        let mut tree = KdTree::new(10, 0.5, 8);
+        for i in 0..9 {
+            let s = state(i);
+            assert!(tree.observe(&s, step(s[0])).is_empty(), "split before min_samples at {}", i);
+        }
+        let s = state(9);
+        let splits = tree.observe(&s, step(s[0]));
+        assert_eq!(splits, vec![(0, 1), (0, 2)]);
+        assert_eq!(tree.leaves(), 2);
+        // Split on x (the only feature that varies) at the mean of the ten x's, 0.375
+        assert_eq!(tree.keys(&[0.3, 0.0]), vec![1]);
+        assert_eq!(tree.keys(&[0.4, 0.0]), vec![2]);
+    }
+
+    #[test]
+    fn refined_cells_inherit_their_parents_row() {
+        let mut agent = QLearningAgent::with_abstraction(Box::new(KdTree::new(10, 0.5, 8)));
+        agent.q_table.insert(0, vec![1.0, 2.0, 3.0]);
+        agent.q_table.insert(7, vec![9.0]);
+        agent.seed_refined(&[(0, 1), (0, 2)]);
+        assert_eq!(agent.q_table.get(&1), Some(&vec![1.0, 2.0, 3.0]));
+        assert_eq!(agent.q_table.get(&2), Some(&vec![1.0, 2.0, 3.0]));
+        assert!(!agent.q_table.contains_key(&0), "the parent's row outlived its cell");
+        assert_eq!(agent.q_table.get(&7), Some(&vec![9.0]));
+    }
+
+    #[test]
+    fn tile_keys_settle_after_warmup_and_generalise() {
+        let mut tiles = TileCoding::new(8, 0.5);
+        tiles.warmup = 100;
+        let wander = |i: usize| [(i as f64).sin(), 3.0 * (0.7 * i as f64).cos()];
+        for i in 0..tiles.warmup {
+            assert!(!tiles.ready());
+            tiles.keys(&wander(i));
+        }
+        assert!(tiles.ready());
+
+        let probe = [0.2, -0.4];
+        let settled = tiles.keys(&probe);
+        assert_eq!(settled.len(), 8);
+        // Far-off states after warmup don't move the tiles
+        for i in 0..500 {
+            tiles.keys(&[100.0 * wander(i)[0], -50.0]);
+        }
+        assert_eq!(tiles.keys(&probe), settled);
+
+        let shared = |a: &[u64], b: &[u64]| a.iter().zip(b).filter(|(x, y)| x == y).count();
+        // A nudge of well under a tile crosses at most one tiling's boundary per feature
+        let near = tiles.keys(&[0.205, -0.395]);
+        assert!(shared(&settled, &near) >= 6, "only {} of 8 keys shared", shared(&settled, &near));
+        let far = tiles.keys(&[20.0, 40.0]);
+        assert_eq!(shared(&settled, &far), 0);
+    }
+}
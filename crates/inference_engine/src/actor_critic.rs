@@ -0,0 +1,448 @@
+//! Actor-critic brain with continuous actions, trained PPO-style.
+//!
+//! Each tick the policy picks one knob from `ActionSpace::dimensions`
+//! (a Perturb target or a bounded parameter), or Noop, then draws a value for
+//! it from a Gaussian. The value is squashed with tanh onto the knob's range,
+//! so it can nudge Lorenz by 0.3 or set Gray-Scott's `f` to 0.0371 rather than
+//! choosing between fixed ±5 kicks.
+//!
+//! Rollouts of `rollout` steps are scored with GAE, then the actor (clipped
+//! surrogate plus an entropy bonus) and the critic (squared error) get a few
+//! epochs of minibatch Adam. A summary of every fourth update goes to the feed.
+
+use crate::nn::{Adam, Mlp, Normalizer};
+use crate::{random, AgentObservation, AgentObservationSpace, DiscoveryEvent, Experimenter};
+use sim_engine::action::Dimension;
+use sim_engine::{Action, ActionSpace};
+
+#[derive(Clone, Debug)]
+pub struct ActorCriticConfig {
+    /// Width of each of the two hidden layers (actor and critic alike)
+    pub hidden: usize,
+    pub learning_rate: f64,
+    pub gamma: f64,
+    /// GAE λ
+    pub lambda: f64,
+    /// Steps collected per update
+    pub rollout: usize,
+    pub epochs: usize,
+    pub minibatch: usize,
+    /// PPO ratio clip ε
+    pub clip: f64,
+    /// Weight of the entropy bonus
+    pub entropy: f64,
+    /// Starting log standard deviation of the value Gaussians (in pre-tanh units)
+    pub init_log_std: f64,
+}
+
+impl Default for ActorCriticConfig {
+    fn default() -> Self {
+        Self {
+            hidden: 64,
+            learning_rate: 3e-4,
+            gamma: 0.99,
+            lambda: 0.95,
+            rollout: 128,
+            epochs: 4,
+            minibatch: 32,
+            clip: 0.2,
+            entropy: 0.01,
+            init_log_std: -0.5,
+        }
+    }
+}
+
+/// What one update looked like (averaged over its minibatches).
+#[derive(Clone, Debug, Default)]
+pub struct UpdateStats {
+    pub update: u64,
+    pub mean_reward: f64,
+    pub policy_loss: f64,
+    pub value_loss: f64,
+    /// Entropy of the knob choice
+    pub entropy: f64,
+    /// Mean of (old log-prob - new log-prob); large values mean the policy moved a lot
+    pub approx_kl: f64,
+    /// Mean standard deviation of the value Gaussians
+    pub mean_std: f64,
+    /// Most-used knob in the rollout and its share of the steps
+    pub favourite: (String, f64),
+}
+
+struct Sample {
+    /// Normalised features, as the networks saw them
+    x: Vec<f64>,
+    /// Index into the dimensions, or `dims.len()` for Noop
+    knob: usize,
+    /// Pre-tanh value drawn for the knob
+    u: f64,
+    logp: f64,
+    value: f64,
+    reward: f64,
+}
+
+pub struct ActorCriticAgent {
+    config: ActorCriticConfig,
+    /// Outputs `dims + 1` knob logits (Noop last), then `dims` Gaussian means
+    actor: Option<Mlp>,
+    critic: Option<Mlp>,
+    log_std: Vec<f64>,
+    actor_opt: Option<Adam>,
+    critic_opt: Option<Adam>,
+    std_opt: Option<Adam>,
+    space: ActionSpace,
+    dims: Vec<Dimension>,
+    norm: Normalizer,
+    rollout: Vec<Sample>,
+    /// The last act's sample, until its reward arrives with the next act
+    pending: Option<Sample>,
+    stats: Option<UpdateStats>,
+}
+
+impl ActorCriticAgent {
+    pub fn new() -> Self {
+        Self::with_config(ActorCriticConfig::default())
+    }
+
+    pub fn with_config(config: ActorCriticConfig) -> Self {
+        // Until a simulation says otherwise: three axes, like Lorenz
+        let space = ActionSpace::new().with_perturb("axis", 3, -10.0, 10.0);
+        Self {
+            config,
+            actor: None,
+            critic: None,
+            log_std: Vec::new(),
+            actor_opt: None,
+            critic_opt: None,
+            std_opt: None,
+            dims: space.dimensions(),
+            space,
+            norm: Normalizer::default(),
+            rollout: Vec::new(),
+            pending: None,
+            stats: None,
+        }
+    }
+
+    /// Diagnostics from the most recent update
+    pub fn stats(&self) -> Option<&UpdateStats> {
+        self.stats.as_ref()
+    }
+
+    /// Fresh networks whenever the feature count or the number of knobs changes.
+    fn ensure_networks(&mut self, inputs: usize) {
+        let d = self.dims.len();
+        if self.actor.as_ref().is_some_and(|net| net.inputs() == inputs && net.outputs() == 2 * d + 1)
+            && self.critic.as_ref().is_some_and(|net| net.inputs() == inputs)
+        {
+            return;
+        }
+        let (h, lr) = (self.config.hidden, self.config.learning_rate);
+        let actor = Mlp::new(&[inputs, h, h, 2 * d + 1]);
+        let critic = Mlp::new(&[inputs, h, h, 1]);
+        self.actor_opt = Some(Adam::new(&actor, lr));
+        self.critic_opt = Some(Adam::new(&critic, lr));
+        self.std_opt = Some(Adam::with_len(d, lr));
+        self.actor = Some(actor);
+        self.critic = Some(critic);
+        self.log_std = vec![self.config.init_log_std; d];
+        self.rollout.clear();
+        self.pending = None;
+    }
+
+    /// Map a pre-tanh draw onto knob `k`'s range.
+    fn knob_value(&self, k: usize, u: f64) -> f64 {
+        let dim = &self.dims[k];
+        dim.low + 0.5 * (u.tanh() + 1.0) * (dim.high - dim.low)
+    }
+
+    fn value(&self, x: &[f64]) -> f64 {
+        self.critic.as_ref().map_or(0.0, |net| net.forward(x)[0])
+    }
+
+    /// Log-probability of (knob, u) under the actor's output `out`, and the knob probabilities.
+    fn log_prob(&self, out: &[f64], knob: usize, u: f64) -> (f64, Vec<f64>) {
+        let d = self.dims.len();
+        let probs = softmax(&out[..=d]);
+        let mut logp = probs[knob].max(1e-12).ln();
+        if knob < d {
+            logp += gaussian_log_pdf(u, out[d + 1 + knob], self.log_std[knob]);
+        }
+        (logp, probs)
+    }
+
+    fn update(&mut self, bootstrap: f64) {
+        let n = self.rollout.len();
+        let (gamma, lambda) = (self.config.gamma, self.config.lambda);
+
+        // GAE advantages and returns
+        let mut advantages = vec![0.0; n];
+        let mut next_value = bootstrap;
+        let mut running = 0.0;
+        for (s, advantage) in self.rollout.iter().zip(advantages.iter_mut()).rev() {
+            let delta = s.reward + gamma * next_value - s.value;
+            running = delta + gamma * lambda * running;
+            *advantage = running;
+            next_value = s.value;
+        }
+        let returns: Vec<f64> = advantages.iter().zip(&self.rollout).map(|(a, s)| a + s.value).collect();
+        let mean = advantages.iter().sum::<f64>() / n as f64;
+        let std = (advantages.iter().map(|a| (a - mean).powi(2)).sum::<f64>() / n as f64).sqrt().max(1e-8);
+        for a in &mut advantages {
+            *a = (*a - mean) / std;
+        }
+
+        let d = self.dims.len();
+        let (clip, beta) = (self.config.clip, self.config.entropy);
+        let mut stats = UpdateStats {
+            update: self.stats.as_ref().map_or(1, |s| s.update + 1),
+            mean_reward: self.rollout.iter().map(|s| s.reward).sum::<f64>() / n as f64,
+            ..Default::default()
+        };
+        let mut batches = 0.0;
+        let mut order: Vec<usize> = (0..n).collect();
+
+        for _ in 0..self.config.epochs {
+            shuffle(&mut order);
+            for chunk in order.chunks(self.config.minibatch.max(1)) {
+                let (Some(actor), Some(critic)) = (self.actor.as_ref(), self.critic.as_ref()) else { return };
+                let m = chunk.len() as f64;
+                let mut actor_grads = vec![0.0; actor.len()];
+                let mut critic_grads = vec![0.0; critic.len()];
+                let mut std_grads = vec![0.0; d];
+                let (mut policy_loss, mut value_loss, mut entropy, mut kl) = (0.0, 0.0, 0.0, 0.0);
+
+                for &i in chunk {
+                    let s = &self.rollout[i];
+                    let a = advantages[i];
+                    let trace = actor.trace(&s.x);
+                    let out = trace.last().unwrap();
+                    let (logp, probs) = self.log_prob(out, s.knob, s.u);
+                    let ratio = (logp - s.logp).exp();
+                    let clipped = ratio.clamp(1.0 - clip, 1.0 + clip);
+                    policy_loss -= (ratio * a).min(clipped * a) / m;
+                    kl += (s.logp - logp) / m;
+
+                    // d(loss)/d(logp): zero once the ratio is clipped in the helpful direction
+                    let active = (a >= 0.0 && ratio < 1.0 + clip) || (a < 0.0 && ratio > 1.0 - clip);
+                    let g = if active { -a * ratio / m } else { 0.0 };
+
+                    let h: f64 = -probs.iter().map(|p| p * p.max(1e-12).ln()).sum::<f64>();
+                    entropy += h / m;
+                    let mut grad_out = vec![0.0; 2 * d + 1];
+                    for (j, p) in probs.iter().enumerate() {
+                        let onehot = if j == s.knob { 1.0 } else { 0.0 };
+                        // Policy term, then the entropy bonus (-β H)
+                        grad_out[j] = g * (onehot - p) + beta * p * (p.max(1e-12).ln() + h) / m;
+                    }
+                    if s.knob < d {
+                        let (mu, sigma) = (out[d + 1 + s.knob], self.log_std[s.knob].exp());
+                        grad_out[d + 1 + s.knob] = g * (s.u - mu) / (sigma * sigma);
+                        std_grads[s.knob] += g * ((s.u - mu).powi(2) / (sigma * sigma) - 1.0);
+                    }
+                    for grad in &mut std_grads {
+                        *grad -= beta / m; // Gaussian entropy grows with log σ
+                    }
+                    actor.backward(&trace, &grad_out, &mut actor_grads);
+
+                    let ctrace = critic.trace(&s.x);
+                    let err = ctrace.last().unwrap()[0] - returns[i];
+                    value_loss += 0.5 * err * err / m;
+                    critic.backward(&ctrace, &[err / m], &mut critic_grads);
+                }
+
+                if let (Some(actor), Some(opt)) = (self.actor.as_mut(), self.actor_opt.as_mut()) {
+                    opt.step(actor, &actor_grads);
+                }
+                if let (Some(critic), Some(opt)) = (self.critic.as_mut(), self.critic_opt.as_mut()) {
+                    opt.step(critic, &critic_grads);
+                }
+                if let Some(opt) = self.std_opt.as_mut() {
+                    opt.step_params(&mut self.log_std, &std_grads);
+                }
+                for ls in &mut self.log_std {
+                    *ls = ls.clamp(-3.0, 0.5);
+                }
+                stats.policy_loss += policy_loss;
+                stats.value_loss += value_loss;
+                stats.entropy += entropy;
+                stats.approx_kl += kl;
+                batches += 1.0;
+            }
+        }
+
+        if batches > 0.0 {
+            stats.policy_loss /= batches;
+            stats.value_loss /= batches;
+            stats.entropy /= batches;
+            stats.approx_kl /= batches;
+        }
+        stats.mean_std = if d == 0 { 0.0 } else { self.log_std.iter().map(|ls| ls.exp()).sum::<f64>() / d as f64 };
+        let mut counts = vec![0usize; d + 1];
+        for s in &self.rollout {
+            counts[s.knob] += 1;
+        }
+        let (best, count) = counts.iter().enumerate().max_by_key(|(_, c)| **c).map_or((d, 0), |(i, c)| (i, *c));
+        let name = self.dims.get(best).map_or_else(|| "noop".to_string(), |dim| dim.name.clone());
+        stats.favourite = (name, count as f64 / n as f64);
+        self.stats = Some(stats);
+    }
+}
+
+impl Default for ActorCriticAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Experimenter for ActorCriticAgent {
+    fn act(&mut self, obs: &AgentObservation, reward: f64, _step: u64) -> (Action, Option<DiscoveryEvent>) {
+        let features = obs.features();
+        if features.is_empty() {
+            return (Action::Noop, None);
+        }
+        self.ensure_networks(features.len());
+        self.norm.update(&features);
+        let x = self.norm.apply(&features);
+
+        // The reward that just arrived belongs to the previous act
+        if let Some(mut sample) = self.pending.take() {
+            sample.reward = reward;
+            self.rollout.push(sample);
+        }
+
+        let mut discovery = None;
+        if self.rollout.len() >= self.config.rollout.max(2) {
+            self.update(self.value(&x));
+            self.rollout.clear();
+            if let Some(s) = self.stats.as_ref().filter(|s| s.update == 1 || s.update % 4 == 0) {
+                discovery = Some(DiscoveryEvent::Insight {
+                    topic: "Actor-Critic".into(),
+                    content: format!(
+                        "Update {}: reward {:.3}, policy loss {:.3}, value loss {:.3}, entropy {:.2}, KL {:.4}, σ {:.2}; favourite knob {} ({:.0}%)",
+                        s.update, s.mean_reward, s.policy_loss, s.value_loss, s.entropy, s.approx_kl, s.mean_std,
+                        s.favourite.0, 100.0 * s.favourite.1
+                    ),
+                });
+            }
+        }
+
+        let Some(actor) = self.actor.as_ref() else { return (Action::Noop, discovery) };
+        let out = actor.forward(&x);
+        let d = self.dims.len();
+        let probs = softmax(&out[..=d]);
+        let knob = sample_index(&probs);
+        let u = if knob < d { out[d + 1 + knob] + self.log_std[knob].exp() * gaussian() } else { 0.0 };
+        let (logp, _) = self.log_prob(&out, knob, u);
+        let value = self.value(&x);
+        self.pending = Some(Sample { x, knob, u, logp, value, reward: 0.0 });
+
+        let action = if knob < d {
+            self.space.box_action(knob, self.knob_value(knob, u)).unwrap_or(Action::Noop)
+        } else {
+            Action::Noop
+        };
+        (action, discovery)
+    }
+
+    fn set_observation_space(&mut self, space: &AgentObservationSpace) {
+        if space.feature_len() > 0 {
+            self.ensure_networks(space.feature_len());
+        }
+    }
+
+    fn set_action_space(&mut self, space: &ActionSpace) {
+        self.space = space.clone();
+        self.dims = space.dimensions();
+        // A different number of knobs needs new output layers
+        if let Some(inputs) = self.actor.as_ref().map(Mlp::inputs) {
+            self.ensure_networks(inputs);
+        }
+    }
+}
+
+fn softmax(logits: &[f64]) -> Vec<f64> {
+    let max = logits.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let exps: Vec<f64> = logits.iter().map(|l| (l - max).exp()).collect();
+    let sum: f64 = exps.iter().sum();
+    exps.into_iter().map(|e| e / sum).collect()
+}
+
+fn gaussian_log_pdf(u: f64, mu: f64, log_std: f64) -> f64 {
+    let z = (u - mu) / log_std.exp();
+    -0.5 * z * z - log_std - 0.5 * (2.0 * std::f64::consts::PI).ln()
+}
+
+/// Standard normal draw (Box-Muller)
+fn gaussian() -> f64 {
+    let u1 = random().max(1e-12);
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * random()).cos()
+}
+
+fn sample_index(probs: &[f64]) -> usize {
+    let mut r = random();
+    for (i, p) in probs.iter().enumerate() {
+        r -= p;
+        if r <= 0.0 {
+            return i;
+        }
+    }
+    probs.len() - 1
+}
+
+fn shuffle(items: &mut [usize]) {
+    for i in (1..items.len()).rev() {
+        let j = ((random() * (i + 1) as f64) as usize).min(i);
+        items.swap(i, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed_random;
+
+    /// The same task as dqn's test: push a point on a line (state x, v) back
+    /// to the origin, reward 1 - |x|, but with a push anywhere in [-1, 1].
+    /// Returns the mean reward per episode.
+    fn point_to_origin(agent: &mut ActorCriticAgent, episodes: usize, steps: usize) -> Vec<f64> {
+        agent.set_action_space(&ActionSpace::new().with_perturb("push", 1, -1.0, 1.0));
+        let mut means = Vec::with_capacity(episodes);
+        let mut step = 0;
+        for _ in 0..episodes {
+            let (mut x, mut v) = (4.0 * random() - 2.0, 0.0);
+            let (mut reward, mut total) = (0.0, 0.0);
+            for _ in 0..steps {
+                let (action, _) = agent.act(&AgentObservation::Vector(vec![x, v]), reward, step);
+                let push = match action {
+                    Action::Perturb { delta, .. } => delta,
+                    _ => 0.0,
+                };
+                v = 0.9 * v + 0.1 * push;
+                x = (x + v).clamp(-3.0, 3.0);
+                reward = 1.0 - x.abs();
+                total += reward;
+                step += 1;
+            }
+            means.push(total / steps as f64);
+        }
+        means
+    }
+
+    #[test]
+    fn learns_to_park_the_point() {
+        seed_random(7);
+        // Smaller and faster-learning than the defaults, so the test runs in debug builds
+        let mut agent = ActorCriticAgent::with_config(ActorCriticConfig {
+            hidden: 32,
+            learning_rate: 1e-3,
+            gamma: 0.95,
+            ..ActorCriticConfig::default()
+        });
+        let means = point_to_origin(&mut agent, 150, 100);
+        let mean = |rewards: &[f64]| rewards.iter().sum::<f64>() / rewards.len() as f64;
+        let (early, late) = (mean(&means[..20]), mean(&means[130..]));
+        assert!(late > early + 0.1, "mean reward went from {:.3} to {:.3}", early, late);
+        assert!(agent.stats().is_some_and(|s| s.update > 0), "no update ran");
+    }
+}
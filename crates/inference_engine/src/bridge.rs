@@ -0,0 +1,34 @@
+//! Brains take sim_engine's actions and action spaces as they are, but see
+//! observations through their own mirror; these `From` impls translate (the
+//! frontend session and the Python bindings both use them).
+
+use crate::{AgentObservation, AgentObservationSpace};
+use sim_engine::{Observation, ObservationSpace};
+
+impl From<Observation> for AgentObservation {
+    fn from(obs: Observation) -> Self {
+        match obs {
+            Observation::Scalar(v) => AgentObservation::Scalar(v),
+            Observation::Vector(v) => AgentObservation::Vector(v),
+            Observation::Tensor { shape, data } => AgentObservation::Tensor { shape, data },
+            Observation::Dict(entries) => AgentObservation::Dict(entries.into_iter().map(|(k, v)| (k, v.into())).collect()),
+            Observation::Text(text) => AgentObservation::Text(text),
+            Observation::None => AgentObservation::None,
+        }
+    }
+}
+
+impl From<&ObservationSpace> for AgentObservationSpace {
+    fn from(space: &ObservationSpace) -> Self {
+        match space {
+            ObservationSpace::Scalar => AgentObservationSpace::Scalar,
+            ObservationSpace::Vector(n) => AgentObservationSpace::Vector(*n),
+            ObservationSpace::Tensor(shape) => AgentObservationSpace::Tensor(shape.clone()),
+            ObservationSpace::Dict(entries) => {
+                AgentObservationSpace::Dict(entries.iter().map(|(k, v)| (k.clone(), v.into())).collect())
+            }
+            ObservationSpace::Text => AgentObservationSpace::Text,
+            ObservationSpace::None => AgentObservationSpace::None,
+        }
+    }
+}
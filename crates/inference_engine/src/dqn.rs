@@ -0,0 +1,292 @@
+//! Deep Q-Network brain: an MLP maps the continuous feature vector straight
+//! to one value per action, so nothing is bucketed into string keys.
+//!
+//! The usual stabilisers: an experience replay buffer, a target network
+//! synced every few hundred steps, Double-DQN targets, a Huber loss and Adam.
+//! Actions come from `ActionSpace::discrete`.
+
+use crate::nn::{Adam, Mlp, Normalizer};
+use crate::{default_menu, random, AgentObservation, AgentObservationSpace, DiscoveryEvent, Experimenter};
+use sim_engine::{Action, ActionSpace};
+
+#[derive(Clone, Debug)]
+pub struct DqnConfig {
+    /// Width of each of the two hidden layers
+    pub hidden: usize,
+    pub learning_rate: f64,
+    pub gamma: f64,
+    pub replay_capacity: usize,
+    pub batch_size: usize,
+    /// Transitions collected before training starts
+    pub warmup: usize,
+    /// Steps between copies of the online network into the target network
+    pub target_sync: u64,
+    pub epsilon_start: f64,
+    pub epsilon_end: f64,
+    /// Steps over which epsilon falls linearly from start to end
+    pub epsilon_decay: u64,
+}
+
+impl Default for DqnConfig {
+    fn default() -> Self {
+        Self {
+            hidden: 64,
+            learning_rate: 1e-3,
+            gamma: 0.99,
+            replay_capacity: 10_000,
+            batch_size: 32,
+            warmup: 256,
+            target_sync: 500,
+            epsilon_start: 1.0,
+            epsilon_end: 0.05,
+            epsilon_decay: 5_000,
+        }
+    }
+}
+
+struct Transition {
+    state: Vec<f64>,
+    action: usize,
+    reward: f64,
+    next: Vec<f64>,
+}
+
+/// Fixed-size ring of past transitions, sampled uniformly.
+struct Replay {
+    items: Vec<Transition>,
+    capacity: usize,
+    next: usize,
+}
+
+impl Replay {
+    fn new(capacity: usize) -> Self {
+        Self { items: Vec::new(), capacity: capacity.max(1), next: 0 }
+    }
+
+    fn push(&mut self, t: Transition) {
+        if self.items.len() < self.capacity {
+            self.items.push(t);
+        } else {
+            self.items[self.next] = t;
+        }
+        self.next = (self.next + 1) % self.capacity;
+    }
+
+    fn sample(&self) -> &Transition {
+        &self.items[((random() * self.items.len() as f64) as usize).min(self.items.len() - 1)]
+    }
+}
+
+pub struct DqnAgent {
+    config: DqnConfig,
+    online: Option<Mlp>,
+    target: Option<Mlp>,
+    adam: Option<Adam>,
+    replay: Replay,
+    norm: Normalizer,
+    menu: Vec<Action>,
+    /// Features and menu index of the previous act
+    last: Option<(Vec<f64>, usize)>,
+    steps: u64,
+    /// Running average of the TD loss, for the feed
+    loss: f64,
+}
+
+impl DqnAgent {
+    pub fn new() -> Self {
+        Self::with_config(DqnConfig::default())
+    }
+
+    pub fn with_config(config: DqnConfig) -> Self {
+        Self {
+            replay: Replay::new(config.replay_capacity),
+            config,
+            online: None,
+            target: None,
+            adam: None,
+            norm: Normalizer::default(),
+            menu: default_menu(),
+            last: None,
+            steps: 0,
+            loss: 0.0,
+        }
+    }
+
+    pub fn epsilon(&self) -> f64 {
+        let c = &self.config;
+        let t = (self.steps as f64 / c.epsilon_decay.max(1) as f64).min(1.0);
+        c.epsilon_start + t * (c.epsilon_end - c.epsilon_start)
+    }
+
+    /// Q-value of every menu action for these features (empty before the first act).
+    pub fn q_values(&self, features: &[f64]) -> Vec<f64> {
+        self.online.as_ref().map_or_else(Vec::new, |net| net.forward(&self.norm.apply(features)))
+    }
+
+    /// Fresh networks (and an empty buffer) whenever the input or menu size changes.
+    fn ensure_network(&mut self, inputs: usize) {
+        let outputs = self.menu.len();
+        if self.online.as_ref().is_some_and(|net| net.inputs() == inputs && net.outputs() == outputs) {
+            return;
+        }
+        let h = self.config.hidden;
+        let online = Mlp::new(&[inputs, h, h, outputs]);
+        self.adam = Some(Adam::new(&online, self.config.learning_rate));
+        self.target = Some(online.clone());
+        self.online = Some(online);
+        self.replay = Replay::new(self.config.replay_capacity);
+        self.last = None;
+    }
+
+    fn train(&mut self) {
+        let (Some(online), Some(target), Some(adam)) = (self.online.as_mut(), self.target.as_ref(), self.adam.as_mut()) else {
+            return;
+        };
+        let batch = self.config.batch_size.max(1);
+        let mut grads = vec![0.0; online.len()];
+        let mut loss = 0.0;
+        for _ in 0..batch {
+            let t = self.replay.sample();
+            let next = self.norm.apply(&t.next);
+            // Double DQN: the online net picks the next action, the target net values it
+            let best = argmax(&online.forward(&next));
+            let y = t.reward + self.config.gamma * target.forward(&next)[best];
+
+            let trace = online.trace(&self.norm.apply(&t.state));
+            let q = trace.last().unwrap()[t.action];
+            let err = q - y;
+            loss += if err.abs() <= 1.0 { 0.5 * err * err } else { err.abs() - 0.5 };
+
+            let mut grad_out = vec![0.0; online.outputs()];
+            grad_out[t.action] = err.clamp(-1.0, 1.0) / batch as f64;
+            online.backward(&trace, &grad_out, &mut grads);
+        }
+        adam.step(online, &grads);
+        self.loss = 0.99 * self.loss + 0.01 * loss / batch as f64;
+    }
+}
+
+impl Default for DqnAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Experimenter for DqnAgent {
+    fn act(&mut self, obs: &AgentObservation, reward: f64, _step: u64) -> (Action, Option<DiscoveryEvent>) {
+        let features = obs.features();
+        if features.is_empty() {
+            return (Action::Noop, None);
+        }
+        self.ensure_network(features.len());
+        self.norm.update(&features);
+
+        // Remember what the last action led to, then learn from a random batch
+        if let Some((state, action)) = self.last.take() {
+            self.replay.push(Transition { state, action, reward, next: features.clone() });
+        }
+        if self.replay.items.len() >= self.config.warmup.max(self.config.batch_size) {
+            self.train();
+        }
+
+        let action = if random() < self.epsilon() {
+            ((random() * self.menu.len() as f64) as usize).min(self.menu.len() - 1)
+        } else {
+            argmax(&self.q_values(&features))
+        };
+        self.last = Some((features, action));
+        self.steps += 1;
+
+        let mut discovery = None;
+        if self.steps.is_multiple_of(self.config.target_sync.max(1)) {
+            if let (Some(online), Some(target)) = (self.online.as_ref(), self.target.as_mut()) {
+                target.copy_from(online);
+            }
+            // Only every fourth sync makes the feed
+            if self.steps.is_multiple_of(4 * self.config.target_sync.max(1)) {
+                discovery = Some(DiscoveryEvent::Insight {
+                    topic: "DQN".into(),
+                    content: format!(
+                        "{} steps: TD loss {:.4}, ε {:.2}, {} transitions remembered",
+                        self.steps,
+                        self.loss,
+                        self.epsilon(),
+                        self.replay.items.len()
+                    ),
+                });
+            }
+        }
+
+        (self.menu[action].clone(), discovery)
+    }
+
+    fn set_observation_space(&mut self, space: &AgentObservationSpace) {
+        if space.feature_len() > 0 {
+            self.ensure_network(space.feature_len());
+        }
+    }
+
+    fn set_action_space(&mut self, space: &ActionSpace) {
+        self.menu = space.discrete();
+        // A menu of a different length needs a new output layer
+        if let Some(inputs) = self.online.as_ref().map(Mlp::inputs) {
+            self.ensure_network(inputs);
+        }
+    }
+}
+
+fn argmax(values: &[f64]) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map_or(0, |(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed_random;
+
+    /// The `dqn_control` example's task: push a point on a line (state x, v)
+    /// back to the origin, reward 1 - |x|. Returns the mean reward per episode.
+    fn point_to_origin(agent: &mut DqnAgent, episodes: usize, steps: usize) -> Vec<f64> {
+        agent.set_action_space(&ActionSpace::new().with_perturb("push", 1, -1.0, 1.0));
+        let mut means = Vec::with_capacity(episodes);
+        let mut step = 0;
+        for _ in 0..episodes {
+            let (mut x, mut v) = (4.0 * random() - 2.0, 0.0);
+            let (mut reward, mut total) = (0.0, 0.0);
+            for _ in 0..steps {
+                let (action, _) = agent.act(&AgentObservation::Vector(vec![x, v]), reward, step);
+                let push = match action {
+                    Action::Perturb { delta, .. } => delta,
+                    _ => 0.0,
+                };
+                v = 0.9 * v + 0.1 * push;
+                x = (x + v).clamp(-3.0, 3.0);
+                reward = 1.0 - x.abs();
+                total += reward;
+                step += 1;
+            }
+            means.push(total / steps as f64);
+        }
+        means
+    }
+
+    #[test]
+    fn learns_to_park_the_point() {
+        seed_random(7);
+        // Smaller and faster than the defaults, so the test runs in debug builds
+        let mut agent = DqnAgent::with_config(DqnConfig {
+            hidden: 32,
+            target_sync: 250,
+            epsilon_decay: 3_000,
+            ..DqnConfig::default()
+        });
+        let means = point_to_origin(&mut agent, 100, 100);
+        let mean = |rewards: &[f64]| rewards.iter().sum::<f64>() / rewards.len() as f64;
+        let (early, late) = (mean(&means[..20]), mean(&means[80..]));
+        assert!(late > early + 0.1, "mean reward went from {:.3} to {:.3}", early, late);
+    }
+}
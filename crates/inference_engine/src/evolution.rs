@@ -0,0 +1,93 @@
+//! Evolutionary search brain.
+//!
+//! Rather than steering the live simulation step by step, it runs
+//! `sim_engine::evolution::EvolutionSearch` in the background: each tick
+//! spends `steps_per_tick` steps evaluating candidates in headless copies of
+//! the live run (its build, sidebar picks and reward spec, via `set_setup`).
+//! Every finished generation is summarised on the feed with the parameters
+//! that reproduce its fittest and most novel candidates, and a new overall
+//! best is also set on the live simulation (the parameters its action space
+//! accepts), so the viewport shows what was found. Both go to the elite
+//! archive too (`take_elites`).
+
+use crate::{AgentObservation, DiscoveryEvent, Experimenter};
+use sim_engine::archive::Elite;
+use sim_engine::evolution::{EvolutionSearch, GenerationReport, SearchConfig, Setup};
+use sim_engine::registry;
+use sim_engine::{Action, ActionSpace};
+use std::collections::VecDeque;
+
+pub struct EvolutionAgent {
+    search: EvolutionSearch,
+    /// Simulation steps of evaluation per tick
+    pub steps_per_tick: usize,
+    space: ActionSpace,
+    /// SetParams still to send for the latest best
+    queue: VecDeque<Action>,
+    /// Generation bests and most novel, waiting for `take_elites`
+    elites: Vec<Elite>,
+}
+
+impl EvolutionAgent {
+    /// None if the registry has no such simulation or nothing in it to search.
+    pub fn for_sim(key: &str) -> Option<Self> {
+        Self::with_config(key, SearchConfig::default())
+    }
+
+    pub fn with_config(key: &str, config: SearchConfig) -> Option<Self> {
+        let info = registry::find(key)?;
+        Some(Self {
+            search: EvolutionSearch::new(info, config)?,
+            steps_per_tick: 50,
+            space: ActionSpace::default(),
+            queue: VecDeque::new(),
+            elites: Vec::new(),
+        })
+    }
+
+    pub fn search(&self) -> &EvolutionSearch {
+        &self.search
+    }
+
+    fn report(&mut self, report: &GenerationReport) -> DiscoveryEvent {
+        let mut content = format!(
+            "{} generation {}: best fitness {:.3} with {}; most novel ({:.2}) with {}; step size {:.3}",
+            self.search.info().name,
+            report.generation,
+            report.best.fitness,
+            report.best.describe(),
+            report.most_novel.novelty,
+            report.most_novel.describe(),
+            report.sigma
+        );
+        if report.new_record {
+            content.push_str(". New record, now running live");
+            let settable = report.best.params.iter().filter(|(name, _)| self.space.params.iter().any(|p| &p.name == name));
+            self.queue = settable.map(|(name, value)| Action::SetParam { name: name.clone(), value: *value }).collect();
+        }
+        self.elites.extend(self.search.elite(&report.best, "evolution"));
+        if report.most_novel != report.best {
+            self.elites.extend(self.search.elite(&report.most_novel, "evolution"));
+        }
+        DiscoveryEvent::Insight { topic: "Evolution".into(), content }
+    }
+}
+
+impl Experimenter for EvolutionAgent {
+    fn act(&mut self, _obs: &AgentObservation, _reward: f64, _step: u64) -> (Action, Option<DiscoveryEvent>) {
+        let discovery = self.search.advance(self.steps_per_tick).map(|report| self.report(&report));
+        (self.queue.pop_front().unwrap_or(Action::Noop), discovery)
+    }
+
+    fn set_action_space(&mut self, space: &ActionSpace) {
+        self.space = space.clone();
+    }
+
+    fn set_setup(&mut self, setup: &Setup) {
+        self.search.set_setup(setup.clone());
+    }
+
+    fn take_elites(&mut self) -> Vec<Elite> {
+        std::mem::take(&mut self.elites)
+    }
+}
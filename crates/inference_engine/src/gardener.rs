@@ -0,0 +1,235 @@
+//! Gardener brain for grid simulations (Life, Gray-Scott and friends).
+//!
+//! It watches the coverage of the first field in the observation (the mean of
+//! Life's density map, Gray-Scott's V) and intervenes when the system leaves
+//! the interesting band: it plants Life patterns in the emptiest patch when
+//! the population dies off or freezes, injects V when a reaction fades, and
+//! nudges the kill rate `k` when a reaction fades or floods the grid. Each
+//! intervention is explained on the discovery feed, then it waits a while to
+//! see what happened.
+
+use crate::{random, AgentObservation, DiscoveryEvent, Experimenter};
+use sim_engine::{Action, ActionSpace};
+use std::collections::VecDeque;
+
+#[derive(Clone, Debug)]
+pub struct GardenerConfig {
+    /// Coverage below this counts as dying out
+    pub low: f64,
+    /// Coverage above this counts as flooding
+    pub high: f64,
+    /// Ticks to wait after an intervention
+    pub cooldown: u64,
+    /// Ticks of (nearly) unchanged coverage before the system counts as frozen
+    pub stagnation: u64,
+    /// Share of a parameter's range moved per nudge
+    pub nudge: f64,
+}
+
+impl Default for GardenerConfig {
+    fn default() -> Self {
+        Self { low: 0.02, high: 0.4, cooldown: 60, stagnation: 300, nudge: 0.03 }
+    }
+}
+
+/// Offsets (row, col) of a few Life patterns
+const PATTERNS: &[(&str, &[(usize, usize)])] = &[
+    ("an R-pentomino", &[(0, 1), (0, 2), (1, 0), (1, 1), (2, 1)]),
+    ("an acorn", &[(0, 1), (1, 3), (2, 0), (2, 1), (2, 4), (2, 5), (2, 6)]),
+    ("a glider", &[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Condition {
+    Healthy,
+    Dying,
+    Flooding,
+    Frozen,
+}
+
+pub struct GardenerAgent {
+    config: GardenerConfig,
+    space: ActionSpace,
+    /// Actions still to send for the current intervention, one per tick
+    queue: VecDeque<Action>,
+    last_coverage: Option<f64>,
+    /// Last tick the coverage moved noticeably
+    last_change: u64,
+    /// No interventions before this tick
+    quiet_until: u64,
+    interventions: u64,
+}
+
+impl GardenerAgent {
+    pub fn new() -> Self {
+        Self::with_config(GardenerConfig::default())
+    }
+
+    pub fn with_config(config: GardenerConfig) -> Self {
+        Self {
+            config,
+            space: ActionSpace::default(),
+            queue: VecDeque::new(),
+            last_coverage: None,
+            last_change: 0,
+            quiet_until: 0,
+            interventions: 0,
+        }
+    }
+
+    fn condition(&self, coverage: f64, step: u64) -> Condition {
+        if coverage < self.config.low {
+            Condition::Dying
+        } else if coverage > self.config.high {
+            Condition::Flooding
+        } else if step.saturating_sub(self.last_change) > self.config.stagnation {
+            Condition::Frozen
+        } else {
+            Condition::Healthy
+        }
+    }
+
+    /// Queue the flips of a Life pattern at the emptiest patch of the field.
+    fn plant(&mut self, field: &Field, rows: usize, cols: usize, frozen: bool) -> Option<String> {
+        // Fading populations get a long-lived methuselah, frozen ones a glider to shake them up
+        let (name, cells) = if frozen { PATTERNS[2] } else { PATTERNS[(self.interventions % 2) as usize] };
+        let (ty, tx) = field.emptiest();
+        let r0 = ((ty as f64 + 0.5) * rows as f64 / field.height as f64) as usize;
+        let c0 = ((tx as f64 + 0.5) * cols as f64 / field.width as f64) as usize;
+        let (r0, c0) = (r0.min(rows.saturating_sub(3)), c0.min(cols.saturating_sub(7)));
+        self.queue.extend(
+            cells
+                .iter()
+                .map(|&(dr, dc)| (r0 + dr, c0 + dc))
+                .filter(|&(r, c)| r < rows && c < cols)
+                .map(|(r, c)| Action::FlipCell { r, c }),
+        );
+        Some(format!("planted {} at ({}, {})", name, r0, c0))
+    }
+
+    /// Queue a few injections at random spots.
+    fn inject(&mut self, count: usize) -> Option<String> {
+        let p = self.space.perturb.as_ref()?;
+        let (low, high) = (p.low, p.high);
+        self.queue.extend((0..count).map(|_| Action::Perturb { which: 0, delta: low + random() * (high - low) }));
+        Some(format!("injected {} at {} random spots", p.label, count))
+    }
+
+    /// Move parameter `name` by `direction` nudges from its observed value.
+    fn nudge(&mut self, obs: &AgentObservation, name: &str, direction: f64) -> Option<String> {
+        let p = self.space.param(name)?;
+        let current = match obs.get(name) {
+            Some(AgentObservation::Scalar(v)) => *v,
+            _ => 0.5 * (p.low + p.high),
+        };
+        let value = (current + direction * self.config.nudge * (p.high - p.low)).clamp(p.low, p.high);
+        self.queue.push_back(Action::SetParam { name: p.name.clone(), value });
+        Some(format!("moved {} from {:.4} to {:.4}", name, current, value))
+    }
+
+    /// The actions (queued) and explanation for one condition, if the simulation allows any.
+    fn intervene(&mut self, obs: &AgentObservation, field: &Field, condition: Condition) -> Option<String> {
+        let flip = self.space.flip_cell;
+        match (condition, flip) {
+            (Condition::Dying, Some((rows, cols))) => self.plant(field, rows, cols, false),
+            (Condition::Frozen, Some((rows, cols))) => self.plant(field, rows, cols, true),
+            // Less killing (and fresh seeds) revive a fading reaction; more killing thins a flood
+            (Condition::Dying, None) => {
+                let injected = self.inject(3);
+                let nudged = self.nudge(obs, "k", -1.0);
+                join(injected, nudged)
+            }
+            (Condition::Frozen, None) => self.inject(1),
+            (Condition::Flooding, _) => self.nudge(obs, "k", 1.0),
+            (Condition::Healthy, _) => None,
+        }
+    }
+}
+
+impl Default for GardenerAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Experimenter for GardenerAgent {
+    fn act(&mut self, obs: &AgentObservation, _reward: f64, step: u64) -> (Action, Option<DiscoveryEvent>) {
+        let Some(field) = Field::find(obs) else {
+            return (self.queue.pop_front().unwrap_or(Action::Noop), None);
+        };
+        let coverage = field.coverage();
+        if self.last_coverage.is_none_or(|last| (coverage - last).abs() > 1e-4) {
+            self.last_change = step;
+        }
+        self.last_coverage = Some(coverage);
+
+        let mut discovery = None;
+        if self.queue.is_empty() && step >= self.quiet_until {
+            let condition = self.condition(coverage, step);
+            if condition != Condition::Healthy {
+                if let Some(what) = self.intervene(obs, &field, condition) {
+                    self.interventions += 1;
+                    self.quiet_until = step + self.config.cooldown;
+                    self.last_change = step;
+                    let why = match condition {
+                        Condition::Dying => "dying out",
+                        Condition::Flooding => "flooding the grid",
+                        Condition::Frozen => "frozen",
+                        Condition::Healthy => "healthy",
+                    };
+                    discovery = Some(DiscoveryEvent::Insight {
+                        topic: "Gardener".into(),
+                        content: format!("Coverage {:.1}% ({}): {}", 100.0 * coverage, why, what),
+                    });
+                }
+            }
+        }
+        (self.queue.pop_front().unwrap_or(Action::Noop), discovery)
+    }
+
+    fn set_action_space(&mut self, space: &ActionSpace) {
+        self.space = space.clone();
+        self.queue.clear();
+    }
+}
+
+fn join(a: Option<String>, b: Option<String>) -> Option<String> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(format!("{} and {}", a, b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// The first 2D tensor in an observation
+struct Field<'a> {
+    width: usize,
+    height: usize,
+    data: &'a [f64],
+}
+
+impl<'a> Field<'a> {
+    fn find(obs: &'a AgentObservation) -> Option<Self> {
+        match obs {
+            AgentObservation::Tensor { shape, data } if shape.len() == 2 && shape[0] * shape[1] > 0 => {
+                Some(Field { height: shape[0], width: shape[1], data })
+            }
+            AgentObservation::Dict(entries) => entries.iter().find_map(|(_, value)| Field::find(value)),
+            _ => None,
+        }
+    }
+
+    fn coverage(&self) -> f64 {
+        self.data.iter().sum::<f64>() / self.data.len() as f64
+    }
+
+    /// (row, col) of the lowest cell
+    fn emptiest(&self) -> (usize, usize) {
+        let i = self
+            .data
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map_or(0, |(i, _)| i);
+        (i / self.width, i % self.width)
+    }
+}
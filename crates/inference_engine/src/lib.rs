@@ -1,8 +1,68 @@
 use serde::{Deserialize, Serialize};
+use sim_engine::archive::Elite;
+use sim_engine::evolution::Setup;
+use sim_engine::{Action, ActionSpace};
 use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
 use js_sys::Math;
 
+/// Conversions from sim_engine's observations
+pub mod bridge;
+pub mod abstraction;
+pub mod actor_critic;
+pub mod dqn;
+/// Parameter search over headless copies of a simulation
+pub mod evolution;
+pub mod gardener;
+pub mod nn;
+pub mod world_model;
+
+pub use abstraction::{AbstractionKind, Foveated, KdTree, StateAbstraction, TileCoding};
+pub use actor_critic::ActorCriticAgent;
+pub use dqn::DqnAgent;
+pub use evolution::EvolutionAgent;
+pub use gardener::GardenerAgent;
+pub use world_model::{Curiosity, EnsembleModel, WorldModelConfig};
+
+// --- RANDOMNESS ---
+// Math.random in the browser. js-sys imports panic off wasm (Python bindings,
+// headless tools), so native builds use a per-thread xorshift seeded from the clock.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn random() -> f64 {
+    Math::random()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+thread_local! {
+    static RANDOM_STATE: std::cell::Cell<u64> = std::cell::Cell::new(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0x9E37_79B9_7F4A_7C15, |d| d.as_nanos() as u64)
+            | 1,
+    );
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn random() -> f64 {
+    RANDOM_STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        state.set(x);
+        (x >> 11) as f64 / (1u64 << 53) as f64
+    })
+}
+
+/// Restart this thread's generator from `seed`, so native runs (tests,
+/// benchmarks) can be repeated exactly.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn seed_random(seed: u64) {
+    // Zero is xorshift's one fixed point
+    RANDOM_STATE.with(|state| state.set(seed.max(1)));
+}
+
 // --- SHARED EVENTS ---
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum DiscoveryEvent {
@@ -11,47 +71,119 @@ pub enum DiscoveryEvent {
 }
 
 // --- AGENT INTERFACE ---
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
-pub enum DiscreteAction {
-    Noop,
-    KickXPos, KickXNeg,
-    KickYPos, KickYNeg,
-    KickZPos, KickZNeg,
+// Actions are sim_engine's own (`Action`, `ActionSpace`); observations are
+// mirrored below so brains can work on plain numbers.
+
+/// Mirrors sim_engine's Observation: named scalars, vectors and field tensors.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentObservation {
+    Scalar(f64),
+    Vector(Vec<f64>),
+    Tensor { shape: Vec<usize>, data: Vec<f64> },
+    Dict(Vec<(String, AgentObservation)>),
+    Text(String),
+    None,
 }
 
-#[derive(Debug, Clone)]
-pub enum AgentAction {
-    FlipCell { r: usize, c: usize },
-    Perturb { which: u8, delta: f64 },
-    SetParam { name: String, val: f64 },
-    Noop,
+impl AgentObservation {
+    /// Scalars and vectors in order, tensors skipped
+    pub fn features(&self) -> Vec<f64> {
+        let mut out = Vec::new();
+        self.collect(&mut out, false);
+        out
+    }
+
+    /// Every number, tensors included
+    pub fn flatten(&self) -> Vec<f64> {
+        let mut out = Vec::new();
+        self.collect(&mut out, true);
+        out
+    }
+
+    fn collect(&self, out: &mut Vec<f64>, tensors: bool) {
+        match self {
+            AgentObservation::Scalar(v) => out.push(*v),
+            AgentObservation::Vector(v) => out.extend_from_slice(v),
+            AgentObservation::Tensor { data, .. } if tensors => out.extend_from_slice(data),
+            AgentObservation::Dict(entries) => entries.iter().for_each(|(_, value)| value.collect(out, tensors)),
+            _ => {}
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&AgentObservation> {
+        match self {
+            AgentObservation::Dict(entries) => entries.iter().find(|(k, _)| k == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
-pub enum AgentObservation {
-    GridSummary { width: usize, height: usize },
-    StateVec([f64; 3]),
+/// Shape of the observations a simulation will send (mirrors ObservationSpace).
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentObservationSpace {
+    Scalar,
+    Vector(usize),
+    Tensor(Vec<usize>),
+    Dict(Vec<(String, AgentObservationSpace)>),
+    Text,
     None,
 }
 
+impl AgentObservationSpace {
+    /// Length of `AgentObservation::features`
+    pub fn feature_len(&self) -> usize {
+        match self {
+            AgentObservationSpace::Scalar => 1,
+            AgentObservationSpace::Vector(n) => *n,
+            AgentObservationSpace::Dict(entries) => entries.iter().map(|(_, s)| s.feature_len()).sum(),
+            _ => 0,
+        }
+    }
+}
+
 pub trait Experimenter {
-    fn act(&mut self, obs: &AgentObservation, reward: f64, step: u64) -> (AgentAction, Option<DiscoveryEvent>);
+    fn act(&mut self, obs: &AgentObservation, reward: f64, step: u64) -> (Action, Option<DiscoveryEvent>);
+
+    /// Called once before the first `act`, so agents can size tables or networks.
+    fn set_observation_space(&mut self, _space: &AgentObservationSpace) {}
+
+    /// Called before the first `act` and whenever the accepted actions change.
+    fn set_action_space(&mut self, _space: &ActionSpace) {}
+
+    /// Called whenever the live run's setup changes (sidebar picks, reward spec),
+    /// for brains that evaluate off-screen copies of it.
+    fn set_setup(&mut self, _setup: &Setup) {}
+
+    /// Finds made off screen (headless searches) since the last call, for the
+    /// elite archive.
+    fn take_elites(&mut self) -> Vec<Elite> {
+        Vec::new()
+    }
 }
 
 // ---------------------------------------------------------
 // INTELLIGENCE: Curious Q-Learning Agent (v2: Predictive)
 // ---------------------------------------------------------
 pub struct QLearningAgent {
-    // Q-Table: Maps "StateHash" -> {Action: Value}
-    q_table: HashMap<String, HashMap<DiscreteAction, f64>>,
+    // Q-Table: Maps cell key -> one value per menu action
+    q_table: HashMap<u64, Vec<f64>>,
+    // How continuous features become cell keys (foveated, tile coding, k-d tree)
+    abstraction: Box<dyn StateAbstraction>,
     
     // NEW: World Model (Physics Engine in the Brain)
-    // Maps (StateHash, Action) -> Predicted Next Continuous State [x, y, z]
-    world_model: HashMap<(String, DiscreteAction), [f64; 3]>,
+    // Maps (Cell, Action) -> Predicted Next Continuous State [x, y, z]
+    world_model: HashMap<(u64, usize), [f64; 3]>,
+    // Learned ensemble, for every scheme but Tabular (built on the first features)
+    curiosity: Curiosity,
+    ensemble: Option<EnsembleModel>,
 
-    last_action: DiscreteAction,
-    last_state_key: String,
+    last_action: usize,
+    last_keys: Vec<u64>,
+    last_features: Vec<f64>,
     last_state_vec: [f64; 3], // Keep track of exact physics state
+    refinements: usize,
+    // What the actions in the table stand for (`ActionSpace::discrete`); Noop first
+    menu: Vec<Action>,
     
     // Hyperparameters
     epsilon: f64, 
@@ -61,128 +193,191 @@ pub struct QLearningAgent {
 
 impl QLearningAgent {
     pub fn new() -> Self {
+        Self::with_abstraction(AbstractionKind::default().build())
+    }
+
+    pub fn with_abstraction(abstraction: Box<dyn StateAbstraction>) -> Self {
         Self {
             q_table: HashMap::new(),
+            abstraction,
             world_model: HashMap::new(),
-            last_action: DiscreteAction::Noop,
-            last_state_key: "0_0_0".to_string(),
+            curiosity: Curiosity::default(),
+            ensemble: None,
+            last_action: 0,
+            last_keys: Vec::new(),
+            last_features: Vec::new(),
             last_state_vec: [0.0, 0.0, 0.0],
+            refinements: 0,
+            menu: default_menu(),
             epsilon: 0.5, 
             alpha: 0.1,
             gamma: 0.9,
         }
     }
 
-    // CHANGE #3: Foveated Vision (Logarithmic Discretization)
-    // High resolution near 0, low resolution far away.
-    fn discretize(&self, state: [f64; 3]) -> String {
-        let foveate = |v: f64| -> i32 {
-            // log(1 + |x|) compresses huge distances.
-            // Scale factor 4.0 gives us buckets like: 0..0.25, 0.25..0.6, etc.
-            let sign = v.signum();
-            let val = (v.abs() + 1.0).ln(); 
-            (sign * val * 4.0) as i32
-        };
+    /// Where the intrinsic reward comes from
+    pub fn with_curiosity(mut self, curiosity: Curiosity) -> Self {
+        self.curiosity = curiosity;
+        self
+    }
+
+    /// Number of cells with a row in the Q-table
+    pub fn table_size(&self) -> usize {
+        self.q_table.len()
+    }
 
-        format!("{}_{}_{}", foveate(state[0]), foveate(state[1]), foveate(state[2]))
+    fn map_action(&self, action: usize) -> Action {
+        self.menu.get(action).cloned().unwrap_or(Action::Noop)
     }
 
-    fn map_action(&self, action: DiscreteAction) -> AgentAction {
-        let kick = 5.0; 
-        match action {
-            DiscreteAction::Noop => AgentAction::Noop,
-            DiscreteAction::KickXPos => AgentAction::Perturb { which: 0, delta: kick },
-            DiscreteAction::KickXNeg => AgentAction::Perturb { which: 0, delta: -kick },
-            DiscreteAction::KickYPos => AgentAction::Perturb { which: 1, delta: kick },
-            DiscreteAction::KickYNeg => AgentAction::Perturb { which: 1, delta: -kick },
-            DiscreteAction::KickZPos => AgentAction::Perturb { which: 2, delta: kick },
-            DiscreteAction::KickZNeg => AgentAction::Perturb { which: 2, delta: -kick },
+    // Mean over the cells' rows; cells never visited count as zero
+    fn q_values(&self, keys: &[u64]) -> Vec<f64> {
+        let mut values = vec![0.0; self.menu.len()];
+        for row in keys.iter().filter_map(|k| self.q_table.get(k)) {
+            for (v, q) in values.iter_mut().zip(row) {
+                *v += q / keys.len() as f64;
+            }
         }
+        values
     }
 
-    fn get_max_q(&self, state_key: &str) -> f64 {
-        if let Some(actions) = self.q_table.get(state_key) {
-            actions.values().cloned().fold(f64::NEG_INFINITY, f64::max)
-        } else {
-            0.0 
+    // Refined cells start from their parent's values; the parent is gone
+    fn seed_refined(&mut self, splits: &[(u64, u64)]) {
+        for (old, new) in splits {
+            if let Some(row) = self.q_table.get(old).cloned() {
+                self.q_table.insert(*new, row);
+            }
+        }
+        for (old, _) in splits {
+            self.q_table.remove(old);
         }
     }
 
+    fn get_max_q(&self, keys: &[u64]) -> f64 {
+        self.q_values(keys).into_iter().fold(f64::NEG_INFINITY, f64::max)
+    }
+
     // Calculate Euclidean distance between two 3D points
     fn dist(&self, a: [f64; 3], b: [f64; 3]) -> f64 {
         ((a[0]-b[0]).powi(2) + (a[1]-b[1]).powi(2) + (a[2]-b[2]).powi(2)).sqrt()
     }
 }
 
+impl Default for QLearningAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Experimenter for QLearningAgent {
-    fn act(&mut self, obs: &AgentObservation, base_reward: f64, step: u64) -> (AgentAction, Option<DiscoveryEvent>) {
+    fn act(&mut self, obs: &AgentObservation, base_reward: f64, step: u64) -> (Action, Option<DiscoveryEvent>) {
         let mut discovery = None;
 
-        if let AgentObservation::StateVec(current_state) = obs {
+        // Cells come from every feature; the world model tracks the first three (padded with zeros)
+        let features = obs.features();
+        if !features.is_empty() {
+            let mut state = [0.0; 3];
+            for (slot, v) in state.iter_mut().zip(&features) {
+                *slot = *v;
+            }
+            let current_state = &state;
+
             // 1. OBSERVE
-            let current_state_key = self.discretize(*current_state);
+            let mut current_keys = self.abstraction.keys(&features);
             
             // 2. CHANGE #1: CALCULATE SURPRISE (Prediction Error)
             // Did the world behave how we thought it would given our last action?
             let mut surprise = 0.0;
-            let prediction_key = (self.last_state_key.clone(), self.last_action);
+            if self.curiosity == Curiosity::Tabular {
+                let prediction_key = (self.last_keys.first().copied().unwrap_or(0), self.last_action);
             
-            if let Some(predicted_state) = self.world_model.get(&prediction_key) {
-                let error = self.dist(*predicted_state, *current_state);
-                // If error is high, we are surprised! Reward this.
-                // We cap it to prevent infinite loops of chaos.
-                surprise = (error * 5.0).min(50.0); 
-            } else {
-                // First time trying this? Moderate curiosity boost.
-                surprise = 5.0;
-            }
+                if let Some(predicted_state) = self.world_model.get(&prediction_key) {
+                    let error = self.dist(*predicted_state, *current_state);
+                    // If error is high, we are surprised! Reward this.
+                    // We cap it to prevent infinite loops of chaos.
+                    surprise = (error * 5.0).min(50.0); 
+                } else {
+                    // First time trying this? Moderate curiosity boost.
+                    surprise = 5.0;
+                }
 
-            // 3. UPDATE WORLD MODEL (Learn Physics)
-            // "Next time I am in [LastState] and do [Action], I expect [CurrentState]"
-            // Use a simple moving average to smooth out noise (Learning Rate 0.5)
-            let new_prediction = if let Some(prev) = self.world_model.get(&prediction_key) {
-                [
-                    0.5 * prev[0] + 0.5 * current_state[0],
-                    0.5 * prev[1] + 0.5 * current_state[1],
-                    0.5 * prev[2] + 0.5 * current_state[2]
-                ]
+                // 3. UPDATE WORLD MODEL (Learn Physics)
+                // "Next time I am in [LastState] and do [Action], I expect [CurrentState]"
+                // Use a simple moving average to smooth out noise (Learning Rate 0.5)
+                let new_prediction = if let Some(prev) = self.world_model.get(&prediction_key) {
+                    [
+                        0.5 * prev[0] + 0.5 * current_state[0],
+                        0.5 * prev[1] + 0.5 * current_state[1],
+                        0.5 * prev[2] + 0.5 * current_state[2]
+                    ]
+                } else {
+                    *current_state
+                };
+                self.world_model.insert(prediction_key, new_prediction);
             } else {
-                *current_state
-            };
-            self.world_model.insert(prediction_key, new_prediction);
+                let actions = self.menu.len();
+                if !self.ensemble.as_ref().is_some_and(|m| m.fits(features.len(), actions)) {
+                    self.ensemble = Some(EnsembleModel::new(features.len(), actions, WorldModelConfig::default()));
+                } else if let (Some(model), true) = (self.ensemble.as_mut(), self.last_features.len() == features.len()) {
+                    let s = model.observe(&self.last_features, self.last_action, &features);
+                    let signal = match self.curiosity {
+                        Curiosity::Disagreement => s.disagreement,
+                        // A difference of two slow averages: small, so scaled up
+                        Curiosity::LearningProgress => 10.0 * s.progress,
+                        _ => s.error,
+                    };
+                    surprise = (signal * 5.0).min(50.0);
+                }
+            }
 
             // 4. TOTAL REWARD = Stability (Base) + Curiosity (Surprise)
             let total_reward = base_reward + surprise;
 
-            // 5. LEARN (Update Q-Table)
-            let max_future_q = self.get_max_q(&current_state_key);
-            let action_values = self.q_table.entry(self.last_state_key.clone()).or_default();
-            let current_q = action_values.entry(self.last_action).or_insert(0.0);
-            
-            *current_q += self.alpha * (total_reward + self.gamma * max_future_q - *current_q);
+            // 5. LEARN (Update every cell the last state fell in, once the cells have settled)
+            let settled = self.abstraction.ready();
+            if settled && !self.last_keys.is_empty() {
+                let target = total_reward + self.gamma * self.get_max_q(&current_keys);
+                let error = target - self.q_values(&self.last_keys)[self.last_action];
+                let actions = self.menu.len();
+                for key in &self.last_keys {
+                    let row = self.q_table.entry(*key).or_insert_with(|| vec![0.0; actions]);
+                    row[self.last_action] += self.alpha * error;
+                }
 
-            // 6. DECIDE ACTION (Epsilon-Greedy)
-            let action = if Math::random() < self.epsilon {
-                match (Math::random() * 7.0) as u8 {
-                    0 => DiscreteAction::KickXPos, 1 => DiscreteAction::KickXNeg,
-                    2 => DiscreteAction::KickYPos, 3 => DiscreteAction::KickYNeg,
-                    4 => DiscreteAction::KickZPos, 5 => DiscreteAction::KickZNeg,
-                    _ => DiscreteAction::Noop,
+                let splits = self.abstraction.observe(&self.last_features, target);
+                self.seed_refined(&splits);
+                if !splits.is_empty() {
+                    current_keys = self.abstraction.keys(&features);
+                    self.refinements += 1;
+                }
+                // Reported at 1, 2, 4, 8... refinements so a growing tree doesn't flood the feed
+                if !splits.is_empty() && self.refinements.is_power_of_two() {
+                    discovery = Some(DiscoveryEvent::Insight {
+                        topic: "State Abstraction".into(),
+                        content: format!("Refined the state space: {}", self.abstraction.describe()),
+                    });
                 }
+            }
+
+            // 6. DECIDE ACTION (Epsilon-Greedy)
+            let action = if random() < self.epsilon {
+                ((random() * self.menu.len() as f64) as usize).min(self.menu.len().saturating_sub(1))
             } else {
-                let values = self.q_table.entry(current_state_key.clone()).or_default();
+                // First best, so an unexplored cell picks Noop
+                let values = self.q_values(&current_keys);
                 values.iter()
-                    .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
-                    .map(|(k, _)| *k)
-                    .unwrap_or(DiscreteAction::Noop)
+                    .enumerate()
+                    .fold((0, f64::NEG_INFINITY), |best, (i, &v)| if v > best.1 { (i, v) } else { best })
+                    .0
             };
 
             // 7. LOGGING & MEMORY
-            self.last_state_key = current_state_key;
+            self.last_keys = current_keys;
+            self.last_features = features;
             self.last_state_vec = *current_state;
             self.last_action = action;
             
-            if self.epsilon > 0.05 { self.epsilon *= 0.995; }
+            if settled && self.epsilon > 0.05 { self.epsilon *= 0.995; }
 
             // Generate insights based on Surprise, not just random text
             if surprise > 25.0 && step % 60 == 0 {
@@ -195,37 +390,121 @@ impl Experimenter for QLearningAgent {
             return (self.map_action(action), discovery);
         }
 
-        (AgentAction::Noop, None)
+        (Action::Noop, None)
+    }
+
+    fn set_action_space(&mut self, space: &ActionSpace) {
+        let menu = space.discrete();
+        // Indices into a different menu mean different actions: start over
+        if menu != self.menu {
+            self.q_table.clear();
+            self.world_model.clear();
+            self.last_action = 0;
+            self.last_keys.clear();
+            self.menu = menu;
+        }
     }
 }
 
-// ... (GardenerAgent, MockExperimenter, Factory - Keep same) ...
-pub struct GardenerAgent;
-impl GardenerAgent { pub fn new() -> Self { Self } }
-impl Experimenter for GardenerAgent {
-    fn act(&mut self, _: &AgentObservation, _: f64, _: u64) -> (AgentAction, Option<DiscoveryEvent>) {
-        (AgentAction::Noop, None)
+/// Before any action space arrives: ±5 kicks on three axes, as for Lorenz
+pub(crate) fn default_menu() -> Vec<Action> {
+    let mut menu = vec![Action::Noop];
+    for which in 0..3 {
+        menu.push(Action::Perturb { which, delta: 5.0 });
+        menu.push(Action::Perturb { which, delta: -5.0 });
     }
+    menu
 }
 
+// ... (MockExperimenter, Factory - Keep same) ...
 pub struct MockExperimenter;
 impl MockExperimenter { pub fn new() -> Self { Self } }
 impl Experimenter for MockExperimenter {
-    fn act(&mut self, _: &AgentObservation, _: f64, _: u64) -> (AgentAction, Option<DiscoveryEvent>) {
-        (AgentAction::Noop, None)
+    fn act(&mut self, _: &AgentObservation, _: f64, _: u64) -> (Action, Option<DiscoveryEvent>) {
+        (Action::Noop, None)
     }
 }
 
+/// Every brain `BrainType::from_name` knows, for menus: (name, label, description)
+pub const BRAINS: &[(&str, &str, &str)] = &[
+    ("qlearner", "Q-learner", "Tabular Q-learning over tile-coded features, curious where its world models disagree"),
+    ("qlearner:kdtree", "Q-learner (k-d tree)", "Tabular Q-learning over cells that split where values vary"),
+    ("qlearner:progress", "Q-learner (progress)", "Curious where its prediction error is falling fastest"),
+    ("qlearner:tabular", "Q-learner (tabular)", "The original per-cell next-state averages as curiosity"),
+    ("dqn", "DQN", "Deep Q-network over the raw features"),
+    ("actor-critic", "Actor-critic", "PPO-style policy with continuous knob values"),
+    ("gardener", "Gardener", "Rule-based: replants, injects and nudges k to keep the grid alive"),
+    ("evolution", "Evolution", "CMA-ES over the parameters, in headless copies"),
+    ("mock", "None", "Watches without acting"),
+];
+
 pub enum BrainType {
-    QLearner,
+    QLearner { abstraction: AbstractionKind, curiosity: Curiosity },
+    Dqn,
+    ActorCritic,
     Gardener,
+    /// Searches the simulation's parameters headlessly; needs `create_brain_for`
+    Evolution,
     Mock,
 }
 
-pub fn create_brain(brain_type: BrainType) -> Box<dyn Experimenter> {
-    match brain_type {
-        BrainType::QLearner => Box::new(QLearningAgent::new()),
+impl BrainType {
+    /// "qlearner", "dqn", "actor-critic", "gardener", "evolution" or "mock" (the
+    /// names the simulation registry uses). The Q-learner takes options after
+    /// colons, in any order: a state abstraction (see `AbstractionKind`) and a
+    /// curiosity scheme (see `Curiosity`), e.g. "qlearner:kdtree:progress".
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        let mut parts = name.split(':').map(str::trim);
+        let brain = match parts.next()? {
+            "qlearner" | "q-learner" => {
+                let (mut abstraction, mut curiosity) = (AbstractionKind::default(), Curiosity::default());
+                for option in parts.by_ref() {
+                    match (AbstractionKind::from_name(option), Curiosity::from_name(option)) {
+                        (Some(kind), _) => abstraction = kind,
+                        (_, Some(scheme)) => curiosity = scheme,
+                        _ => return None,
+                    }
+                }
+                BrainType::QLearner { abstraction, curiosity }
+            }
+            "dqn" => BrainType::Dqn,
+            "actor-critic" | "ppo" => BrainType::ActorCritic,
+            "gardener" => BrainType::Gardener,
+            "evolution" | "cma-es" => BrainType::Evolution,
+            "mock" => BrainType::Mock,
+            _ => return None,
+        };
+        // Only the Q-learner takes options
+        match parts.next() {
+            Some(_) => None,
+            None => Some(brain),
+        }
+    }
+}
+
+/// Err for brains that need to know their simulation (see `create_brain_for`).
+pub fn create_brain(brain_type: BrainType) -> Result<Box<dyn Experimenter>, String> {
+    Ok(match brain_type {
+        BrainType::QLearner { abstraction, curiosity } => {
+            Box::new(QLearningAgent::with_abstraction(abstraction.build()).with_curiosity(curiosity))
+        }
+        BrainType::Dqn => Box::new(DqnAgent::new()),
+        BrainType::ActorCritic => Box::new(ActorCriticAgent::new()),
         BrainType::Gardener => Box::new(GardenerAgent::new()),
+        BrainType::Evolution => return Err("the evolution brain needs a simulation to search".into()),
         BrainType::Mock => Box::new(MockExperimenter::new()),
+    })
+}
+
+/// `create_brain`, for brains that need to know which registry simulation they
+/// drive. Err if the simulation gives them nothing to work with.
+pub fn create_brain_for(brain_type: BrainType, sim_key: &str) -> Result<Box<dyn Experimenter>, String> {
+    match brain_type {
+        BrainType::Evolution => match EvolutionAgent::for_sim(sim_key) {
+            Some(agent) => Ok(Box::new(agent)),
+            None => Err(format!("evolution has no parameters to search in '{}'", sim_key)),
+        },
+        other => create_brain(other),
     }
 }
@@ -0,0 +1,193 @@
+//! A small dense network (ReLU hidden layers, linear output) and Adam.
+//! Plain f64 and no dependencies, so it runs the same in the browser and
+//! natively. All weights live in one flat vector; gradients use the same
+//! layout, which keeps Adam and target-network copies trivial.
+
+use crate::random;
+
+#[derive(Clone, Debug)]
+pub struct Mlp {
+    sizes: Vec<usize>,
+    params: Vec<f64>,
+}
+
+impl Mlp {
+    /// `sizes` = [inputs, hidden..., outputs], He-uniform initial weights.
+    pub fn new(sizes: &[usize]) -> Self {
+        assert!(sizes.len() >= 2, "an Mlp needs at least an input and an output size");
+        let mut params = Vec::new();
+        for pair in sizes.windows(2) {
+            let (inputs, outputs) = (pair[0].max(1), pair[1]);
+            let bound = (6.0 / inputs as f64).sqrt();
+            params.extend((0..inputs * outputs).map(|_| (2.0 * random() - 1.0) * bound));
+            params.extend(std::iter::repeat_n(0.0, outputs));
+        }
+        Self { sizes: sizes.to_vec(), params }
+    }
+
+    pub fn inputs(&self) -> usize {
+        self.sizes[0]
+    }
+
+    pub fn outputs(&self) -> usize {
+        *self.sizes.last().unwrap()
+    }
+
+    /// Number of weights and biases (the length of a gradient vector)
+    pub fn len(&self) -> usize {
+        self.params.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    pub fn forward(&self, x: &[f64]) -> Vec<f64> {
+        self.trace(x).pop().unwrap()
+    }
+
+    /// Every layer's activations, input first and output last, for `backward`.
+    pub fn trace(&self, x: &[f64]) -> Vec<Vec<f64>> {
+        let last = self.sizes.len() - 2;
+        let mut acts = vec![x.to_vec()];
+        let mut offset = 0;
+        for (l, pair) in self.sizes.windows(2).enumerate() {
+            let (inputs, outputs) = (pair[0], pair[1]);
+            let input = &acts[l];
+            let (w, b) = self.params[offset..offset + inputs * outputs + outputs].split_at(inputs * outputs);
+            let out: Vec<f64> = (0..outputs)
+                .map(|o| {
+                    let z = b[o] + w[o * inputs..(o + 1) * inputs].iter().zip(input).map(|(w, a)| w * a).sum::<f64>();
+                    if l < last { z.max(0.0) } else { z }
+                })
+                .collect();
+            acts.push(out);
+            offset += inputs * outputs + outputs;
+        }
+        acts
+    }
+
+    /// Add the gradient of a loss to `grads`, given the loss's derivative
+    /// with respect to the output. Returns the derivative with respect to the input.
+    pub fn backward(&self, trace: &[Vec<f64>], grad_out: &[f64], grads: &mut [f64]) -> Vec<f64> {
+        let mut offsets = Vec::with_capacity(self.sizes.len() - 1);
+        let mut offset = 0;
+        for pair in self.sizes.windows(2) {
+            offsets.push(offset);
+            offset += pair[0] * pair[1] + pair[1];
+        }
+
+        let mut delta = grad_out.to_vec();
+        for l in (0..self.sizes.len() - 1).rev() {
+            let (inputs, outputs) = (self.sizes[l], self.sizes[l + 1]);
+            let offset = offsets[l];
+            let input = &trace[l];
+            let mut grad_in = vec![0.0; inputs];
+            for o in 0..outputs {
+                let d = delta[o];
+                if d == 0.0 {
+                    continue;
+                }
+                let row = offset + o * inputs;
+                for i in 0..inputs {
+                    grads[row + i] += d * input[i];
+                    grad_in[i] += d * self.params[row + i];
+                }
+                grads[offset + inputs * outputs + o] += d;
+            }
+            // ReLU: no gradient through units that were off
+            if l > 0 {
+                for (g, a) in grad_in.iter_mut().zip(input) {
+                    if *a <= 0.0 {
+                        *g = 0.0;
+                    }
+                }
+            }
+            delta = grad_in;
+        }
+        delta
+    }
+
+    /// Overwrite these weights with another network's (same shape).
+    pub fn copy_from(&mut self, other: &Mlp) {
+        self.params.copy_from_slice(&other.params);
+    }
+}
+
+/// Adam with global gradient-norm clipping.
+#[derive(Clone, Debug)]
+pub struct Adam {
+    pub lr: f64,
+    pub beta1: f64,
+    pub beta2: f64,
+    /// Gradients longer than this are scaled down first
+    pub clip: f64,
+    m: Vec<f64>,
+    v: Vec<f64>,
+    t: i32,
+}
+
+impl Adam {
+    pub fn new(net: &Mlp, lr: f64) -> Self {
+        Self::with_len(net.len(), lr)
+    }
+
+    /// For parameters that aren't an Mlp (e.g. a policy's log standard deviations)
+    pub fn with_len(len: usize, lr: f64) -> Self {
+        Self { lr, beta1: 0.9, beta2: 0.999, clip: 10.0, m: vec![0.0; len], v: vec![0.0; len], t: 0 }
+    }
+
+    /// One descent step along `grads` (already averaged over the batch).
+    pub fn step(&mut self, net: &mut Mlp, grads: &[f64]) {
+        self.step_params(&mut net.params, grads);
+    }
+
+    pub fn step_params(&mut self, params: &mut [f64], grads: &[f64]) {
+        let norm = grads.iter().map(|g| g * g).sum::<f64>().sqrt();
+        let scale = if norm > self.clip { self.clip / norm } else { 1.0 };
+        self.t += 1;
+        let (c1, c2) = (1.0 - self.beta1.powi(self.t), 1.0 - self.beta2.powi(self.t));
+        for (i, p) in params.iter_mut().enumerate() {
+            let g = grads[i] * scale;
+            self.m[i] = self.beta1 * self.m[i] + (1.0 - self.beta1) * g;
+            self.v[i] = self.beta2 * self.v[i] + (1.0 - self.beta2) * g * g;
+            *p -= self.lr * (self.m[i] / c1) / ((self.v[i] / c2).sqrt() + 1e-8);
+        }
+    }
+}
+
+/// Running per-feature mean and variance (Welford), so observations on very
+/// different scales (Lorenz ±20, total mass in the hundreds) train alike.
+#[derive(Clone, Debug, Default)]
+pub struct Normalizer {
+    count: f64,
+    mean: Vec<f64>,
+    m2: Vec<f64>,
+}
+
+impl Normalizer {
+    pub fn update(&mut self, x: &[f64]) {
+        if self.mean.len() != x.len() {
+            *self = Self { count: 0.0, mean: vec![0.0; x.len()], m2: vec![0.0; x.len()] };
+        }
+        self.count += 1.0;
+        for (i, &v) in x.iter().enumerate() {
+            let d = v - self.mean[i];
+            self.mean[i] += d / self.count;
+            self.m2[i] += d * (v - self.mean[i]);
+        }
+    }
+
+    /// Standardised and clamped to ±10; unseen features pass through.
+    pub fn apply(&self, x: &[f64]) -> Vec<f64> {
+        x.iter()
+            .enumerate()
+            .map(|(i, &v)| match (self.mean.get(i), self.m2.get(i)) {
+                (Some(mean), Some(m2)) if self.count > 1.0 => {
+                    ((v - mean) / (m2 / self.count + 1e-8).sqrt()).clamp(-10.0, 10.0)
+                }
+                _ => v,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,181 @@
+//! Learned forward model for curiosity: an ensemble of small MLPs, each
+//! predicting the change in the features from (features, one-hot action).
+//!
+//! Members start from different random weights and each trains on a random
+//! ~half of the transitions, so where data is scarce they disagree; that
+//! disagreement is the model's epistemic uncertainty. Everything is compared
+//! in standardised units, so one scale fits Lorenz and Gray-Scott alike.
+
+use crate::nn::{Adam, Mlp, Normalizer};
+use crate::random;
+
+/// How the Q-learner turns its world model into an intrinsic reward.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Curiosity {
+    /// Averaged next states per discretised cell (the original scheme)
+    Tabular,
+    /// The ensemble's error predicting what just happened
+    PredictionError,
+    /// How much the ensemble members disagreed about it beforehand
+    #[default]
+    Disagreement,
+    /// How fast the prediction error for the action is falling
+    LearningProgress,
+}
+
+impl Curiosity {
+    /// "tabular", "prediction-error", "disagreement" or "learning-progress"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "tabular" => Some(Curiosity::Tabular),
+            "prediction-error" | "error" => Some(Curiosity::PredictionError),
+            "disagreement" | "ensemble" => Some(Curiosity::Disagreement),
+            "learning-progress" | "progress" => Some(Curiosity::LearningProgress),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WorldModelConfig {
+    pub members: usize,
+    pub hidden: usize,
+    pub learning_rate: f64,
+    /// Chance that a member trains on any given transition
+    pub bootstrap: f64,
+    /// Smoothing of the fast and slow error averages behind learning progress
+    pub fast: f64,
+    pub slow: f64,
+}
+
+impl Default for WorldModelConfig {
+    fn default() -> Self {
+        Self { members: 5, hidden: 32, learning_rate: 1e-3, bootstrap: 0.5, fast: 0.1, slow: 0.01 }
+    }
+}
+
+/// What the model made of one transition (standardised units).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Surprise {
+    /// Mean squared error of the ensemble's mean prediction
+    pub error: f64,
+    /// Mean variance across members, per feature
+    pub disagreement: f64,
+    /// Slow minus fast average of this action's error; positive while it improves
+    pub progress: f64,
+}
+
+pub struct EnsembleModel {
+    config: WorldModelConfig,
+    members: Vec<(Mlp, Adam)>,
+    features: usize,
+    actions: usize,
+    inputs: Normalizer,
+    deltas: Normalizer,
+    /// Per action: (fast, slow) averages of the error, once it has been tried
+    errors: Vec<Option<(f64, f64)>>,
+}
+
+impl EnsembleModel {
+    pub fn new(features: usize, actions: usize, config: WorldModelConfig) -> Self {
+        let members = (0..config.members.max(1))
+            .map(|_| {
+                let net = Mlp::new(&[features + actions, config.hidden, config.hidden, features]);
+                let adam = Adam::new(&net, config.learning_rate);
+                (net, adam)
+            })
+            .collect();
+        Self {
+            config,
+            members,
+            features,
+            actions,
+            inputs: Normalizer::default(),
+            deltas: Normalizer::default(),
+            errors: vec![None; actions],
+        }
+    }
+
+    /// Whether it was built for this many features and actions
+    pub fn fits(&self, features: usize, actions: usize) -> bool {
+        self.features == features && self.actions == actions
+    }
+
+    fn input(&self, state: &[f64], action: usize) -> Vec<f64> {
+        let mut x = self.inputs.apply(state);
+        x.extend((0..self.actions).map(|a| if a == action { 1.0 } else { 0.0 }));
+        x
+    }
+
+    /// Score the transition, then train on it.
+    pub fn observe(&mut self, state: &[f64], action: usize, next: &[f64]) -> Surprise {
+        let delta: Vec<f64> = next.iter().zip(state).map(|(n, s)| n - s).collect();
+        self.inputs.update(state);
+        self.deltas.update(&delta);
+        let x = self.input(state, action);
+        let target = self.deltas.apply(&delta);
+
+        let traces: Vec<Vec<Vec<f64>>> = self.members.iter().map(|(net, _)| net.trace(&x)).collect();
+        let k = traces.len() as f64;
+        let n = self.features.max(1) as f64;
+        let mut surprise = Surprise::default();
+        for (i, t) in target.iter().enumerate() {
+            let preds: Vec<f64> = traces.iter().map(|trace| trace.last().unwrap()[i]).collect();
+            let mean = preds.iter().sum::<f64>() / k;
+            surprise.error += (mean - t).powi(2) / n;
+            surprise.disagreement += preds.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / k / n;
+        }
+
+        for ((net, adam), trace) in self.members.iter_mut().zip(&traces) {
+            if random() >= self.config.bootstrap {
+                continue;
+            }
+            let out = trace.last().unwrap();
+            let grad_out: Vec<f64> = out.iter().zip(&target).map(|(p, t)| (p - t) / n).collect();
+            let mut grads = vec![0.0; net.len()];
+            net.backward(trace, &grad_out, &mut grads);
+            adam.step(net, &grads);
+        }
+
+        match self.errors.get_mut(action) {
+            Some(Some((fast, slow))) => {
+                *fast += self.config.fast * (surprise.error - *fast);
+                *slow += self.config.slow * (surprise.error - *slow);
+                surprise.progress = (*slow - *fast).max(0.0);
+            }
+            Some(slot) => *slot = Some((surprise.error, surprise.error)),
+            None => {}
+        }
+        surprise
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed_random;
+
+    #[test]
+    fn disagreement_falls_as_the_model_fits_a_linear_map() {
+        seed_random(11);
+        let mut model = EnsembleModel::new(2, 2, WorldModelConfig { learning_rate: 3e-3, ..WorldModelConfig::default() });
+        // next = A·state plus a push along x whose sign is the action
+        let step = |s: &[f64], a: usize| {
+            let push = if a == 0 { -0.2 } else { 0.2 };
+            vec![0.9 * s[0] - 0.3 * s[1] + push, 0.3 * s[0] + 0.8 * s[1]]
+        };
+        let surprises: Vec<Surprise> = (0..5000)
+            .map(|_| {
+                let state = [2.0 * random() - 1.0, 2.0 * random() - 1.0];
+                let action = (random() < 0.5) as usize;
+                model.observe(&state, action, &step(&state, action))
+            })
+            .collect();
+        let mean = |window: &[Surprise], f: fn(&Surprise) -> f64| window.iter().map(f).sum::<f64>() / window.len() as f64;
+        let (early, late) = (&surprises[..200], &surprises[4800..]);
+        let (before, after) = (mean(early, |s| s.disagreement), mean(late, |s| s.disagreement));
+        assert!(after < 0.25 * before, "disagreement went from {:.4} to {:.4}", before, after);
+        let (before, after) = (mean(early, |s| s.error), mean(late, |s| s.error));
+        assert!(after < 0.25 * before, "error went from {:.4} to {:.4}", before, after);
+    }
+}
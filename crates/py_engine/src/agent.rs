@@ -0,0 +1,163 @@
+//! `py_engine.Agent`: the `inference_engine` brains, pointed at a Simulation or Env.
+//!
+//! ```python
+//! sim = py_engine.Simulation("lorenz")
+//! agent = py_engine.Agent("qlearner")
+//! log = py_engine.run(sim, agent, steps=2000)
+//! plt.plot(log["rewards"])
+//! ```
+
+use crate::sim::PySimulation;
+use crate::{action_tuple, PyEnv};
+use inference_engine::{create_brain, create_brain_for, BrainType, Curiosity, DiscoveryEvent, Experimenter};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use sim_engine::{Action, ActionError, ActionSpace, ObservationSpace, Simulation};
+
+#[pyclass(name = "Agent", unsendable)]
+pub(crate) struct PyAgent {
+    brain: Box<dyn Experimenter>,
+    name: String,
+    steps: u64,
+    /// What the brain was last told, so it's only re-told on a change
+    observation_space: Option<ObservationSpace>,
+    action_space: Option<ActionSpace>,
+}
+
+#[pymethods]
+impl PyAgent {
+    /// "qlearner", "dqn", "actor-critic", "gardener", "evolution" or "mock";
+    /// the Q-learner takes a state abstraction after a colon ("qlearner:kdtree",
+    /// "qlearner:foveated"; tile coding by default). `sim` is the registry key
+    /// of the simulation it drives ("evolution" needs it). `curiosity` picks
+    /// the Q-learner's intrinsic reward: "disagreement" (the default),
+    /// "prediction-error", "learning-progress" or "tabular".
+    #[new]
+    #[pyo3(signature = (brain = "qlearner", sim = None, curiosity = None))]
+    fn new(brain: &str, sim: Option<&str>, curiosity: Option<&str>) -> PyResult<Self> {
+        let mut kind = BrainType::from_name(brain).ok_or_else(|| PyValueError::new_err(format!("unknown brain '{}'", brain)))?;
+        if let Some(name) = curiosity {
+            let scheme = Curiosity::from_name(name).ok_or_else(|| PyValueError::new_err(format!("unknown curiosity '{}'", name)))?;
+            match &mut kind {
+                BrainType::QLearner { curiosity, .. } => *curiosity = scheme,
+                _ => return Err(PyValueError::new_err(format!("'{}' takes no curiosity (only the Q-learner does)", brain))),
+            }
+        }
+        Ok(Self {
+            brain: match sim {
+                Some(key) => create_brain_for(kind, key),
+                None => create_brain(kind),
+            }
+            .map_err(PyValueError::new_err)?,
+            name: brain.into(),
+            steps: 0,
+            observation_space: None,
+            action_space: None,
+        })
+    }
+
+    /// Look at `world` (a Simulation or Env) and pick an action without
+    /// applying it: `(action, event)`, where the action is a tuple `step` and
+    /// `apply_action` accept and `event` is `{"topic", "content"}` or None.
+    fn act<'py>(&mut self, py: Python<'py>, world: &Bound<'py, PyAny>) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+        let (action, event) = with_world(world, |sim| self.think(sim))??;
+        Ok((action_tuple(py, &action)?, event_dict(py, event)?))
+    }
+
+    #[getter]
+    fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Agent('{}', step={})", self.name, self.steps)
+    }
+}
+
+impl PyAgent {
+    /// One Observe -> Think pass (the frontend session's loop minus the step).
+    fn think(&mut self, sim: &mut dyn Simulation) -> PyResult<(Action, Option<DiscoveryEvent>)> {
+        let exp = sim.as_experimentable().ok_or_else(|| PyTypeError::new_err("this simulation takes no actions"))?;
+        let obs_space = exp.observation_space();
+        if self.observation_space.as_ref() != Some(&obs_space) {
+            self.brain.set_observation_space(&(&obs_space).into());
+            self.observation_space = Some(obs_space);
+        }
+        let act_space = exp.action_space();
+        if self.action_space.as_ref() != Some(&act_space) {
+            self.brain.set_action_space(&act_space);
+            self.action_space = Some(act_space);
+        }
+        let (action, event) = self.brain.act(&exp.observe().into(), exp.reward(), self.steps);
+        self.steps += 1;
+        Ok((action, event))
+    }
+}
+
+fn with_world<R>(world: &Bound<'_, PyAny>, f: impl FnOnce(&mut dyn Simulation) -> R) -> PyResult<R> {
+    if let Ok(sim) = world.cast::<PySimulation>() {
+        Ok(f(&mut sim.borrow_mut().sim))
+    } else if let Ok(env) = world.cast::<PyEnv>() {
+        Ok(f(env.borrow_mut().env.sim_mut()))
+    } else {
+        Err(PyTypeError::new_err("expected a py_engine.Simulation or py_engine.Env"))
+    }
+}
+
+fn event_dict<'py>(py: Python<'py>, event: Option<DiscoveryEvent>) -> PyResult<Bound<'py, PyAny>> {
+    let (topic, content) = match event {
+        None => return Ok(py.None().into_bound(py)),
+        Some(DiscoveryEvent::Text(text)) => (None, text),
+        Some(DiscoveryEvent::Insight { topic, content }) => (Some(topic), content),
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("topic", topic)?;
+    dict.set_item("content", content)?;
+    Ok(dict.into_any())
+}
+
+/// Let `agent` drive `sim` for `steps` ticks (act, apply, step), the way the
+/// frontend does. Returns `{"rewards": [...], "events": [(step, event), ...],
+/// "rejected": [(step, action, reason), ...]}`; rewards are read before each act.
+#[pyfunction]
+pub(crate) fn run<'py>(
+    py: Python<'py>,
+    sim: &Bound<'py, PySimulation>,
+    agent: &Bound<'py, PyAgent>,
+    steps: u64,
+) -> PyResult<Bound<'py, PyDict>> {
+    let mut sim = sim.borrow_mut();
+    let mut agent = agent.borrow_mut();
+    let mut rewards = Vec::with_capacity(steps as usize);
+    let mut events = Vec::new();
+    let mut rejected: Vec<(u64, Action, ActionError)> = Vec::new();
+
+    for _ in 0..steps {
+        let step = sim.steps;
+        let (action, event) = agent.think(&mut sim.sim)?;
+        let exp = sim.sim.as_experimentable().expect("think checked this");
+        rewards.push(exp.reward());
+        if let Err(err) = exp.try_apply(action.clone()) {
+            rejected.push((step, action, err));
+        }
+        if let Some(event) = event {
+            events.push((step, event));
+        }
+        sim.tick();
+    }
+
+    let log = PyDict::new(py);
+    log.set_item("rewards", rewards)?;
+    let list = PyList::empty(py);
+    for (step, event) in events {
+        list.append((step, event_dict(py, Some(event))?))?;
+    }
+    log.set_item("events", list)?;
+    let list = PyList::empty(py);
+    for (step, action, err) in rejected {
+        list.append((step, action_tuple(py, &action)?, err.to_string()))?;
+    }
+    log.set_item("rejected", list)?;
+    Ok(log)
+}
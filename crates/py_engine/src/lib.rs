@@ -0,0 +1,301 @@
+//! Python bindings for the simulation registry and the agents.
+//!
+//! ```text
+//! cd crates/py_engine && maturin develop --release
+//! ```
+//!
+//! ```python
+//! import py_engine
+//! env = py_engine.Env("gray-scott", max_steps=500, params={"f": 0.03})
+//! obs, info = env.reset(seed=1)
+//! obs, reward, terminated, truncated, info = env.step(("perturb", 0, 0.3))
+//!
+//! sim = py_engine.Simulation("gray-scott", params={"k": 0.062})
+//! sim.step(100)
+//! field = sim.get_state()          # (h, w) float64
+//! ```
+//!
+//! `Env` is the Gymnasium-style episode API, `Simulation` the bare world, and
+//! `Agent` (see `agent.rs`) wraps the `inference_engine` brains.
+//!
+//! Actions are `None` (no-op), an int indexing `discrete_actions`, or a
+//! tuple: `("flip", r, c)`, `("perturb", which, delta)`, `("set", name, value)`.
+//! Observations come back as nested dicts of floats and NumPy arrays.
+
+mod agent;
+mod sim;
+
+use numpy::{PyArray1, PyArrayMethods, PyReadonlyArray3, PyUntypedArrayMethods};
+use pyo3::exceptions::{PyIndexError, PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyTuple};
+use sim_engine::env::{Env, StepInfo, Termination};
+use sim_engine::{registry, reward, Action, ActionSpace, Observation, ObservationSpace, ParamSpec, ParamValue};
+
+#[pyclass(name = "Env", unsendable)]
+struct PyEnv {
+    env: Env,
+    key: String,
+}
+
+#[pymethods]
+impl PyEnv {
+    /// `terminations` takes strings like "extinction", "saturation:0.9" or
+    /// "divergence:1e6"; an empty list disables early termination. `reward`
+    /// is a spec like "coverage:0.2" (default: the simulation's own).
+    #[new]
+    #[pyo3(signature = (key, max_steps = Some(1000), params = None, terminations = None, reward = None))]
+    fn new(
+        key: &str,
+        max_steps: Option<u64>,
+        params: Option<&Bound<'_, PyDict>>,
+        terminations: Option<Vec<String>>,
+        reward: Option<&str>,
+    ) -> PyResult<Self> {
+        let mut env = Env::new(key)
+            .ok_or_else(|| PyKeyError::new_err(format!("unknown simulation '{}'", key)))?
+            .with_max_steps(max_steps);
+        if let Some(specs) = terminations {
+            let parsed = specs
+                .iter()
+                .map(|s| Termination::parse(s).ok_or_else(|| PyValueError::new_err(format!("bad termination '{}'", s))))
+                .collect::<PyResult<Vec<_>>>()?;
+            env = env.with_terminations(parsed);
+        }
+        if let Some(spec) = reward {
+            reward::parse(spec).map_err(PyValueError::new_err)?;
+            env.set_param("reward", ParamValue::String(spec.into()));
+        }
+        if let Some(params) = params {
+            for (name, value) in params.iter() {
+                let name = name.extract::<String>()?;
+                env.set_param(&name, param_value(schema(key), &name, &value)?);
+            }
+        }
+        Ok(Self { env, key: key.into() })
+    }
+
+    #[pyo3(signature = (seed = None))]
+    fn reset<'py>(&mut self, py: Python<'py>, seed: Option<u64>) -> PyResult<Bound<'py, PyTuple>> {
+        let (obs, info) = self.env.reset(seed);
+        PyTuple::new(py, [observation(py, &obs)?, info_dict(py, &info)?])
+    }
+
+    /// `(obs, reward, terminated, truncated, info)`
+    #[pyo3(signature = (action = None))]
+    fn step<'py>(&mut self, py: Python<'py>, action: Option<&Bound<'py, PyAny>>) -> PyResult<Bound<'py, PyTuple>> {
+        let action = match action {
+            Some(action) => parse_action(action, &self.env.action_space())?,
+            None => Action::Noop,
+        };
+        let step = self.env.step(action);
+        PyTuple::new(
+            py,
+            [
+                observation(py, &step.observation)?,
+                step.reward.into_pyobject(py)?.into_any(),
+                step.terminated.into_pyobject(py)?.to_owned().into_any(),
+                step.truncated.into_pyobject(py)?.to_owned().into_any(),
+                info_dict(py, &step.info)?,
+            ],
+        )
+    }
+
+    /// Set a parameter now and on every later reset ("reward" and
+    /// "reward.<knob>" included; a bad one raises ValueError).
+    fn set_param(&mut self, name: &str, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        let value = param_value(schema(&self.key), name, value)?;
+        if let ("reward", ParamValue::String(spec)) = (name, &value) {
+            // Check before it's stored for replay
+            reward::parse(spec).map_err(PyValueError::new_err)?;
+        }
+        self.env.set_param(name, value);
+        match self.env.reward() {
+            (_, Some(err)) if name.starts_with("reward") => Err(PyValueError::new_err(err.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Shapes of the observation: `()` for scalars, `(n,)` for vectors, the
+    /// tensor shape for fields, nested dicts for named entries.
+    #[getter]
+    fn observation_space<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        space(py, &self.env.observation_space())
+    }
+
+    /// `{"flip_cell": (rows, cols) | None, "perturb": {...} | None, "params": [...], "box": [(name, low, high), ...]}`
+    #[getter]
+    fn action_space<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        action_space(py, &self.env.action_space())
+    }
+
+    /// The fixed menu integer actions index into.
+    #[getter]
+    fn discrete_actions<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let actions = self.env.action_space().discrete();
+        let items = actions.iter().map(|a| action_tuple(py, a)).collect::<PyResult<Vec<_>>>()?;
+        PyList::new(py, items)
+    }
+
+    #[getter]
+    fn episode(&self) -> u64 {
+        self.env.episode()
+    }
+
+    #[getter]
+    fn steps(&self) -> u64 {
+        self.env.steps()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Env('{}', episode={}, step={})", self.key, self.env.episode(), self.env.steps())
+    }
+}
+
+// --- Conversions ---
+
+/// `None`, an index into `space.discrete()`, or a `("noop" | "flip" | "perturb" | "set", ...)` tuple
+fn parse_action(action: &Bound<'_, PyAny>, space: &ActionSpace) -> PyResult<Action> {
+    if action.is_none() {
+        return Ok(Action::Noop);
+    }
+    if let Ok(index) = action.extract::<usize>() {
+        let menu = space.discrete();
+        return menu
+            .get(index)
+            .cloned()
+            .ok_or_else(|| PyIndexError::new_err(format!("action {} out of range (0..{})", index, menu.len())));
+    }
+    let tuple = action.cast::<PyTuple>()?;
+    let kind: String = tuple.get_item(0)?.extract()?;
+    Ok(match kind.as_str() {
+        "noop" => Action::Noop,
+        "flip" => Action::FlipCell { r: tuple.get_item(1)?.extract()?, c: tuple.get_item(2)?.extract()? },
+        "perturb" => Action::Perturb { which: tuple.get_item(1)?.extract()?, delta: tuple.get_item(2)?.extract()? },
+        "set" => Action::SetParam { name: tuple.get_item(1)?.extract()?, value: tuple.get_item(2)?.extract()? },
+        other => return Err(PyValueError::new_err(format!("unknown action '{}' (noop, flip, perturb, set)", other))),
+    })
+}
+
+/// Parameters of registry simulation `key` (none if it isn't one)
+fn schema(key: &str) -> &'static [ParamSpec] {
+    registry::find(key).map_or(&[][..], |info| info.schema)
+}
+
+/// bool, number, str or an (h, w, 4) uint8 image (a Python bool is also an
+/// int, so it goes first). Numbers are typed by `schema`, not by Python: an
+/// int for a float parameter arrives as Float, a float for an int one as Int.
+fn param_value(schema: &[ParamSpec], name: &str, value: &Bound<'_, PyAny>) -> PyResult<ParamValue> {
+    if let Ok(b) = value.extract::<bool>() {
+        Ok(ParamValue::Bool(b))
+    } else if let Ok(f) = value.extract::<f64>() {
+        Ok(sim_engine::param_value(schema, name, f))
+    } else if let Ok(s) = value.extract::<String>() {
+        Ok(ParamValue::String(s))
+    } else if let Ok(image) = value.extract::<PyReadonlyArray3<u8>>() {
+        match *image.shape() {
+            [height, width, 4] => Ok(ParamValue::Image { width, height, rgba: image.as_array().iter().copied().collect() }),
+            _ => Err(PyValueError::new_err("images must be (h, w, 4) uint8 RGBA arrays")),
+        }
+    } else {
+        Err(PyValueError::new_err("parameter values must be bool, int, float, str or an RGBA image"))
+    }
+}
+
+fn observation<'py>(py: Python<'py>, obs: &Observation) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match obs {
+        Observation::Scalar(v) => v.into_pyobject(py)?.into_any(),
+        Observation::Vector(v) => PyArray1::from_slice(py, v).into_any(),
+        Observation::Tensor { shape, data } => PyArray1::from_slice(py, data).reshape(shape.clone())?.into_any(),
+        Observation::Dict(entries) => {
+            let dict = PyDict::new(py);
+            for (name, value) in entries {
+                dict.set_item(name, observation(py, value)?)?;
+            }
+            dict.into_any()
+        }
+        Observation::Text(text) => text.into_pyobject(py)?.into_any(),
+        Observation::None => py.None().into_bound(py),
+    })
+}
+
+fn space<'py>(py: Python<'py>, space: &ObservationSpace) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match space {
+        ObservationSpace::Scalar => PyTuple::empty(py).into_any(),
+        ObservationSpace::Vector(n) => PyTuple::new(py, [*n])?.into_any(),
+        ObservationSpace::Tensor(shape) => PyTuple::new(py, shape)?.into_any(),
+        ObservationSpace::Dict(entries) => {
+            let dict = PyDict::new(py);
+            for (name, value) in entries {
+                dict.set_item(name, self::space(py, value)?)?;
+            }
+            dict.into_any()
+        }
+        ObservationSpace::Text => "text".into_pyobject(py)?.into_any(),
+        ObservationSpace::None => py.None().into_bound(py),
+    })
+}
+
+fn action_space<'py>(py: Python<'py>, space: &ActionSpace) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("flip_cell", space.flip_cell)?;
+    let perturb = match &space.perturb {
+        Some(p) => {
+            let d = PyDict::new(py);
+            d.set_item("targets", p.targets)?;
+            d.set_item("low", p.low)?;
+            d.set_item("high", p.high)?;
+            d.set_item("label", &p.label)?;
+            d.into_any()
+        }
+        None => py.None().into_bound(py),
+    };
+    dict.set_item("perturb", perturb)?;
+    let params = PyList::empty(py);
+    for p in &space.params {
+        let d = PyDict::new(py);
+        d.set_item("name", &p.name)?;
+        d.set_item("low", p.low)?;
+        d.set_item("high", p.high)?;
+        d.set_item("integer", p.integer)?;
+        params.append(d)?;
+    }
+    dict.set_item("params", params)?;
+    let dims: Vec<(String, f64, f64)> = space.dimensions().into_iter().map(|d| (d.name, d.low, d.high)).collect();
+    dict.set_item("box", dims)?;
+    Ok(dict)
+}
+
+fn action_tuple<'py>(py: Python<'py>, action: &Action) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match action {
+        Action::Noop => ("noop",).into_pyobject(py)?.into_any(),
+        Action::FlipCell { r, c } => ("flip", *r, *c).into_pyobject(py)?.into_any(),
+        Action::Perturb { which, delta } => ("perturb", *which, *delta).into_pyobject(py)?.into_any(),
+        Action::SetParam { name, value } => ("set", name.as_str(), *value).into_pyobject(py)?.into_any(),
+    })
+}
+
+fn info_dict<'py>(py: Python<'py>, info: &StepInfo) -> PyResult<Bound<'py, PyAny>> {
+    let dict = PyDict::new(py);
+    dict.set_item("episode", info.episode)?;
+    dict.set_item("step", info.step)?;
+    dict.set_item("terminated_by", info.terminated_by.map(|t| t.name()))?;
+    dict.set_item("rejected", info.rejected.as_ref().map(|e| e.to_string()))?;
+    Ok(dict.into_any())
+}
+
+/// `[(key, name, category), ...]` for every registered simulation
+#[pyfunction]
+fn simulations() -> Vec<(&'static str, &'static str, &'static str)> {
+    registry::SIMULATIONS.iter().map(|info| (info.key, info.name, info.category)).collect()
+}
+
+#[pymodule]
+fn py_engine(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyEnv>()?;
+    m.add_class::<sim::PySimulation>()?;
+    m.add_class::<agent::PyAgent>()?;
+    m.add_function(wrap_pyfunction!(simulations, m)?)?;
+    m.add_function(wrap_pyfunction!(agent::run, m)?)?;
+    Ok(())
+}
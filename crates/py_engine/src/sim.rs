@@ -0,0 +1,187 @@
+//! `py_engine.Simulation`: one registered world, driven step by step.
+
+use crate::{action_space, action_tuple, observation, param_value, parse_action, space};
+use numpy::{PyArray1, PyArrayMethods};
+use pyo3::exceptions::{PyKeyError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use sim_engine::reward::Rewarded;
+use sim_engine::{registry, Experimentable, ParamKind, ParamSpec, SimState, Simulation};
+
+#[pyclass(name = "Simulation", unsendable)]
+pub(crate) struct PySimulation {
+    pub(crate) sim: Rewarded,
+    key: &'static str,
+    schema: &'static [ParamSpec],
+    pub(crate) steps: u64,
+}
+
+#[pymethods]
+impl PySimulation {
+    /// `reward` takes a spec such as "coverage:0.2" or "0.5*entropy + 0.5*stability"
+    /// (default: the simulation's own reward).
+    #[new]
+    #[pyo3(signature = (key, params = None, reward = None))]
+    fn new(key: &str, params: Option<&Bound<'_, PyDict>>, reward: Option<&str>) -> PyResult<Self> {
+        let info = registry::find(key).ok_or_else(|| PyKeyError::new_err(format!("unknown simulation '{}'", key)))?;
+        let mut sim = Self { sim: Rewarded::wrap(info.build()), key: info.key, schema: info.schema, steps: 0 };
+        if let Some(spec) = reward {
+            sim.sim.set_reward_spec(spec).map_err(PyValueError::new_err)?;
+        }
+        if let Some(params) = params {
+            for (name, value) in params.iter() {
+                sim.set_param(&name.extract::<String>()?, &value)?;
+            }
+        }
+        Ok(sim)
+    }
+
+    /// Advance `n` steps.
+    #[pyo3(signature = (n = 1))]
+    fn step(&mut self, n: u64) {
+        for _ in 0..n {
+            self.tick();
+        }
+    }
+
+    /// Also takes "reward" (a spec) and "reward.<knob>"; a bad one raises ValueError.
+    fn set_param(&mut self, name: &str, value: &Bound<'_, PyAny>) -> PyResult<()> {
+        self.sim.set_param(name, param_value(self.schema, name, value)?);
+        match &self.sim.error {
+            Some(err) if name.starts_with("reward") => Err(PyValueError::new_err(err.clone())),
+            _ => Ok(()),
+        }
+    }
+
+    /// The world as NumPy: `(h, w)` float64 for fields, `(h, w)` bool for
+    /// cell grids, `(n, 3)` for point sets, and a dict of arrays for particles.
+    fn get_state<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        state(py, &self.sim.get_state())
+    }
+
+    fn observe<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        observation(py, &self.experimentable()?.observe())
+    }
+
+    fn reward(&mut self) -> PyResult<f64> {
+        Ok(self.experimentable()?.reward())
+    }
+
+    /// Same forms as `Env.step`; raises ValueError if the world refuses it.
+    fn apply_action(&mut self, action: &Bound<'_, PyAny>) -> PyResult<()> {
+        let exp = self.experimentable()?;
+        let action = parse_action(action, &exp.action_space())?;
+        exp.try_apply(action).map_err(|err| PyValueError::new_err(err.to_string()))
+    }
+
+    #[getter]
+    fn observation_space<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        space(py, &self.experimentable()?.observation_space())
+    }
+
+    #[getter]
+    fn action_space<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        action_space(py, &self.experimentable()?.action_space())
+    }
+
+    #[getter]
+    fn discrete_actions<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let actions = self.experimentable()?.action_space().discrete();
+        let items = actions.iter().map(|a| action_tuple(py, a)).collect::<PyResult<Vec<_>>>()?;
+        PyList::new(py, items)
+    }
+
+    /// `[{"name", "description", "kind", "default", ...}, ...]`: numbers add
+    /// "min", "max" and "integer", choices add "choices"
+    #[getter]
+    fn schema<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyList>> {
+        let list = PyList::empty(py);
+        for spec in self.schema {
+            let d = PyDict::new(py);
+            d.set_item("name", spec.name)?;
+            d.set_item("description", spec.description)?;
+            match spec.kind {
+                ParamKind::Number => {
+                    d.set_item("kind", "number")?;
+                    d.set_item("default", spec.default)?;
+                    d.set_item("min", spec.min)?;
+                    d.set_item("max", spec.max)?;
+                    d.set_item("integer", spec.integer)?;
+                }
+                ParamKind::Choice(choices) => {
+                    d.set_item("kind", "choice")?;
+                    d.set_item("default", spec.default_text)?;
+                    d.set_item("choices", choices.to_vec())?;
+                }
+                ParamKind::Text => {
+                    d.set_item("kind", "text")?;
+                    d.set_item("default", spec.default_text)?;
+                }
+            }
+            list.append(d)?;
+        }
+        Ok(list)
+    }
+
+    /// The reward function in use, as a spec
+    #[getter]
+    fn reward_spec(&self) -> String {
+        self.sim.reward_spec()
+    }
+
+    #[getter]
+    fn key(&self) -> &'static str {
+        self.key
+    }
+
+    #[getter]
+    fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Simulation('{}', step={})", self.key, self.steps)
+    }
+}
+
+impl PySimulation {
+    pub(crate) fn tick(&mut self) {
+        self.sim.step();
+        self.steps += 1;
+    }
+
+    fn experimentable(&mut self) -> PyResult<&mut dyn Experimentable> {
+        let key = self.key;
+        self.sim
+            .as_experimentable()
+            .ok_or_else(|| PyTypeError::new_err(format!("'{}' takes no actions and has no observations", key)))
+    }
+}
+
+fn state<'py>(py: Python<'py>, state: &SimState) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match state {
+        SimState::FloatGrid { width, height, values } => {
+            PyArray1::from_slice(py, values).reshape([*height as usize, *width as usize])?.into_any()
+        }
+        SimState::Grid { width, height, cells, .. } => {
+            PyArray1::from_slice(py, cells).reshape([*height as usize, *width as usize])?.into_any()
+        }
+        SimState::Points(points) => {
+            let flat: Vec<f64> = points.iter().flat_map(|&(x, y, z)| [x, y, z]).collect();
+            PyArray1::from_vec(py, flat).reshape([points.len(), 3])?.into_any()
+        }
+        SimState::Particles { width, height, positions, velocities, species } => {
+            let pairs = |v: &[(f64, f64)]| -> PyResult<Bound<'py, PyAny>> {
+                let flat: Vec<f64> = v.iter().flat_map(|&(x, y)| [x, y]).collect();
+                Ok(PyArray1::from_vec(py, flat).reshape([v.len(), 2])?.into_any())
+            };
+            let dict = PyDict::new(py);
+            dict.set_item("width", width)?;
+            dict.set_item("height", height)?;
+            dict.set_item("positions", pairs(positions)?)?;
+            dict.set_item("velocities", pairs(velocities)?)?;
+            dict.set_item("species", PyArray1::from_slice(py, species))?;
+            dict.into_any()
+        }
+    })
+}
@@ -0,0 +1,291 @@
+//! What an agent may do to a simulation, and a descriptor of which actions a
+//! given simulation accepts.
+//!
+//! `ActionSpace` is the parameterised form (FlipCell bounds, Perturb targets
+//! and delta range, settable parameters with their ranges). Agents that want
+//! something flatter can ask it for a discrete menu (`discrete`) or a
+//! continuous box with one dimension per knob (`dimensions` / `box_action`).
+
+use crate::ParamSpec;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Action {
+    FlipCell { r: usize, c: usize },
+    Perturb { which: u8, delta: f64 },
+    SetParam { name: String, value: f64 },
+    Noop,
+}
+
+/// `Perturb { which, delta }` with `which < targets` and `delta` in `[low, high]`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PerturbSpace {
+    pub targets: usize,
+    pub low: f64,
+    pub high: f64,
+    /// What `which` picks, for menus and logs ("channel", "body", ...)
+    pub label: String,
+}
+
+/// `SetParam { name, value }` with `value` in `[low, high]`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ParamRange {
+    pub name: String,
+    pub low: f64,
+    pub high: f64,
+    /// Rounded to a whole number before it reaches `set_param`
+    pub integer: bool,
+}
+
+/// The actions one simulation accepts right now. `Noop` is always valid.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ActionSpace {
+    /// `FlipCell { r, c }` with `r < rows`, `c < cols`
+    pub flip_cell: Option<(usize, usize)>,
+    pub perturb: Option<PerturbSpace>,
+    pub params: Vec<ParamRange>,
+}
+
+/// One axis of the continuous view of an action space
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dimension {
+    pub name: String,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl ActionSpace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_flip_cell(mut self, rows: usize, cols: usize) -> Self {
+        self.flip_cell = Some((rows, cols));
+        self
+    }
+
+    pub fn with_perturb(mut self, label: &str, targets: usize, low: f64, high: f64) -> Self {
+        self.perturb = Some(PerturbSpace { targets, low: low.min(high), high: low.max(high), label: label.into() });
+        self
+    }
+
+    /// Every numeric entry of a schema becomes a settable parameter.
+    pub fn with_params(mut self, schema: &[ParamSpec]) -> Self {
+        self.params.extend(schema.iter().filter(|spec| spec.is_numeric()).map(|spec| ParamRange {
+            name: spec.name.into(),
+            low: spec.min,
+            high: spec.max,
+            integer: spec.integer,
+        }));
+        self
+    }
+
+    /// Only the named entries of a schema.
+    pub fn with_params_named(self, schema: &[ParamSpec], names: &[&str]) -> Self {
+        let picked: Vec<ParamSpec> = schema.iter().filter(|spec| names.contains(&spec.name)).cloned().collect();
+        self.with_params(&picked)
+    }
+
+    pub fn param(&self, name: &str) -> Option<&ParamRange> {
+        self.params.iter().find(|p| p.name == name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.flip_cell.is_none() && self.perturb.is_none() && self.params.is_empty()
+    }
+
+    /// Ok if the simulation would act on `action`; otherwise why not.
+    pub fn check(&self, action: &Action) -> Result<(), ActionError> {
+        match action {
+            Action::Noop => Ok(()),
+            Action::FlipCell { r, c } => match self.flip_cell {
+                None => Err(ActionError::Unsupported("FlipCell")),
+                Some((rows, cols)) if *r >= rows || *c >= cols => {
+                    Err(ActionError::CellOutOfBounds { r: *r, c: *c, rows, cols })
+                }
+                Some(_) => Ok(()),
+            },
+            Action::Perturb { which, delta } => {
+                let Some(p) = &self.perturb else { return Err(ActionError::Unsupported("Perturb")) };
+                if *which as usize >= p.targets {
+                    Err(ActionError::TargetOutOfRange { which: *which, targets: p.targets })
+                } else if !in_range(*delta, p.low, p.high) {
+                    Err(ActionError::OutOfRange { name: "delta".into(), value: *delta, low: p.low, high: p.high })
+                } else {
+                    Ok(())
+                }
+            }
+            Action::SetParam { name, value } => {
+                let Some(p) = self.param(name) else { return Err(ActionError::UnknownParam(name.clone())) };
+                if in_range(*value, p.low, p.high) {
+                    Ok(())
+                } else {
+                    Err(ActionError::OutOfRange { name: name.clone(), value: *value, low: p.low, high: p.high })
+                }
+            }
+        }
+    }
+
+    /// A small fixed menu: `Noop`, then flips on a coarse grid of cells (at most
+    /// 4×4, one at the centre of each tile), then a kick of ±half the delta
+    /// range at each Perturb target (at most 16), then each bounded parameter
+    /// set to a quarter and three quarters of its range.
+    pub fn discrete(&self) -> Vec<Action> {
+        let mut actions = vec![Action::Noop];
+        if let Some((rows, cols)) = self.flip_cell {
+            actions.extend(flip_grid(rows, cols).map(|(r, c)| Action::FlipCell { r, c }));
+        }
+        if let Some(p) = &self.perturb {
+            let (down, up) = (0.5 * p.low, 0.5 * p.high);
+            for which in 0..p.targets.min(16) as u8 {
+                actions.extend([up, down].into_iter().filter(|d| *d != 0.0).map(|delta| Action::Perturb { which, delta }));
+            }
+        }
+        for p in self.bounded_params() {
+            for t in [0.25, 0.75] {
+                actions.push(Action::SetParam { name: p.name.clone(), value: round_if(p.integer, p.low + t * (p.high - p.low)) });
+            }
+        }
+        actions
+    }
+
+    /// Continuous view: one axis per Perturb target (its delta) and per
+    /// bounded parameter (its value).
+    pub fn dimensions(&self) -> Vec<Dimension> {
+        let mut dims = Vec::new();
+        if let Some(p) = &self.perturb {
+            dims.extend((0..p.targets.min(u8::MAX as usize + 1)).map(|i| Dimension {
+                name: format!("{}[{}]", p.label, i),
+                low: p.low,
+                high: p.high,
+            }));
+        }
+        dims.extend(self.bounded_params().map(|p| Dimension {
+            name: p.name.clone(),
+            low: p.low,
+            high: p.high,
+        }));
+        dims
+    }
+
+    /// The action that moves axis `dim` of `dimensions()` to `value` (clamped).
+    pub fn box_action(&self, dim: usize, value: f64) -> Option<Action> {
+        let perturb_dims = self.perturb.as_ref().map_or(0, |p| p.targets.min(u8::MAX as usize + 1));
+        if dim < perturb_dims {
+            let p = self.perturb.as_ref()?;
+            return Some(Action::Perturb { which: dim as u8, delta: value.clamp(p.low, p.high) });
+        }
+        let p = self.bounded_params().nth(dim - perturb_dims)?;
+        Some(Action::SetParam { name: p.name.clone(), value: round_if(p.integer, value.clamp(p.low, p.high)) })
+    }
+
+    /// Parameters worth sweeping: seeds and the like (ranges up to i64::MAX) are left out
+    fn bounded_params(&self) -> impl Iterator<Item = &ParamRange> {
+        self.params.iter().filter(|p| p.high - p.low < 1e12)
+    }
+}
+
+/// Centres of an (at most) 4×4 tiling of a rows×cols grid
+fn flip_grid(rows: usize, cols: usize) -> impl Iterator<Item = (usize, usize)> {
+    let (tr, tc) = (rows.min(4), cols.min(4));
+    (0..tr).flat_map(move |i| (0..tc).map(move |j| ((2 * i + 1) * rows / (2 * tr), (2 * j + 1) * cols / (2 * tc))))
+}
+
+fn in_range(value: f64, low: f64, high: f64) -> bool {
+    value.is_finite() && value >= low && value <= high
+}
+
+fn round_if(integer: bool, value: f64) -> f64 {
+    if integer { value.round() } else { value }
+}
+
+/// Why a simulation turned an action down.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionError {
+    /// The simulation takes no actions of this kind
+    Unsupported(&'static str),
+    CellOutOfBounds { r: usize, c: usize, rows: usize, cols: usize },
+    TargetOutOfRange { which: u8, targets: usize },
+    UnknownParam(String),
+    /// Perturb delta or parameter value outside its range (or not finite)
+    OutOfRange { name: String, value: f64, low: f64, high: f64 },
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionError::Unsupported(kind) => write!(f, "{} is not supported here", kind),
+            ActionError::CellOutOfBounds { r, c, rows, cols } => {
+                write!(f, "cell ({}, {}) is outside the {}×{} grid", r, c, rows, cols)
+            }
+            ActionError::TargetOutOfRange { which, targets } => {
+                write!(f, "perturb target {} out of range (0..{})", which, targets)
+            }
+            ActionError::UnknownParam(name) => write!(f, "unknown parameter '{}'", name),
+            ActionError::OutOfRange { name, value, low, high } => {
+                write!(f, "{} = {} outside [{}, {}]", name, value, low, high)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ActionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn space() -> ActionSpace {
+        ActionSpace::new().with_flip_cell(4, 5).with_perturb("channel", 2, -1.0, 1.0).with_params(&[
+            ParamSpec::float("f", "Feed", 0.05, 0.0, 0.1),
+            ParamSpec::int("seed", "Seed", 0, 0, 10),
+            ParamSpec::text("preset", "Preset", ""),
+        ])
+    }
+
+    fn set(name: &str, value: f64) -> Action {
+        Action::SetParam { name: name.into(), value }
+    }
+
+    #[test]
+    fn check_accepts_actions_in_the_space() {
+        let cases = [
+            Action::Noop,
+            Action::FlipCell { r: 0, c: 0 },
+            Action::FlipCell { r: 3, c: 4 },
+            Action::Perturb { which: 1, delta: -1.0 },
+            Action::Perturb { which: 0, delta: 1.0 },
+            set("f", 0.0),
+            set("f", 0.1),
+            set("seed", 10.0),
+        ];
+        for action in cases {
+            assert_eq!(space().check(&action), Ok(()), "{:?}", action);
+        }
+    }
+
+    #[test]
+    fn check_refuses_actions_outside_the_space() {
+        let out_of_range = |name: &str, value, low, high| ActionError::OutOfRange { name: name.into(), value, low, high };
+        let cases = [
+            (Action::FlipCell { r: 4, c: 0 }, ActionError::CellOutOfBounds { r: 4, c: 0, rows: 4, cols: 5 }),
+            (Action::FlipCell { r: 0, c: 5 }, ActionError::CellOutOfBounds { r: 0, c: 5, rows: 4, cols: 5 }),
+            (Action::Perturb { which: 2, delta: 0.0 }, ActionError::TargetOutOfRange { which: 2, targets: 2 }),
+            (Action::Perturb { which: 0, delta: 1.5 }, out_of_range("delta", 1.5, -1.0, 1.0)),
+            (Action::Perturb { which: 0, delta: f64::INFINITY }, out_of_range("delta", f64::INFINITY, -1.0, 1.0)),
+            (set("f", 0.2), out_of_range("f", 0.2, 0.0, 0.1)),
+            (set("seed", -1.0), out_of_range("seed", -1.0, 0.0, 10.0)),
+            (set("k", 0.06), ActionError::UnknownParam("k".into())),
+            (set("preset", 0.0), ActionError::UnknownParam("preset".into())),
+        ];
+        for (action, expected) in cases {
+            assert_eq!(space().check(&action), Err(expected), "{:?}", action);
+        }
+
+        let empty = ActionSpace::new();
+        assert_eq!(empty.check(&Action::Noop), Ok(()));
+        assert_eq!(empty.check(&Action::FlipCell { r: 0, c: 0 }), Err(ActionError::Unsupported("FlipCell")));
+        assert_eq!(empty.check(&Action::Perturb { which: 0, delta: 0.0 }), Err(ActionError::Unsupported("Perturb")));
+    }
+}
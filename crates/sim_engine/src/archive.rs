@@ -0,0 +1,319 @@
+//! MAP-Elites style archive of everything interesting found so far.
+//!
+//! Each simulation's behaviours are placed on a `bins`×`bins` grid by a
+//! two-number descriptor in [0, 1]²; each grid cell keeps only its fittest
+//! occupant (the elite), together with the parameters that reproduce it and
+//! a small PNG thumbnail. Descriptors by state kind:
+//!
+//! - float grids (Gray-Scott and other fields): coverage × spatial frequency
+//! - cell grids (Life and other CA): population density × period
+//! - particles: spread × mean speed
+//!
+//! The archive serialises with serde, so it can live in localStorage or a file.
+
+use crate::thumbnail::{encode_png, render_rgb};
+use crate::{ParamValue, SimState};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+/// Longest side of an archived thumbnail, in pixels
+const THUMBNAIL_SIDE: u32 = 32;
+
+/// Longest period `PeriodDetector` can see
+pub const MAX_PERIOD: usize = 32;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Elite {
+    /// Registry key of the simulation
+    pub sim: String,
+    /// What to `set_param` on a fresh build to get back here, in order
+    pub params: Vec<(String, ParamValue)>,
+    pub fitness: f64,
+    pub descriptor: [f64; 2],
+    /// Who found it (a brain name, "evolution", ...)
+    pub source: String,
+    /// Simulation step at which it was recorded
+    pub step: u64,
+    /// `data:image/png;base64,...`
+    pub thumbnail: Option<String>,
+    /// Cell flips or kicks shaped the run, so `params` alone don't rebuild it
+    #[serde(default)]
+    pub view_only: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EliteArchive {
+    pub bins: usize,
+    elites: Vec<Elite>,
+}
+
+impl Default for EliteArchive {
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
+
+impl EliteArchive {
+    pub fn new(bins: usize) -> Self {
+        Self { bins: bins.max(1), elites: Vec::new() }
+    }
+
+    /// Grid cell (row, col) of a descriptor: row from the second axis, col from the first
+    pub fn cell(&self, descriptor: [f64; 2]) -> (usize, usize) {
+        let bin = |v: f64| ((v.clamp(0.0, 1.0) * self.bins as f64) as usize).min(self.bins - 1);
+        (bin(descriptor[1]), bin(descriptor[0]))
+    }
+
+    /// Keep `elite` if its cell is empty or it beats the occupant. Returns whether it was kept.
+    pub fn insert(&mut self, elite: Elite) -> bool {
+        let cell = self.cell(elite.descriptor);
+        match self.elites.iter().position(|e| e.sim == elite.sim && self.cell(e.descriptor) == cell) {
+            Some(i) if self.elites[i].fitness >= elite.fitness => false,
+            Some(i) => {
+                self.elites[i] = elite;
+                true
+            }
+            None => {
+                self.elites.push(elite);
+                true
+            }
+        }
+    }
+
+    pub fn get(&self, sim: &str, cell: (usize, usize)) -> Option<&Elite> {
+        self.elites.iter().find(|e| e.sim == sim && self.cell(e.descriptor) == cell)
+    }
+
+    /// Every elite of one simulation
+    pub fn for_sim<'a>(&'a self, sim: &'a str) -> impl Iterator<Item = &'a Elite> + 'a {
+        self.elites.iter().filter(move |e| e.sim == sim)
+    }
+
+    pub fn len(&self) -> usize {
+        self.elites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.elites.is_empty()
+    }
+}
+
+/// Names of the two descriptor axes for a state
+pub fn axes(state: &SimState) -> Option<[&'static str; 2]> {
+    match state {
+        SimState::FloatGrid { .. } => Some(["coverage", "spatial frequency"]),
+        SimState::Grid { .. } => Some(["population", "period"]),
+        SimState::Particles { .. } => Some(["spread", "speed"]),
+        SimState::Points(_) => None,
+    }
+}
+
+/// Where a state sits in behaviour space. `period` (cell grids only) comes
+/// from a `PeriodDetector`; None means no repeat was seen.
+pub fn descriptor(state: &SimState, period: Option<usize>) -> Option<[f64; 2]> {
+    match state {
+        SimState::FloatGrid { width, height, values } => {
+            let (w, h) = (*width as usize, *height as usize);
+            if w * h == 0 || values.len() < w * h {
+                return None;
+            }
+            let mean = values.iter().sum::<f64>() / values.len() as f64;
+            // Share of neighbouring pairs on opposite sides of the mean: 0 for blobs, ~1 for fine stripes
+            let above = |i: usize| values[i] > mean;
+            let (mut crossings, mut pairs) = (0usize, 0usize);
+            for y in 0..h {
+                for x in 0..w {
+                    let i = y * w + x;
+                    if x + 1 < w {
+                        crossings += usize::from(above(i) != above(i + 1));
+                        pairs += 1;
+                    }
+                    if y + 1 < h {
+                        crossings += usize::from(above(i) != above(i + w));
+                        pairs += 1;
+                    }
+                }
+            }
+            Some([mean.clamp(0.0, 1.0), crossings as f64 / pairs.max(1) as f64])
+        }
+        SimState::Grid { cells, .. } => {
+            if cells.is_empty() {
+                return None;
+            }
+            // Life rarely fills more than half its cells, so density is doubled
+            let density = cells.iter().filter(|&&alive| alive).count() as f64 / cells.len() as f64;
+            let period = period.map_or(1.0, |p| (p.saturating_sub(1) as f64 / MAX_PERIOD as f64).min(1.0));
+            Some([(2.0 * density).min(1.0), period])
+        }
+        SimState::Particles { width, height, positions, velocities, .. } => {
+            if positions.is_empty() {
+                return None;
+            }
+            let n = positions.len() as f64;
+            let (cx, cy) = positions.iter().fold((0.0, 0.0), |(sx, sy), (x, y)| (sx + x / n, sy + y / n));
+            // RMS distance from the centroid, against that of a uniform spread over the box
+            let rms = (positions.iter().map(|(x, y)| (x - cx).powi(2) + (y - cy).powi(2)).sum::<f64>() / n).sqrt();
+            let uniform = ((width * width + height * height) / 12.0).sqrt().max(1e-9);
+            let speed = velocities.iter().map(|(vx, vy)| (vx * vx + vy * vy).sqrt()).sum::<f64>() / n;
+            Some([(rms / uniform).min(1.0), speed / (1.0 + speed)])
+        }
+        SimState::Points(_) => None,
+    }
+}
+
+/// Spots repeating cell-grid states over the last `MAX_PERIOD` pushes.
+#[derive(Clone, Debug, Default)]
+pub struct PeriodDetector {
+    hashes: VecDeque<u64>,
+}
+
+impl PeriodDetector {
+    /// Record a state; returns the period if it repeats one seen recently.
+    pub fn push(&mut self, state: &SimState) -> Option<usize> {
+        let SimState::Grid { cells, .. } = state else { return None };
+        let mut hasher = DefaultHasher::new();
+        cells.hash(&mut hasher);
+        let hash = hasher.finish();
+        let period = self.hashes.iter().rev().position(|&h| h == hash).map(|i| i + 1);
+        self.hashes.push_back(hash);
+        if self.hashes.len() > MAX_PERIOD {
+            self.hashes.pop_front();
+        }
+        period
+    }
+}
+
+/// A small PNG of the state as a data URL, for archive grids.
+pub fn thumbnail(state: &SimState) -> Option<String> {
+    let (w, h, rgb) = render_rgb(state)?;
+    if w == 0 || h == 0 {
+        return None;
+    }
+    // Nearest-neighbour down to THUMBNAIL_SIDE on the longer side
+    let scale = (w.max(h) as f64 / THUMBNAIL_SIDE as f64).max(1.0);
+    let (tw, th) = (((w as f64 / scale) as u32).max(1), ((h as f64 / scale) as u32).max(1));
+    let mut small = Vec::with_capacity((tw * th * 3) as usize);
+    for y in 0..th {
+        for x in 0..tw {
+            let (sx, sy) = (((x as f64 * scale) as u32).min(w - 1), ((y as f64 * scale) as u32).min(h - 1));
+            let i = ((sy * w + sx) * 3) as usize;
+            small.extend_from_slice(&rgb[i..i + 3]);
+        }
+    }
+    Some(format!("data:image/png;base64,{}", base64(&encode_png(tw, th, &small))))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = ((chunk[0] as u32) << 16) | ((*chunk.get(1).unwrap_or(&0) as u32) << 8) | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[((n >> (18 - 6 * i)) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elite(fitness: f64, descriptor: [f64; 2]) -> Elite {
+        Elite {
+            sim: "gol".into(),
+            params: Vec::new(),
+            fitness,
+            descriptor,
+            source: "mock".into(),
+            step: 0,
+            thumbnail: None,
+            view_only: false,
+        }
+    }
+
+    fn grid(width: u32, height: u32, alive: &[(u32, u32)]) -> SimState {
+        let mut cells = vec![false; (width * height) as usize];
+        for &(x, y) in alive {
+            cells[(y * width + x) as usize] = true;
+        }
+        SimState::Grid { offset_x: 0, offset_y: 0, width, height, cells }
+    }
+
+    #[test]
+    fn insert_keeps_the_fitter_elite_per_cell() {
+        let mut archive = EliteArchive::new(10);
+        assert!(archive.insert(elite(1.0, [0.51, 0.52])));
+        assert!(!archive.insert(elite(0.5, [0.55, 0.58])), "a weaker elite in the same cell is dropped");
+        assert!(archive.insert(elite(2.0, [0.59, 0.50])), "a fitter one replaces the occupant");
+        assert!(archive.insert(elite(0.1, [0.05, 0.95])), "an empty cell takes anything");
+        assert_eq!(archive.len(), 2);
+        assert_eq!(archive.get("gol", (5, 5)).map(|e| e.fitness), Some(2.0));
+        assert_eq!(archive.get("gol", (9, 0)).map(|e| e.fitness), Some(0.1));
+        // Another simulation's elites live in their own grid
+        let mut other = elite(0.0, [0.5, 0.5]);
+        other.sim = "lenia".into();
+        assert!(archive.insert(other));
+        assert_eq!(archive.for_sim("gol").count(), 2);
+    }
+
+    #[test]
+    fn descriptors_stay_in_the_unit_square() {
+        let ramp: Vec<f64> = (0..64).map(|i| (i as f64 - 20.0) / 10.0).collect();
+        let checker: Vec<bool> = (0..64).map(|i| (i + i / 8) % 2 == 0).collect();
+        let cases = [
+            SimState::FloatGrid { width: 8, height: 8, values: ramp },
+            SimState::FloatGrid { width: 8, height: 8, values: vec![0.0; 64] },
+            SimState::Grid { offset_x: 0, offset_y: 0, width: 8, height: 8, cells: checker },
+            SimState::Grid { offset_x: 0, offset_y: 0, width: 8, height: 8, cells: vec![true; 64] },
+            SimState::Particles {
+                width: 10.0,
+                height: 10.0,
+                positions: vec![(0.0, 0.0), (10.0, 10.0), (0.0, 10.0)],
+                velocities: vec![(1e6, 0.0), (0.0, -3.0), (0.0, 0.0)],
+                species: vec![0, 0, 1],
+            },
+        ];
+        for state in &cases {
+            for period in [None, Some(1), Some(2), Some(MAX_PERIOD), Some(10 * MAX_PERIOD)] {
+                let d = descriptor(state, period).unwrap_or_else(|| panic!("no descriptor for {:?}", state));
+                assert!(d.iter().all(|v| (0.0..=1.0).contains(v)), "{:?} out of range for {:?}", d, state);
+            }
+        }
+        assert_eq!(descriptor(&SimState::Points(vec![(1.0, 2.0, 3.0)]), None), None);
+        assert_eq!(descriptor(&SimState::FloatGrid { width: 0, height: 0, values: Vec::new() }, None), None);
+    }
+
+    #[test]
+    fn period_detector_sees_a_blinker() {
+        let vertical = grid(5, 5, &[(2, 1), (2, 2), (2, 3)]);
+        let horizontal = grid(5, 5, &[(1, 2), (2, 2), (3, 2)]);
+        let mut periods = PeriodDetector::default();
+        assert_eq!(periods.push(&vertical), None);
+        assert_eq!(periods.push(&horizontal), None);
+        for _ in 0..3 {
+            assert_eq!(periods.push(&vertical), Some(2));
+            assert_eq!(periods.push(&horizontal), Some(2));
+        }
+        // A still life repeats every step
+        let block = grid(4, 4, &[(1, 1), (1, 2), (2, 1), (2, 2)]);
+        let mut periods = PeriodDetector::default();
+        periods.push(&block);
+        assert_eq!(periods.push(&block), Some(1));
+    }
+
+    #[test]
+    fn base64_matches_rfc_4648_vectors() {
+        let cases = [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foob", "Zm9vYg=="), ("fooba", "Zm9vYmE="), ("foobar", "Zm9vYmFy")];
+        for (input, expected) in cases {
+            assert_eq!(base64(input.as_bytes()), expected, "'{}'", input);
+        }
+        assert_eq!(base64(&[0xff, 0xfe, 0xfd]), "//79");
+    }
+}
@@ -0,0 +1,267 @@
+//! Headless runner: batch experiments without the browser.
+//!
+//! ```text
+//! headless soup [--seed N] [--soups N] [--size N] [--density F] [--census FILE]
+//! headless thumbnails [--size N] [--steps N] [--out DIR]
+//! headless parameter-map [--size N] [--steps N] [--f-range LO:HI] [--k-range LO:HI] [--out FILE]
+//! headless list
+//! headless run <sim> [--steps N] [--params k=v,k=v] [--reward SPEC] [--every N] [--out FILE]
+//! ```
+
+use sim_engine::gray_scott::GrayScott;
+use sim_engine::gray_scott_presets::PRESETS;
+use sim_engine::soup::{SoupCensus, SoupConfig, SoupSearch};
+use sim_engine::registry::{self, SIMULATIONS};
+use sim_engine::reward::{self, Rewarded};
+use sim_engine::{thumbnail, Action, ParamKind, ParamValue, Simulation};
+use std::collections::HashMap;
+use std::process::ExitCode;
+
+/// Observation features printed per line by `run`
+const SHOWN_FEATURES: usize = 8;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(command) = args.first() else {
+        eprintln!("usage: headless <soup|thumbnails|parameter-map|list|run> [options]");
+        return ExitCode::FAILURE;
+    };
+    let opts = parse_opts(&args[1..]);
+
+    let outcome = match command.as_str() {
+        "soup" => run_soup_search(&opts),
+        "thumbnails" => render_thumbnails(&opts),
+        "parameter-map" => render_parameter_map(&opts),
+        "list" => list_simulations(),
+        "run" => match args.get(1) {
+            Some(key) if !key.starts_with("--") => run_simulation(key, &parse_opts(&args[2..])),
+            _ => Err("usage: headless run <sim> [options] (see `headless list`)".into()),
+        },
+        other => Err(format!("unknown command '{}'", other)),
+    };
+
+    match outcome {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(msg) => {
+            eprintln!("error: {}", msg);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// --- Soup Search ---
+
+fn run_soup_search(opts: &HashMap<String, String>) -> Result<(), String> {
+    let defaults = SoupConfig::default();
+    let config = SoupConfig {
+        seed: opt(opts, "seed", defaults.seed)?,
+        soup_size: opt(opts, "size", defaults.soup_size)?,
+        density: opt(opts, "density", defaults.density)?,
+        max_generations: opt(opts, "max-gens", defaults.max_generations)?,
+    };
+    let soups: u64 = opt(opts, "soups", 100)?;
+    let census_path = opts.get("census").cloned().unwrap_or_else(|| "census.json".into());
+
+    // Keep tallying into the same file across runs
+    let previous: SoupCensus = match std::fs::read_to_string(&census_path) {
+        Ok(text) => serde_json::from_str(&text).map_err(|e| format!("{}: {}", census_path, e))?,
+        Err(_) => SoupCensus::default(),
+    };
+
+    // Same seed as an earlier run: carry on after the soups it already counted
+    let skipped = previous.counted(&config);
+    if skipped > 0 {
+        println!("{} soups of seed {} already in {}, starting at soup {}", skipped, config.seed, census_path, skipped);
+    }
+    let mut search = SoupSearch::with_config(config).with_census(previous);
+    for result in search.run_batch(soups) {
+        for key in &result.new_objects {
+            println!("soup {:#018x}: new object {}", result.soup_seed, key);
+        }
+    }
+
+    let census = search.census();
+    let json = serde_json::to_string_pretty(census).map_err(|e| e.to_string())?;
+    std::fs::write(&census_path, json).map_err(|e| format!("{}: {}", census_path, e))?;
+
+    println!("\n{} soups total ({} timed out), {} distinct objects", census.soups, census.timeouts, census.objects.len());
+    println!("rarest:");
+    for (key, entry) in census.rare(3).iter().take(20) {
+        println!(
+            "  {:>4}x  {:<10} pop {:<3} seed {:#018x}  {}",
+            entry.count,
+            entry.name.as_deref().unwrap_or("?"),
+            entry.population,
+            entry.first_soup_seed,
+            key
+        );
+    }
+    Ok(())
+}
+
+// --- Gray-Scott Preset Gallery ---
+
+fn render_thumbnails(opts: &HashMap<String, String>) -> Result<(), String> {
+    let size: usize = opt(opts, "size", 64)?;
+    let steps: u64 = opt(opts, "steps", 4000)?;
+    let out_dir = opts.get("out").cloned().unwrap_or_else(|| "thumbnails".into());
+    std::fs::create_dir_all(&out_dir).map_err(|e| format!("{}: {}", out_dir, e))?;
+
+    for preset in PRESETS {
+        let mut sim = GrayScott::from_preset(size, size, preset);
+        for _ in 0..steps {
+            sim.step();
+        }
+        let Some((w, h, pixels)) = thumbnail::render_rgb(&sim.get_state()) else { continue };
+        let path = format!("{}/{}.png", out_dir, preset.name);
+        std::fs::write(&path, thumbnail::encode_png(w, h, &pixels)).map_err(|e| format!("{}: {}", path, e))?;
+        println!("{:<10} {:<2} f={:.4} k={:.4} -> {}", preset.name, preset.class, preset.f, preset.k, path);
+    }
+    Ok(())
+}
+
+/// Munafo-style (f, k) map: k along x, f along y, one image.
+fn render_parameter_map(opts: &HashMap<String, String>) -> Result<(), String> {
+    let size: usize = opt(opts, "size", 256)?;
+    let steps: u64 = opt(opts, "steps", 10_000)?;
+    let f_range = range_opt(opts, "f-range", (0.0, 0.08))?;
+    let k_range = range_opt(opts, "k-range", (0.03, 0.07))?;
+    let path = opts.get("out").cloned().unwrap_or_else(|| "parameter_map.png".into());
+
+    let mut sim = GrayScott::parameter_map(size, size, f_range, k_range);
+    for _ in 0..steps {
+        sim.step();
+    }
+    let (w, h, pixels) = thumbnail::render_rgb(&sim.get_state()).ok_or("nothing to render")?;
+    std::fs::write(&path, thumbnail::encode_png(w, h, &pixels)).map_err(|e| format!("{}: {}", path, e))?;
+    println!("f {:?} (top -> bottom), k {:?} (left -> right) -> {}", f_range, k_range, path);
+    Ok(())
+}
+
+// --- Any Registered Simulation ---
+
+fn list_simulations() -> Result<(), String> {
+    for category in registry::categories() {
+        println!("{}:", category);
+        for info in registry::in_category(category) {
+            println!("  {:<16} {}", info.key, info.description);
+            for spec in info.schema {
+                let show = |x: f64| if spec.integer { format!("{}", x as i64) } else { format!("{:.4}", x) };
+                let (default, range) = match spec.kind {
+                    ParamKind::Number => (show(spec.default), format!("[{} .. {}]", show(spec.min), show(spec.max))),
+                    ParamKind::Choice(choices) => (spec.default_text.to_string(), choices.join("|")),
+                    ParamKind::Text => (spec.default_text.to_string(), "text".to_string()),
+                };
+                println!("      {:<18} {:<10} {:<24} {}", spec.name, default, range, spec.description);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Build `key` from the registry, apply `--params`, step it and print
+/// observation / reward every `--every` steps. `--out` saves a final thumbnail.
+fn run_simulation(key: &str, opts: &HashMap<String, String>) -> Result<(), String> {
+    let info = registry::find(key).ok_or_else(|| {
+        let keys: Vec<&str> = SIMULATIONS.iter().map(|info| info.key).collect();
+        format!("unknown simulation '{}' (one of: {})", key, keys.join(", "))
+    })?;
+    let steps: u64 = opt(opts, "steps", 1000)?;
+    let every: u64 = opt(opts, "every", 100)?;
+
+    // e.g. --reward "0.5*coverage:0.2 + 0.5*entropy" (see sim_engine::reward)
+    let mut sim = Rewarded::wrap(info.build());
+    if let Some(spec) = opts.get("reward") {
+        sim.set_reward(reward::parse(spec)?);
+    }
+    for pair in opts.get("params").map(String::as_str).unwrap_or("").split(',').filter(|p| !p.is_empty()) {
+        let (name, raw) = pair.split_once('=').ok_or_else(|| format!("bad param '{}' (expected key=value)", pair))?;
+        // Schema params are typed by their spec. Numbers for anything else
+        // ("child.param" for composites) go through the action space, which
+        // types them by the child's schema; the rest is typed by how it looks
+        let value = match info.param(name) {
+            Some(spec) if spec.is_numeric() => {
+                spec.value(raw.parse().map_err(|_| format!("bad value for {}: '{}'", name, raw))?)
+            }
+            Some(_) => ParamValue::String(raw.to_string()),
+            None => match (raw.parse(), sim.as_experimentable()) {
+                (Ok(value), Some(exp)) if exp.action_space().params.iter().any(|p| p.name == name) => {
+                    exp.apply_action(Action::SetParam { name: name.to_string(), value });
+                    continue;
+                }
+                _ => guess_value(raw),
+            },
+        };
+        sim.set_param(name, value);
+    }
+
+    println!("{} ({}), {} steps, reward {}", info.name, info.key, steps, sim.reward_spec());
+    if every > 0 {
+        if let Some(exp) = sim.as_experimentable() {
+            let names = exp.observation_space().feature_names();
+            let more = names.len().saturating_sub(SHOWN_FEATURES);
+            let names = names.into_iter().take(SHOWN_FEATURES).collect::<Vec<_>>().join(", ");
+            println!("obs {}{}", names, if more > 0 { format!(" (+{} more)", more) } else { String::new() });
+        }
+    }
+    for step in 1..=steps {
+        sim.step();
+        if every > 0 && step % every == 0 {
+            if let Some(exp) = sim.as_experimentable() {
+                // Leading features only: field tensors and CA rows would swamp the log
+                let features: Vec<String> =
+                    exp.observe().features().iter().take(SHOWN_FEATURES).map(|v| format!("{:.4}", v)).collect();
+                let obs = format!("[{}]", features.join(", "));
+                println!("{:>8}  obs {}  reward {:.4}", step, obs, exp.reward());
+            }
+        }
+    }
+
+    if let Some(path) = opts.get("out") {
+        let (w, h, pixels) = thumbnail::render_rgb(&sim.get_state()).ok_or("nothing to render")?;
+        std::fs::write(path, thumbnail::encode_png(w, h, &pixels)).map_err(|e| format!("{}: {}", path, e))?;
+        println!("-> {}", path);
+    }
+    Ok(())
+}
+
+// --- Arg Helpers ---
+
+/// Numbers are Float: a bare `0` must not become an Int that a float param ignores
+fn guess_value(raw: &str) -> ParamValue {
+    if let Ok(f) = raw.parse() {
+        ParamValue::Float(f)
+    } else if let Ok(b) = raw.parse() {
+        ParamValue::Bool(b)
+    } else {
+        ParamValue::String(raw.to_string())
+    }
+}
+
+/// `--key value` pairs -> map
+fn parse_opts(args: &[String]) -> HashMap<String, String> {
+    let mut opts = HashMap::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if let Some(key) = arg.strip_prefix("--") {
+            let value = iter.next().cloned().unwrap_or_default();
+            opts.insert(key.to_string(), value);
+        }
+    }
+    opts
+}
+
+/// `--key LO:HI`
+fn range_opt(opts: &HashMap<String, String>, key: &str, default: (f64, f64)) -> Result<(f64, f64), String> {
+    let Some(raw) = opts.get(key) else { return Ok(default) };
+    let bad = || format!("bad value for --{}: '{}' (expected LO:HI)", key, raw);
+    let (lo, hi) = raw.split_once(':').ok_or_else(bad)?;
+    Ok((lo.parse().map_err(|_| bad())?, hi.parse().map_err(|_| bad())?))
+}
+
+fn opt<T: std::str::FromStr>(opts: &HashMap<String, String>, key: &str, default: T) -> Result<T, String> {
+    match opts.get(key) {
+        Some(raw) => raw.parse().map_err(|_| format!("bad value for --{}: '{}'", key, raw)),
+        None => Ok(default),
+    }
+}
@@ -0,0 +1,228 @@
+//! Boids (Reynolds, 1987): separation, alignment and cohesion. With several
+//! species an affinity matrix says who flocks with whom; negative entries make
+//! a species steer away from another.
+
+use super::{param_value, Action, ActionSpace, Experimentable, Observation, ParamValue, SimState, Simulation};
+use crate::registry::BOIDS_SCHEMA;
+use crate::observation::FIELD_SIDE;
+use crate::particles::{parse_pair_key, ParticleSet, SpatialHash};
+use crate::rng::SimRng;
+
+#[derive(Clone)]
+pub struct Boids {
+    particles: ParticleSet,
+    hash: SpatialHash,
+    /// affinity[i][j]: weight on species j's heading and centre when i steers
+    affinity: Vec<Vec<f64>>,
+    count: usize,
+    /// Neighbours within this distance are seen at all
+    pub view_radius: f64,
+    /// Closer than this, steer away regardless of species
+    pub separation_radius: f64,
+    pub separation: f64,
+    pub alignment: f64,
+    pub cohesion: f64,
+    pub min_speed: f64,
+    pub max_speed: f64,
+    seed: u64,
+    mean_neighbours: f64,
+}
+
+const SIZE: f64 = 256.0;
+
+impl Boids {
+    pub fn init(count: usize, species: usize, seed: u64) -> Self {
+        let view_radius = 20.0;
+        let species = species.clamp(1, 16);
+        let mut boids = Self {
+            particles: ParticleSet::new(SIZE, SIZE),
+            hash: SpatialHash::new(SIZE, SIZE, view_radius),
+            // Flock with your own kind, ignore the rest
+            affinity: (0..species).map(|i| (0..species).map(|j| if i == j { 1.0 } else { 0.0 }).collect()).collect(),
+            count,
+            view_radius,
+            separation_radius: 6.0,
+            separation: 0.05,
+            alignment: 0.05,
+            cohesion: 0.005,
+            min_speed: 1.0,
+            max_speed: 3.0,
+            seed,
+            mean_neighbours: 0.0,
+        };
+        boids.reset();
+        boids
+    }
+
+    /// Respawn the flock from the current seed (the affinity matrix is kept).
+    pub fn reset(&mut self) {
+        let mut rng = SimRng::new(self.seed);
+        let species = self.affinity.len();
+        self.particles = ParticleSet::scatter(SIZE, SIZE, self.count, species, self.max_speed, &mut rng);
+        self.mean_neighbours = 0.0;
+    }
+
+    pub fn affinity(&self) -> &[Vec<f64>] {
+        &self.affinity
+    }
+
+    pub fn set_affinity(&mut self, i: usize, j: usize, value: f64) {
+        if let Some(a) = self.affinity.get_mut(i).and_then(|row| row.get_mut(j)) {
+            *a = value.clamp(-2.0, 2.0);
+        }
+    }
+
+    pub fn particles(&self) -> &ParticleSet {
+        &self.particles
+    }
+
+    /// |mean heading|: 1 when everyone flies the same way, ~0 when disordered
+    pub fn polarization(&self) -> f64 {
+        let n = self.particles.len();
+        if n == 0 {
+            return 0.0;
+        }
+        let (mut sx, mut sy) = (0.0, 0.0);
+        for v in &self.particles.velocities {
+            let speed = v.0.hypot(v.1).max(1e-9);
+            sx += v.0 / speed;
+            sy += v.1 / speed;
+        }
+        sx.hypot(sy) / n as f64
+    }
+}
+
+impl Simulation for Boids {
+    fn new() -> Self {
+        Self::init(400, 1, 0)
+    }
+
+    fn step(&mut self) {
+        self.hash.rebuild(&self.particles.positions);
+        let mut seen = 0usize;
+
+        let velocities: Vec<(f64, f64)> = (0..self.particles.len())
+            .map(|i| {
+                let si = self.particles.species[i] as usize;
+                let v = self.particles.velocities[i];
+                let (mut sep, mut heading, mut centre, mut weight) = ((0.0, 0.0), (0.0, 0.0), (0.0, 0.0), 0.0);
+                for j in self.hash.neighbours(self.particles.positions[i]) {
+                    if j == i { continue; }
+                    let (dx, dy) = self.particles.delta(i, j);
+                    let r = dx.hypot(dy);
+                    if r >= self.view_radius { continue; }
+                    seen += 1;
+                    if r < self.separation_radius && r > 0.0 {
+                        // Harder the closer they are
+                        sep.0 -= dx / (r * r);
+                        sep.1 -= dy / (r * r);
+                    }
+                    let a = self.affinity[si][self.particles.species[j] as usize];
+                    if a != 0.0 {
+                        let vj = self.particles.velocities[j];
+                        heading.0 += a * vj.0;
+                        heading.1 += a * vj.1;
+                        centre.0 += a * dx;
+                        centre.1 += a * dy;
+                        weight += a.abs();
+                    }
+                }
+
+                let mut next = v;
+                next.0 += self.separation * sep.0 * self.separation_radius;
+                next.1 += self.separation * sep.1 * self.separation_radius;
+                if weight > 0.0 {
+                    next.0 += self.alignment * (heading.0 / weight - v.0) + self.cohesion * centre.0 / weight;
+                    next.1 += self.alignment * (heading.1 / weight - v.1) + self.cohesion * centre.1 / weight;
+                }
+                let speed = next.0.hypot(next.1);
+                if speed > 1e-9 {
+                    let clamped = speed.clamp(self.min_speed, self.max_speed);
+                    next = (next.0 / speed * clamped, next.1 / speed * clamped);
+                }
+                next
+            })
+            .collect();
+
+        self.particles.velocities = velocities;
+        self.particles.drift(1.0);
+        self.mean_neighbours = seen as f64 / self.particles.len().max(1) as f64;
+    }
+
+    fn get_state(&self) -> SimState {
+        self.particles.state()
+    }
+
+    fn set_param(&mut self, key: &str, value: ParamValue) {
+        match (key, value) {
+            ("seed", ParamValue::Int(seed)) => {
+                self.seed = seed as u64;
+                self.reset();
+            }
+            ("species", ParamValue::Int(n)) => {
+                let n = n.clamp(1, 16) as usize;
+                self.affinity = (0..n).map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect()).collect();
+                self.reset();
+            }
+            ("count", ParamValue::Int(n)) => {
+                self.count = n.clamp(1, 20_000) as usize;
+                self.reset();
+            }
+            ("view_radius", ParamValue::Float(v)) => {
+                self.view_radius = v.clamp(2.0, SIZE / 3.0);
+                self.hash = SpatialHash::new(SIZE, SIZE, self.view_radius);
+            }
+            ("separation_radius", ParamValue::Float(v)) => self.separation_radius = v.max(0.0),
+            ("separation", ParamValue::Float(v)) => self.separation = v,
+            ("alignment", ParamValue::Float(v)) => self.alignment = v,
+            ("cohesion", ParamValue::Float(v)) => self.cohesion = v,
+            ("min_speed", ParamValue::Float(v)) => self.min_speed = v.max(0.0).min(self.max_speed),
+            ("max_speed", ParamValue::Float(v)) => self.max_speed = v.max(self.min_speed),
+            (key, ParamValue::Float(v)) => {
+                if let Some((i, j)) = parse_pair_key(key, "affinity") {
+                    self.set_affinity(i, j, v);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn as_experimentable(&mut self) -> Option<&mut dyn Experimentable> {
+        Some(self)
+    }
+}
+
+impl Experimentable for Boids {
+    fn apply_action(&mut self, action: Action) {
+        match action {
+            // Nudge one affinity entry; `which` indexes the flattened matrix row by row
+            Action::Perturb { which, delta } => {
+                let s = self.affinity.len();
+                let k = which as usize % (s * s);
+                let current = self.affinity[k / s][k % s];
+                self.set_affinity(k / s, k % s, current + delta);
+            }
+            Action::SetParam { name, value } => self.set_param(&name, param_value(BOIDS_SCHEMA, &name, value)),
+            _ => {}
+        }
+    }
+
+    fn action_space(&self) -> ActionSpace {
+        let s = self.affinity.len();
+        ActionSpace::new().with_perturb("affinity", (s * s).min(256), -1.0, 1.0).with_params(BOIDS_SCHEMA)
+    }
+
+    fn observe(&self) -> Observation {
+        Observation::named(&[
+            ("polarization", self.polarization()),
+            ("mean_speed", self.particles.mean_speed()),
+            ("mean_neighbours", self.mean_neighbours),
+        ])
+        .with("density", self.particles.density_map(FIELD_SIDE))
+    }
+
+    fn reward(&self) -> f64 {
+        // A flock: everyone heading the same way
+        self.polarization()
+    }
+}
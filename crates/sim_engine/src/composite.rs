@@ -0,0 +1,414 @@
+//! Several simulations stepped together, with outputs of one driving
+//! parameters of another (e.g. Lorenz `x` modulating the Gray-Scott feed rate).
+//!
+//! The coupling graph is plain data: each `Coupling` reads one number from a
+//! source child (a named observation feature, a component of its feature
+//! vector, or a statistic of its rendered field), maps it through `offset + gain * value`, optionally clamps it, and
+//! writes it to a parameter of the target child before the tick's steps.
+
+use super::{Action, ActionSpace, Experimentable, Observation, ParamValue, SimState, Simulation};
+use crate::action::ParamRange;
+use crate::gray_scott::GrayScott;
+use crate::ode::ODESim;
+
+/// What a coupling reads from its source child.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// Component i of the child's `Observation::features`
+    Observation(usize),
+    /// A named scalar in the child's observation ("x", "total_v", ...)
+    Feature(String),
+    /// A statistic over the child's rendered state (see `field_values`)
+    Field(FieldStat),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldStat {
+    Mean,
+    Variance,
+    Min,
+    Max,
+    /// Fraction of values above the threshold
+    Active(f64),
+}
+
+/// One edge of the coupling graph: `to.param = clamp(offset + gain * from.source)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coupling {
+    pub from: String,
+    pub source: Source,
+    pub to: String,
+    pub param: String,
+    pub gain: f64,
+    pub offset: f64,
+    pub clamp: Option<(f64, f64)>,
+}
+
+impl Coupling {
+    pub fn new(from: &str, source: Source, to: &str, param: &str) -> Self {
+        Self { from: from.into(), source, to: to.into(), param: param.into(), gain: 1.0, offset: 0.0, clamp: None }
+    }
+
+    pub fn scaled(mut self, gain: f64, offset: f64) -> Self {
+        self.gain = gain;
+        self.offset = offset;
+        self
+    }
+
+    pub fn clamped(mut self, lo: f64, hi: f64) -> Self {
+        self.clamp = Some((lo.min(hi), lo.max(hi)));
+        self
+    }
+
+    /// Text form for the string param API and the command line:
+    /// `"lorenz.x -> gs.f * 0.0006 + 0.045 in 0.01:0.08"`.
+    /// Sources are `mean`, `var`, `min`, `max`, `active:T`, `obsN` or any
+    /// other name, taken as a feature of the child's observation; the
+    /// `* gain`, `+ offset` and `in lo:hi` parts are optional.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (lhs, rhs) = spec.split_once("->")?;
+        let (from, source) = lhs.trim().split_once('.')?;
+        let source = match source.trim() {
+            "mean" => Source::Field(FieldStat::Mean),
+            "var" => Source::Field(FieldStat::Variance),
+            "min" => Source::Field(FieldStat::Min),
+            "max" => Source::Field(FieldStat::Max),
+            s => match s.strip_prefix("active:") {
+                Some(t) => Source::Field(FieldStat::Active(t.parse().ok()?)),
+                None => match s.strip_prefix("obs").and_then(|i| i.parse().ok()) {
+                    Some(i) => Source::Observation(i),
+                    None if !s.is_empty() => Source::Feature(s.to_string()),
+                    None => return None,
+                },
+            },
+        };
+
+        let mut words = rhs.split_whitespace();
+        let (to, param) = words.next()?.split_once('.')?;
+        let mut coupling = Coupling::new(from.trim(), source, to, param);
+        while let Some(word) = words.next() {
+            let value = words.next()?;
+            match word {
+                "*" => coupling.gain = value.parse().ok()?,
+                "+" => coupling.offset = value.parse().ok()?,
+                "-" => coupling.offset = -value.parse::<f64>().ok()?,
+                "in" => {
+                    let (lo, hi) = value.split_once(':')?;
+                    coupling = coupling.clamped(lo.parse().ok()?, hi.parse().ok()?);
+                }
+                _ => return None,
+            }
+        }
+        Some(coupling)
+    }
+
+    fn map(&self, value: f64) -> f64 {
+        let mapped = self.offset + self.gain * value;
+        match self.clamp {
+            Some((lo, hi)) => mapped.clamp(lo, hi),
+            None => mapped,
+        }
+    }
+}
+
+struct Child {
+    name: String,
+    sim: Box<dyn Simulation>,
+    /// Steps per composite tick, so slow and fast systems can share a clock
+    steps: usize,
+}
+
+pub struct Composite {
+    children: Vec<Child>,
+    couplings: Vec<Coupling>,
+    /// Child shown by `get_state` and handed actions / asked for reward
+    display: usize,
+    /// Value each coupling wrote last tick (after mapping)
+    driven: Vec<f64>,
+    /// Displayed child's reward and observation, and the combined action
+    /// space, as of the last step (the `&self` methods can't reach children)
+    reward: f64,
+    observation: Observation,
+    actions: ActionSpace,
+}
+
+impl Composite {
+    pub fn empty() -> Self {
+        Self { children: Vec::new(), couplings: Vec::new(), display: 0, driven: Vec::new(), reward: 0.0, observation: Observation::None, actions: ActionSpace::new() }
+    }
+
+    pub fn with_child(self, name: &str, sim: Box<dyn Simulation>) -> Self {
+        self.with_child_steps(name, sim, 1)
+    }
+
+    pub fn with_child_steps(mut self, name: &str, sim: Box<dyn Simulation>, steps: usize) -> Self {
+        self.children.push(Child { name: name.into(), sim, steps: steps.max(1) });
+        self.refresh();
+        self
+    }
+
+    pub fn with_coupling(mut self, coupling: Coupling) -> Self {
+        self.couple(coupling);
+        self
+    }
+
+    /// Show (and hand actions to) the named child.
+    pub fn with_display(mut self, name: &str) -> Self {
+        if let Some(i) = self.index_of(name) {
+            self.display = i;
+            self.refresh();
+        }
+        self
+    }
+
+    /// Add an edge; returns false (and ignores it) if either end isn't a child.
+    pub fn couple(&mut self, coupling: Coupling) -> bool {
+        if self.index_of(&coupling.from).is_none() || self.index_of(&coupling.to).is_none() {
+            return false;
+        }
+        self.couplings.push(coupling);
+        self.driven.push(0.0);
+        true
+    }
+
+    pub fn couplings(&self) -> &[Coupling] {
+        &self.couplings
+    }
+
+    /// Last value written by each coupling, in order
+    pub fn driven_values(&self) -> &[f64] {
+        &self.driven
+    }
+
+    pub fn child_names(&self) -> impl Iterator<Item = &str> {
+        self.children.iter().map(|c| c.name.as_str())
+    }
+
+    pub fn child_mut(&mut self, name: &str) -> Option<&mut dyn Simulation> {
+        let i = self.index_of(name)?;
+        Some(self.children[i].sim.as_mut())
+    }
+
+    /// Re-read the displayed child's reward and observation, and rebuild the
+    /// action space: the displayed child's own, plus every child's parameters
+    /// as "child.param"
+    fn refresh(&mut self) {
+        let shown = self.children.get_mut(self.display).and_then(|c| c.sim.as_experimentable());
+        (self.reward, self.observation) = shown.map_or((0.0, Observation::None), |exp| (exp.reward(), exp.observe()));
+
+        let mut actions = ActionSpace::new();
+        for (i, child) in self.children.iter_mut().enumerate() {
+            let Some(space) = child.sim.as_experimentable().map(|exp| exp.action_space()) else { continue };
+            actions.params.extend(space.params.iter().map(|p| ParamRange { name: format!("{}.{}", child.name, p.name), ..p.clone() }));
+            if i == self.display {
+                actions.flip_cell = space.flip_cell;
+                actions.perturb = space.perturb;
+                actions.params.extend(space.params);
+            }
+        }
+        self.actions = actions;
+    }
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.children.iter().position(|c| c.name == name)
+    }
+
+    fn read(&mut self, child: usize, source: &Source) -> Option<f64> {
+        let sim = &mut self.children[child].sim;
+        match source {
+            Source::Observation(i) => sim.as_experimentable()?.observe().features().get(*i).copied(),
+            Source::Feature(name) => sim.as_experimentable()?.observe().get(name)?.as_scalar(),
+            Source::Field(stat) => field_stat(&field_values(&sim.get_state()), *stat),
+        }
+    }
+
+    /// Evaluate every coupling against the current states, then write them all,
+    /// so the order of edges doesn't matter within a tick.
+    fn propagate(&mut self) {
+        let mut writes = Vec::with_capacity(self.couplings.len());
+        for k in 0..self.couplings.len() {
+            let coupling = &self.couplings[k];
+            let (Some(from), Some(to)) = (self.index_of(&coupling.from), self.index_of(&coupling.to)) else { continue };
+            let source = coupling.source.clone();
+            if let Some(value) = self.read(from, &source) {
+                writes.push((k, to, self.couplings[k].map(value)));
+            }
+        }
+        for (k, to, value) in writes {
+            self.driven[k] = value;
+            let param = self.couplings[k].param.clone();
+            self.children[to].sim.set_param(&param, ParamValue::Float(value));
+        }
+    }
+}
+
+/// The numbers a field statistic is taken over: intensities of a float grid,
+/// 0/1 cells of a boolean grid, distances from the origin for point clouds,
+/// speeds for particles.
+pub fn field_values(state: &SimState) -> Vec<f64> {
+    match state {
+        SimState::FloatGrid { values, .. } => values.clone(),
+        SimState::Grid { cells, .. } => cells.iter().map(|&c| if c { 1.0 } else { 0.0 }).collect(),
+        SimState::Points(points) => points.iter().map(|p| (p.0 * p.0 + p.1 * p.1 + p.2 * p.2).sqrt()).collect(),
+        SimState::Particles { velocities, .. } => velocities.iter().map(|v| v.0.hypot(v.1)).collect(),
+    }
+}
+
+fn field_stat(values: &[f64], stat: FieldStat) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    Some(match stat {
+        FieldStat::Mean => mean,
+        FieldStat::Variance => values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / n,
+        FieldStat::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+        FieldStat::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        FieldStat::Active(threshold) => values.iter().filter(|&&v| v > threshold).count() as f64 / n,
+    })
+}
+
+impl Simulation for Composite {
+    /// Lorenz `x` (roughly ±20) swings the Gray-Scott feed rate between the
+    /// mitosis and worm regimes; Lorenz runs 5 RK4 steps per reaction step.
+    fn new() -> Self {
+        Self::empty()
+            .with_child_steps("lorenz", Box::new(ODESim::new()), 5)
+            .with_child("gs", Box::new(GrayScott::init(100, 100)))
+            .with_coupling(Coupling::new("lorenz", Source::Feature("x".into()), "gs", "f").scaled(0.0004, 0.034).clamped(0.02, 0.05))
+            .with_display("gs")
+    }
+
+    fn step(&mut self) {
+        self.propagate();
+        for child in &mut self.children {
+            for _ in 0..child.steps {
+                child.sim.step();
+            }
+        }
+        self.refresh();
+    }
+
+    fn get_state(&self) -> SimState {
+        match self.children.get(self.display) {
+            Some(child) => child.sim.get_state(),
+            None => SimState::Points(Vec::new()),
+        }
+    }
+
+    /// "child.param" goes to that child; "coupling" adds an edge in text form;
+    /// "clear_couplings" drops them all; "display" picks the shown child.
+    fn set_param(&mut self, key: &str, value: ParamValue) {
+        match (key, value) {
+            ("coupling", ParamValue::String(spec)) => {
+                if let Some(coupling) = Coupling::parse(&spec) {
+                    self.couple(coupling);
+                }
+            }
+            ("clear_couplings", ParamValue::Bool(true)) => {
+                self.couplings.clear();
+                self.driven.clear();
+            }
+            ("display", ParamValue::String(name)) => {
+                if let Some(i) = self.index_of(&name) {
+                    self.display = i;
+                    self.refresh();
+                }
+            }
+            (key, value) => {
+                if let Some((child, param)) = key.split_once('.') {
+                    if let Some(sim) = self.child_mut(child) {
+                        sim.set_param(param, value);
+                    }
+                    self.refresh();
+                }
+            }
+        }
+    }
+
+    fn as_experimentable(&mut self) -> Option<&mut dyn Experimentable> {
+        Some(self)
+    }
+}
+
+impl Experimentable for Composite {
+    fn apply_action(&mut self, action: Action) {
+        // "child.param" names reach any child; everything else goes to the displayed one
+        if let Action::SetParam { name, value } = &action {
+            if let Some((child, param)) = name.split_once('.') {
+                if let Some(sim) = self.child_mut(child) {
+                    match sim.as_experimentable() {
+                        Some(exp) => exp.apply_action(Action::SetParam { name: param.into(), value: *value }),
+                        None => sim.set_param(param, ParamValue::Float(*value)),
+                    }
+                }
+                return;
+            }
+        }
+        if let Some(exp) = self.children.get_mut(self.display).and_then(|c| c.sim.as_experimentable()) {
+            exp.apply_action(action);
+        }
+    }
+
+    fn action_space(&self) -> ActionSpace {
+        self.actions.clone()
+    }
+
+    fn observe(&self) -> Observation {
+        // What the couplings are currently writing, then what the shown child sees
+        let shown = self.children.get(self.display).map_or("shown", |c| c.name.as_str());
+        Observation::Dict(vec![("driven".into(), Observation::Vector(self.driven.clone()))])
+            .with(shown, self.observation.clone())
+    }
+
+    fn reward(&self) -> f64 {
+        self.reward
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coupling_parse_accepts_valid_specs() {
+        let feature = |name: &str| Source::Feature(name.into());
+        let cases = [
+            ("lorenz.x -> gs.f", Coupling::new("lorenz", feature("x"), "gs", "f")),
+            (
+                "lorenz.x -> gs.f * 0.0006 + 0.045 in 0.01:0.08",
+                Coupling::new("lorenz", feature("x"), "gs", "f").scaled(0.0006, 0.045).clamped(0.01, 0.08),
+            ),
+            (
+                " gs.mean->lorenz.rho * 10 - 5 ",
+                Coupling::new("gs", Source::Field(FieldStat::Mean), "lorenz", "rho").scaled(10.0, -5.0),
+            ),
+            ("gs.var -> a.p in 5:1", Coupling::new("gs", Source::Field(FieldStat::Variance), "a", "p").clamped(1.0, 5.0)),
+            ("gs.active:0.3 -> lorenz.sigma", Coupling::new("gs", Source::Field(FieldStat::Active(0.3)), "lorenz", "sigma")),
+            ("lorenz.obs2 -> gs.k * 1e+3", Coupling::new("lorenz", Source::Observation(2), "gs", "k").scaled(1e3, 0.0)),
+        ];
+        for (spec, expected) in cases {
+            assert_eq!(Coupling::parse(spec), Some(expected), "spec '{}'", spec);
+        }
+    }
+
+    #[test]
+    fn coupling_parse_rejects_invalid_specs() {
+        let cases = [
+            "",
+            "lorenz.x gs.f",
+            "lorenz -> gs.f",
+            "lorenz. -> gs.f",
+            "lorenz.x -> gs",
+            "lorenz.x -> gs.f *",
+            "lorenz.x -> gs.f / 2",
+            "lorenz.x -> gs.f * big",
+            "lorenz.x -> gs.f in 0.1",
+            "gs.active:hot -> lorenz.rho",
+        ];
+        for spec in cases {
+            assert_eq!(Coupling::parse(spec), None, "spec '{}'", spec);
+        }
+    }
+}
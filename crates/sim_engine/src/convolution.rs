@@ -0,0 +1,200 @@
+//! Periodic 2D convolution with wide kernels (Lenia, SmoothLife).
+//!
+//! Small kernels are applied directly. Past `FFT_MIN_RADIUS` the per-cell cost
+//! of the direct sum (~4r² taps) loses to a pair of 2D FFTs, so the kernel's
+//! spectrum is precomputed once for the grid size and each step is a forward
+//! FFT per field, a pointwise product per kernel and an inverse FFT.
+
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::sync::Arc;
+
+/// Kernels with at least this radius go through the FFT.
+pub const FFT_MIN_RADIUS: usize = 5;
+
+/// FFT plans for one grid size.
+///
+/// Spectra come out transposed (column-major): nothing but pointwise products
+/// ever looks at them, so the transpose back is skipped until `inverse`.
+#[derive(Clone)]
+pub struct Fft2d {
+    width: usize,
+    height: usize,
+    row_fwd: Arc<dyn Fft<f64>>,
+    row_inv: Arc<dyn Fft<f64>>,
+    col_fwd: Arc<dyn Fft<f64>>,
+    col_inv: Arc<dyn Fft<f64>>,
+}
+
+impl Fft2d {
+    pub fn new(width: usize, height: usize) -> Self {
+        let mut planner = FftPlanner::new();
+        Self {
+            width,
+            height,
+            row_fwd: planner.plan_fft_forward(width),
+            row_inv: planner.plan_fft_inverse(width),
+            col_fwd: planner.plan_fft_forward(height),
+            col_inv: planner.plan_fft_inverse(height),
+        }
+    }
+
+    pub fn forward(&self, values: &[f64]) -> Vec<Complex<f64>> {
+        let mut rows: Vec<Complex<f64>> = values.iter().map(|&v| Complex::new(v, 0.0)).collect();
+        // rustfft runs every `width`-long chunk of the buffer
+        self.row_fwd.process(&mut rows);
+        let mut cols = transpose(&rows, self.height, self.width);
+        self.col_fwd.process(&mut cols);
+        cols
+    }
+
+    pub fn inverse(&self, mut spectrum: Vec<Complex<f64>>) -> Vec<f64> {
+        self.col_inv.process(&mut spectrum);
+        let mut rows = transpose(&spectrum, self.width, self.height);
+        self.row_inv.process(&mut rows);
+        let scale = 1.0 / (self.width * self.height) as f64;
+        rows.iter().map(|c| c.re * scale).collect()
+    }
+}
+
+/// `src` is `rows` × `cols`, row-major
+fn transpose(src: &[Complex<f64>], rows: usize, cols: usize) -> Vec<Complex<f64>> {
+    let mut dst = vec![Complex::new(0.0, 0.0); src.len()];
+    for r in 0..rows {
+        for c in 0..cols {
+            dst[c * rows + r] = src[r * cols + c];
+        }
+    }
+    dst
+}
+
+/// A kernel bound to a grid size: out(x) = Σ w(d)·in(x + d), wrapping at the edges.
+#[derive(Clone)]
+pub struct Convolution {
+    width: usize,
+    height: usize,
+    radius: usize,
+    /// (dx, dy, weight), zero weights dropped
+    taps: Vec<(isize, isize, f64)>,
+    /// Kernel spectrum when this kernel uses the FFT path
+    spectrum: Option<Vec<Complex<f64>>>,
+}
+
+impl Convolution {
+    /// Sample `weight(dx, dy)` over the (2r+1)² square and normalise it to sum to 1.
+    pub fn new(radius: usize, fft: &Fft2d, weight: impl Fn(f64, f64) -> f64) -> Self {
+        let r = radius as isize;
+        let mut taps: Vec<(isize, isize, f64)> = (-r..=r)
+            .flat_map(|dy| (-r..=r).map(move |dx| (dx, dy)))
+            .map(|(dx, dy)| (dx, dy, weight(dx as f64, dy as f64)))
+            .filter(|&(_, _, w)| w != 0.0)
+            .collect();
+        let total: f64 = taps.iter().map(|t| t.2).sum();
+        if total != 0.0 {
+            for tap in &mut taps {
+                tap.2 /= total;
+            }
+        }
+
+        let (w, h) = (fft.width, fft.height);
+        let spectrum = (radius >= FFT_MIN_RADIUS).then(|| {
+            // Correlation is convolution with the mirrored kernel
+            let mut grid = vec![0.0; w * h];
+            for &(dx, dy, wt) in &taps {
+                let x = (-dx).rem_euclid(w as isize) as usize;
+                let y = (-dy).rem_euclid(h as isize) as usize;
+                grid[y * w + x] += wt;
+            }
+            fft.forward(&grid)
+        });
+
+        Self { width: w, height: h, radius, taps, spectrum }
+    }
+
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+
+    pub fn uses_fft(&self) -> bool {
+        self.spectrum.is_some()
+    }
+
+    /// Convolve `values`. Pass the field's spectrum if it is already known
+    /// (several kernels reading the same field should share one forward FFT).
+    pub fn apply(&self, values: &[f64], spectrum: Option<&[Complex<f64>]>, fft: &Fft2d) -> Vec<f64> {
+        match (&self.spectrum, spectrum) {
+            (Some(kernel), Some(field)) => fft.inverse(field.iter().zip(kernel).map(|(a, b)| a * b).collect()),
+            (Some(kernel), None) => {
+                let field = fft.forward(values);
+                fft.inverse(field.iter().zip(kernel).map(|(a, b)| a * b).collect())
+            }
+            (None, _) => self.apply_direct(values),
+        }
+    }
+
+    fn apply_direct(&self, values: &[f64]) -> Vec<f64> {
+        let (w, h) = (self.width as isize, self.height as isize);
+        let mut out = vec![0.0; values.len()];
+        for y in 0..h {
+            for x in 0..w {
+                out[(y * w + x) as usize] = self
+                    .taps
+                    .iter()
+                    .map(|&(dx, dy, wt)| {
+                        let sx = (x + dx).rem_euclid(w);
+                        let sy = (y + dy).rem_euclid(h);
+                        wt * values[(sy * w + sx) as usize]
+                    })
+                    .sum();
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::SimRng;
+
+    fn field(width: usize, height: usize) -> Vec<f64> {
+        let mut rng = SimRng::new(9);
+        (0..width * height).map(|_| rng.next_f64()).collect()
+    }
+
+    #[test]
+    fn fft_matches_the_direct_sum() {
+        // Not square, so a transpose mix-up would show
+        let (w, h) = (24, 20);
+        let fft = Fft2d::new(w, h);
+        let values = field(w, h);
+        // Lopsided ring, so a mirrored kernel would show too
+        let conv = Convolution::new(6, &fft, |dx, dy| {
+            let r = (dx * dx + dy * dy).sqrt();
+            if r <= 6.0 { 1.0 + 0.1 * dx + 0.05 * dy * dy } else { 0.0 }
+        });
+        assert!(conv.uses_fft());
+        let direct = conv.apply_direct(&values);
+        let spectrum = fft.forward(&values);
+        for out in [conv.apply(&values, None, &fft), conv.apply(&values, Some(&spectrum), &fft)] {
+            for (i, (a, b)) in out.iter().zip(&direct).enumerate() {
+                assert!((a - b).abs() < 1e-9, "cell {}: fft {} direct {}", i, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn a_single_tap_shifts_across_the_wrap() {
+        let (w, h) = (16, 12);
+        let fft = Fft2d::new(w, h);
+        let values = field(w, h);
+        let conv = Convolution::new(FFT_MIN_RADIUS, &fft, |dx, dy| if (dx, dy) == (2.0, -1.0) { 1.0 } else { 0.0 });
+        let out = conv.apply(&values, None, &fft);
+        for y in 0..h {
+            for x in 0..w {
+                let expected = values[((y + h - 1) % h) * w + (x + 2) % w];
+                assert!((out[y * w + x] - expected).abs() < 1e-9, "({}, {})", x, y);
+            }
+        }
+    }
+}
@@ -0,0 +1,530 @@
+//! One-dimensional cellular automata: Wolfram's 256 elementary rules and
+//! nearest-neighbour totalistic rules with k colours. Each generation is a
+//! row; the last `height` rows are shown as a scrolling spacetime diagram.
+
+use super::{param_value, Action, ActionSpace, Experimentable, Observation, ParamValue, SimState, Simulation};
+use crate::registry::ECA_SCHEMA;
+use crate::rng::SimRng;
+use std::collections::{HashSet, VecDeque};
+
+/// Longest period (in generations) the class heuristic looks back for
+const MAX_PERIOD: usize = 64;
+/// Block length for the entropy estimate
+const BLOCK: usize = 4;
+/// Recent rows fed to the compressibility estimate
+const LZ_ROWS: usize = 32;
+/// Generations between re-classifications (the reward reads the cached class)
+const CLASS_EVERY: u64 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaRule {
+    /// Wolfram numbering: bit (4l + 2c + r) of the rule is the new centre cell
+    Elementary(u8),
+    /// Base-k digit s of `code` is the new colour when l + c + r = s
+    Totalistic { colors: u8, code: u64 },
+}
+
+impl CaRule {
+    pub fn colors(self) -> u8 {
+        match self {
+            CaRule::Elementary(_) => 2,
+            CaRule::Totalistic { colors, .. } => colors,
+        }
+    }
+
+    /// Totalistic colours clamped to 2..=4, and the code reduced below `code_count`
+    pub fn normalized(self) -> Self {
+        match self {
+            CaRule::Totalistic { colors, code } => {
+                let colors = colors.clamp(2, 4);
+                CaRule::Totalistic { colors, code: code % CaRule::code_count(colors) }
+            }
+            elementary => elementary,
+        }
+    }
+
+    /// Number of distinct rules with this many colours (2 to 4), i.e. one past the largest code
+    pub fn code_count(colors: u8) -> u64 {
+        (colors as u64).pow(3 * (colors as u32 - 1) + 1)
+    }
+
+    /// Lookup table indexed by neighbourhood (elementary) or neighbourhood sum (totalistic).
+    fn table(self) -> Vec<u8> {
+        match self {
+            CaRule::Elementary(rule) => (0..8).map(|i| (rule >> i) & 1).collect(),
+            CaRule::Totalistic { colors, code } => {
+                let k = colors as u64;
+                let mut digits = Vec::with_capacity(3 * (colors as usize - 1) + 1);
+                let mut rest = code;
+                for _ in 0..3 * (colors as usize - 1) + 1 {
+                    digits.push((rest % k) as u8);
+                    rest /= k;
+                }
+                digits
+            }
+        }
+    }
+}
+
+/// A few well-known rules, one or more per class.
+pub struct NotableRule {
+    pub name: &'static str,
+    pub rule: CaRule,
+    pub description: &'static str,
+}
+
+pub const NOTABLE_RULES: &[NotableRule] = &[
+    NotableRule { name: "Rule 30", rule: CaRule::Elementary(30), description: "Class 3: chaotic, used as a random number generator" },
+    NotableRule { name: "Rule 45", rule: CaRule::Elementary(45), description: "Class 3: chaotic" },
+    NotableRule { name: "Rule 90", rule: CaRule::Elementary(90), description: "Class 3: additive, Sierpinski triangle from a single cell" },
+    NotableRule { name: "Rule 110", rule: CaRule::Elementary(110), description: "Class 4: gliders on a periodic ether, Turing complete" },
+    NotableRule { name: "Rule 54", rule: CaRule::Elementary(54), description: "Class 4: particles and collisions" },
+    NotableRule { name: "Rule 184", rule: CaRule::Elementary(184), description: "Class 2: traffic flow, conserves density" },
+    NotableRule { name: "Rule 4", rule: CaRule::Elementary(4), description: "Class 2: freezes into isolated cells" },
+    NotableRule { name: "Rule 8", rule: CaRule::Elementary(8), description: "Class 1: dies out" },
+    NotableRule { name: "Code 1599", rule: CaRule::Totalistic { colors: 3, code: 1599 }, description: "Class 4, 3 colours: long-lived irregular structures" },
+    NotableRule { name: "Code 1635", rule: CaRule::Totalistic { colors: 3, code: 1635 }, description: "Class 4, 3 colours: localised structures" },
+    NotableRule { name: "Code 777", rule: CaRule::Totalistic { colors: 3, code: 777 }, description: "Class 3, 3 colours" },
+];
+
+/// Wolfram's four behaviour classes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WolframClass {
+    /// Dies out to a uniform state
+    Homogeneous = 1,
+    /// Settles into fixed or periodic (possibly shifting) structures
+    Periodic = 2,
+    /// Aperiodic, random-looking
+    Chaotic = 3,
+    /// Localised structures interacting on an ordered background
+    Complex = 4,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InitialCondition {
+    /// One cell of colour 1 in the middle
+    Single,
+    /// Each cell non-zero with this probability, colour uniform over 1..k
+    Random(f64),
+}
+
+#[derive(Clone)]
+pub struct ElementaryCA {
+    rule: CaRule,
+    table: Vec<u8>,
+    width: usize,
+    /// Rows kept for the spacetime diagram (and the class heuristic)
+    height: usize,
+    /// Oldest generation first; the back is the current row
+    rows: VecDeque<Vec<u8>>,
+    init: InitialCondition,
+    seed: u64,
+    generation: u64,
+    /// `wolfram_class` as of the last re-classification
+    class: WolframClass,
+}
+
+impl ElementaryCA {
+    pub fn init(rule: CaRule, width: usize, height: usize, init: InitialCondition, seed: u64) -> Self {
+        let rule = rule.normalized();
+        let mut sim = Self {
+            rule,
+            table: rule.table(),
+            width: width.max(3),
+            height: height.max(2),
+            rows: VecDeque::new(),
+            init,
+            seed,
+            generation: 0,
+            class: WolframClass::Homogeneous,
+        };
+        sim.reset();
+        sim
+    }
+
+    pub fn elementary(rule: u8) -> Self {
+        Self::init(CaRule::Elementary(rule), 250, 192, InitialCondition::Random(0.5), 0)
+    }
+
+    pub fn reset(&mut self) {
+        let k = self.rule.colors() as usize;
+        let row = match self.init {
+            InitialCondition::Single => {
+                let mut row = vec![0; self.width];
+                row[self.width / 2] = 1;
+                row
+            }
+            InitialCondition::Random(density) => {
+                let mut rng = SimRng::new(self.seed);
+                (0..self.width)
+                    .map(|_| if rng.chance(density) { 1 + rng.below(k - 1) as u8 } else { 0 })
+                    .collect()
+            }
+        };
+        self.rows.clear();
+        self.rows.push_back(row);
+        self.generation = 0;
+        self.class = self.wolfram_class();
+    }
+
+    pub fn rule(&self) -> CaRule {
+        self.rule
+    }
+
+    /// Switch rule and keep running from the current row.
+    pub fn set_rule(&mut self, rule: CaRule) {
+        let rule = rule.normalized();
+        let recolour = rule.colors() != self.rule.colors();
+        self.rule = rule;
+        self.table = rule.table();
+        // Colours that don't exist any more would index past the table
+        if recolour {
+            self.reset();
+        }
+        self.class = self.wolfram_class();
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn current(&self) -> &[u8] {
+        self.rows.back().expect("at least one row")
+    }
+
+    /// Fraction of non-zero cells in the current row
+    pub fn density(&self) -> f64 {
+        self.current().iter().filter(|&&c| c != 0).count() as f64 / self.width as f64
+    }
+
+    /// Fraction of cells that changed in the last generation
+    pub fn activity(&self) -> f64 {
+        let n = self.rows.len();
+        if n < 2 {
+            return 0.0;
+        }
+        let (prev, cur) = (&self.rows[n - 2], &self.rows[n - 1]);
+        prev.iter().zip(cur).filter(|(a, b)| a != b).count() as f64 / self.width as f64
+    }
+
+    /// Entropy of length-4 blocks over the recent rows, normalised to 0..1
+    pub fn block_entropy(&self) -> f64 {
+        let k = self.rule.colors() as usize;
+        let mut counts = vec![0usize; k.pow(BLOCK as u32)];
+        let mut total = 0usize;
+        for row in self.rows.iter().rev().take(16) {
+            for x in 0..self.width {
+                let key = (0..BLOCK).fold(0, |acc, d| acc * k + row[(x + d) % self.width] as usize);
+                counts[key] += 1;
+                total += 1;
+            }
+        }
+        let entropy: f64 = counts
+            .iter()
+            .filter(|&&c| c > 0)
+            .map(|&c| {
+                let p = c as f64 / total as f64;
+                -p * p.log2()
+            })
+            .sum();
+        entropy / (BLOCK as f64 * (k as f64).log2())
+    }
+
+    /// Smallest p such that the current row repeats the row p generations ago,
+    /// up to a shift of at most p cells (anything faster than light is ignored).
+    pub fn period(&self) -> Option<(usize, isize)> {
+        let n = self.rows.len();
+        let current = &self.rows[n - 1];
+        let w = self.width as isize;
+        for p in 1..n.min(MAX_PERIOD + 1) {
+            let past = &self.rows[n - 1 - p];
+            let reach = (p as isize).min(w / 2);
+            for shift in -reach..=reach {
+                let matches = (0..w).all(|x| current[x as usize] == past[(x - shift).rem_euclid(w) as usize]);
+                if matches {
+                    return Some((p, shift));
+                }
+            }
+        }
+        None
+    }
+
+    /// LZ78 phrase count of the recent rows, relative to the same cells
+    /// shuffled: ~1 for random-looking output, lower when there's structure
+    /// (class 4 backgrounds and gliders compress, class 3 noise doesn't).
+    pub fn compressibility(&self) -> f64 {
+        let mut cells: Vec<u8> = self.rows.iter().rev().take(LZ_ROWS).rev().flatten().copied().collect();
+        let ordered = lz78_phrases(&cells);
+        let mut rng = SimRng::new(0);
+        for i in (1..cells.len()).rev() {
+            cells.swap(i, rng.below(i + 1));
+        }
+        ordered as f64 / lz78_phrases(&cells).max(1) as f64
+    }
+
+    /// Rough classification of the recent history. Class 3 vs 4 is the hard
+    /// call; it's made on compressibility, which gets the rule 110 family
+    /// and the 3-colour totalistic codes 1599/1635 right, but calls some class 3
+    /// rules with large quiet domains (18, 126) complex.
+    pub fn wolfram_class(&self) -> WolframClass {
+        let current = self.current();
+        if current.iter().all(|&c| c == current[0]) {
+            WolframClass::Homogeneous
+        } else if self.period().is_some() {
+            WolframClass::Periodic
+        } else if self.compressibility() > 0.85 {
+            WolframClass::Chaotic
+        } else {
+            WolframClass::Complex
+        }
+    }
+
+    fn next_row(&self) -> Vec<u8> {
+        let row = self.current();
+        let w = self.width;
+        (0..w)
+            .map(|x| {
+                let (l, c, r) = (row[(x + w - 1) % w], row[x], row[(x + 1) % w]);
+                match self.rule {
+                    CaRule::Elementary(_) => self.table[(4 * l + 2 * c + r) as usize],
+                    CaRule::Totalistic { .. } => self.table[(l + c + r) as usize],
+                }
+            })
+            .collect()
+    }
+}
+
+fn lz78_phrases(seq: &[u8]) -> usize {
+    let mut seen = HashSet::new();
+    let mut start = 0;
+    for end in 1..=seq.len() {
+        if seen.insert(&seq[start..end]) {
+            start = end;
+        }
+    }
+    seen.len()
+}
+
+impl Simulation for ElementaryCA {
+    fn new() -> Self {
+        Self::elementary(110)
+    }
+
+    fn step(&mut self) {
+        let next = self.next_row();
+        self.rows.push_back(next);
+        if self.rows.len() > self.height {
+            self.rows.pop_front();
+        }
+        self.generation += 1;
+        if self.generation % CLASS_EVERY == 0 {
+            self.class = self.wolfram_class();
+        }
+    }
+
+    /// Time runs down the screen, newest row at the bottom once the diagram fills.
+    /// `Grid` is two-state, so with k > 2 colours every non-zero colour shows as alive.
+    fn get_state(&self) -> SimState {
+        let mut cells = Vec::with_capacity(self.width * self.height);
+        for row in &self.rows {
+            cells.extend(row.iter().map(|&c| c != 0));
+        }
+        cells.resize(self.width * self.height, false);
+        SimState::Grid {
+            offset_x: 0,
+            offset_y: self.generation.saturating_sub(self.rows.len() as u64 - 1) as i64,
+            width: self.width as u32,
+            height: self.height as u32,
+            cells,
+        }
+    }
+
+    fn set_param(&mut self, key: &str, value: ParamValue) {
+        match (key, value) {
+            ("rule", ParamValue::Int(rule)) => self.set_rule(CaRule::Elementary(rule.clamp(0, 255) as u8)),
+            // Totalistic code, keeping the current colour count (3 if elementary)
+            ("code", ParamValue::Int(code)) => {
+                let colors = match self.rule {
+                    CaRule::Totalistic { colors, .. } => colors,
+                    CaRule::Elementary(_) => 3,
+                };
+                self.set_rule(CaRule::Totalistic { colors, code: code.max(0) as u64 });
+            }
+            ("colors", ParamValue::Int(k)) => {
+                let code = match self.rule {
+                    CaRule::Totalistic { code, .. } => code,
+                    CaRule::Elementary(_) => 0,
+                };
+                self.set_rule(CaRule::Totalistic { colors: k.clamp(2, 4) as u8, code });
+            }
+            ("init", ParamValue::String(name)) => {
+                self.init = match name.as_str() {
+                    "single" => InitialCondition::Single,
+                    _ => InitialCondition::Random(0.5),
+                };
+                self.reset();
+            }
+            ("density", ParamValue::Float(p)) => {
+                self.init = InitialCondition::Random(p.clamp(0.0, 1.0));
+                self.reset();
+            }
+            ("seed", ParamValue::Int(seed)) => {
+                self.seed = seed as u64;
+                self.reset();
+            }
+            ("width", ParamValue::Int(w)) => {
+                self.width = w.clamp(3, 4096) as usize;
+                self.reset();
+            }
+            ("height", ParamValue::Int(h)) => {
+                self.height = h.clamp(2, 4096) as usize;
+                while self.rows.len() > self.height {
+                    self.rows.pop_front();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn as_experimentable(&mut self) -> Option<&mut dyn Experimentable> {
+        Some(self)
+    }
+}
+
+impl Experimentable for ElementaryCA {
+    fn apply_action(&mut self, action: Action) {
+        match action {
+            // Only the current row can be edited, so `r` is always 0
+            Action::FlipCell { c, .. } => {
+                let k = self.rule.colors();
+                let w = self.width;
+                if let Some(row) = self.rows.back_mut() {
+                    let cell = &mut row[c % w];
+                    *cell = (*cell + 1) % k;
+                }
+            }
+            // Walk rule space: flip one bit of an elementary rule, or step one
+            // digit of a totalistic code up or down
+            Action::Perturb { which, delta } => match self.rule {
+                CaRule::Elementary(rule) => self.set_rule(CaRule::Elementary(rule ^ (1 << (which % 8)))),
+                CaRule::Totalistic { colors, code } => {
+                    let k = colors as u64;
+                    let place = k.pow(which as u32 % (3 * (colors as u32 - 1) + 1));
+                    let digit = (code / place) % k;
+                    let new_digit = if delta >= 0.0 { (digit + 1) % k } else { (digit + k - 1) % k };
+                    self.set_rule(CaRule::Totalistic { colors, code: code - digit * place + new_digit * place });
+                }
+            },
+            Action::SetParam { name, value } => self.set_param(&name, param_value(ECA_SCHEMA, &name, value)),
+            _ => {}
+        }
+    }
+
+    fn action_space(&self) -> ActionSpace {
+        // Perturb targets are rule bits, or totalistic code digits
+        let digits = match self.rule {
+            CaRule::Elementary(_) => 8,
+            CaRule::Totalistic { colors, .. } => 3 * (colors as usize - 1) + 1,
+        };
+        ActionSpace::new()
+            .with_flip_cell(1, self.width)
+            .with_perturb("rule digit", digits, -1.0, 1.0)
+            .with_params(ECA_SCHEMA)
+    }
+
+    fn observe(&self) -> Observation {
+        // Summary statistics, then the newest row as cell states
+        let row = self.rows.back().map_or_else(Vec::new, |r| r.iter().map(|&c| c as f64).collect());
+        Observation::named(&[("density", self.density()), ("block_entropy", self.block_entropy()), ("activity", self.activity())])
+            .with("row", Observation::Vector(row))
+    }
+
+    fn reward(&self) -> f64 {
+        // The interesting rules are the class 4 ones
+        match self.class {
+            WolframClass::Homogeneous => 0.0,
+            WolframClass::Periodic => 0.1,
+            WolframClass::Chaotic => 0.5,
+            WolframClass::Complex => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(rule: u8, generations: usize) -> Vec<String> {
+        let mut ca = ElementaryCA::init(CaRule::Elementary(rule), 11, 16, InitialCondition::Single, 0);
+        let mut out = vec![ca.current().iter().map(|&c| if c == 1 { '#' } else { '.' }).collect()];
+        for _ in 0..generations {
+            ca.step();
+            out.push(ca.current().iter().map(|&c| if c == 1 { '#' } else { '.' }).collect());
+        }
+        out
+    }
+
+    #[test]
+    fn elementary_rules_match_their_known_diagrams() {
+        assert_eq!(rows(30, 4), [".....#.....", "....###....", "...##..#...", "..##.####..", ".##..#...#."]);
+        assert_eq!(rows(110, 4), [".....#.....", "....##.....", "...###.....", "..##.#.....", ".#####....."]);
+    }
+
+    #[test]
+    fn code_count_is_k_to_the_number_of_sums() {
+        assert_eq!(CaRule::code_count(2), 16);
+        assert_eq!(CaRule::code_count(3), 2187);
+        assert_eq!(CaRule::code_count(4), 1_048_576);
+    }
+
+    #[test]
+    fn init_normalises_totalistic_rules() {
+        let cases = [
+            (CaRule::Totalistic { colors: 0, code: 5 }, CaRule::Totalistic { colors: 2, code: 5 }),
+            (CaRule::Totalistic { colors: 1, code: 20 }, CaRule::Totalistic { colors: 2, code: 4 }),
+            (CaRule::Totalistic { colors: 9, code: u64::MAX }, CaRule::Totalistic { colors: 4, code: u64::MAX % 1_048_576 }),
+            (CaRule::Totalistic { colors: 3, code: 1599 }, CaRule::Totalistic { colors: 3, code: 1599 }),
+        ];
+        for (rule, expected) in cases {
+            let mut ca = ElementaryCA::init(rule, 20, 8, InitialCondition::Random(0.5), 1);
+            assert_eq!(ca.rule(), expected);
+            ca.step();
+            assert!(ca.current().iter().all(|&c| c < expected.colors()));
+            assert!(!ca.action_space().params.is_empty());
+        }
+    }
+
+    #[test]
+    fn perturb_steps_round_trip() {
+        let mut ca = ElementaryCA::init(CaRule::Totalistic { colors: 3, code: 1599 }, 20, 8, InitialCondition::Single, 0);
+        for which in 0..7 {
+            ca.apply_action(Action::Perturb { which, delta: 1.0 });
+            assert_ne!(ca.rule(), CaRule::Totalistic { colors: 3, code: 1599 }, "digit {}", which);
+            ca.apply_action(Action::Perturb { which, delta: -1.0 });
+            assert_eq!(ca.rule(), CaRule::Totalistic { colors: 3, code: 1599 }, "digit {}", which);
+            // k steps up come back around
+            for _ in 0..3 {
+                ca.apply_action(Action::Perturb { which, delta: 1.0 });
+            }
+            assert_eq!(ca.rule(), CaRule::Totalistic { colors: 3, code: 1599 }, "digit {}", which);
+        }
+
+        let mut ca = ElementaryCA::elementary(110);
+        for which in 0..8 {
+            ca.apply_action(Action::Perturb { which, delta: 0.5 });
+            assert_eq!(ca.rule(), CaRule::Elementary(110 ^ (1 << which)));
+            ca.apply_action(Action::Perturb { which, delta: 0.5 });
+            assert_eq!(ca.rule(), CaRule::Elementary(110));
+        }
+    }
+
+    #[test]
+    fn reward_follows_the_class_after_a_while() {
+        let mut ca = ElementaryCA::elementary(8);
+        for _ in 0..CLASS_EVERY {
+            ca.step();
+        }
+        assert_eq!(ca.wolfram_class(), WolframClass::Homogeneous);
+        assert_eq!(ca.reward(), 0.0);
+    }
+}
@@ -0,0 +1,264 @@
+//! Gymnasium-style episodes over any simulation:
+//! `reset(seed) -> (obs, info)` and
+//! `step(action) -> (obs, reward, terminated, truncated, info)`.
+//!
+//! An episode is terminated when the world dies out, fills up or blows up
+//! (see `Termination`), and truncated when it hits the step limit. Either
+//! way the caller resets; stepping on past the end keeps running the world
+//! but the flags stay set.
+
+use crate::composite::field_values;
+use crate::registry;
+use crate::reward::Rewarded;
+use crate::{Action, ActionError, ActionSpace, Observation, ObservationSpace, ParamValue, SimState, Simulation};
+
+/// Episode length when none is given
+pub const DEFAULT_MAX_STEPS: u64 = 1000;
+
+/// A condition that ends an episode early.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Termination {
+    /// Grid worlds: every cell at or below the threshold
+    Extinction(f64),
+    /// Grid worlds: at least this fraction of cells at full intensity (0.95 or more)
+    Saturation(f64),
+    /// Any observed feature or field value not finite or beyond ± the limit
+    Divergence(f64),
+}
+
+impl Termination {
+    pub const DEFAULTS: [Termination; 3] =
+        [Termination::Extinction(1e-6), Termination::Saturation(0.98), Termination::Divergence(1e9)];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Termination::Extinction(_) => "extinction",
+            Termination::Saturation(_) => "saturation",
+            Termination::Divergence(_) => "divergence",
+        }
+    }
+
+    /// "extinction", "saturation:0.9", "divergence:1e6" (no value = the default)
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (name, value) = match spec.trim().split_once(':') {
+            Some((name, value)) => (name, Some(value.trim().parse().ok()?)),
+            None => (spec.trim(), None),
+        };
+        let default = Self::DEFAULTS.into_iter().find(|t| t.name() == name)?;
+        Some(match (default, value) {
+            (_, None) => default,
+            (Termination::Extinction(_), Some(v)) => Termination::Extinction(v),
+            (Termination::Saturation(_), Some(v)) => Termination::Saturation(v),
+            (Termination::Divergence(_), Some(v)) => Termination::Divergence(v),
+        })
+    }
+
+    fn triggered(&self, state: &SimState, obs: &Observation) -> bool {
+        let grid = matches!(state, SimState::FloatGrid { .. } | SimState::Grid { .. });
+        match *self {
+            Termination::Extinction(threshold) => grid && field_values(state).iter().all(|&v| v <= threshold),
+            Termination::Saturation(fraction) => {
+                let values = field_values(state);
+                grid && !values.is_empty()
+                    && values.iter().filter(|&&v| v >= 0.95).count() as f64 >= fraction * values.len() as f64
+            }
+            Termination::Divergence(limit) => {
+                let wild = |v: &f64| !v.is_finite() || v.abs() > limit;
+                obs.features().iter().any(wild) || field_values(state).iter().any(wild)
+            }
+        }
+    }
+}
+
+/// Extra per-step detail (Gymnasium's `info` dict).
+#[derive(Debug, Clone, Default)]
+pub struct StepInfo {
+    /// Episodes started so far, counting this one
+    pub episode: u64,
+    /// Steps taken in this episode
+    pub step: u64,
+    /// The condition that ended the episode, if one did
+    pub terminated_by: Option<Termination>,
+    /// Why the action was refused (the world still stepped)
+    pub rejected: Option<ActionError>,
+}
+
+pub struct Step {
+    pub observation: Observation,
+    pub reward: f64,
+    pub terminated: bool,
+    pub truncated: bool,
+    pub info: StepInfo,
+}
+
+pub struct Env {
+    build: Box<dyn Fn() -> Box<dyn Simulation>>,
+    /// Wrapped so `set_param("reward", ...)` picks the reward function
+    sim: Rewarded,
+    /// Replayed onto every fresh world
+    params: Vec<(String, ParamValue)>,
+    /// Parameter `reset(Some(seed))` writes the seed to
+    seed_param: Option<String>,
+    terminations: Vec<Termination>,
+    max_steps: Option<u64>,
+    episode: u64,
+    step: u64,
+    done: Option<(bool, bool, Option<Termination>)>,
+}
+
+impl Env {
+    /// An environment over a registered simulation ("gray-scott", "boids", ...).
+    pub fn new(key: &str) -> Option<Self> {
+        let info = registry::find(key)?;
+        let seed_param = info.schema.iter().find(|spec| spec.is_numeric() && spec.name.ends_with("seed")).map(|spec| spec.name.to_string());
+        let mut env = Self::from_fn(move || info.build());
+        env.seed_param = seed_param;
+        Some(env)
+    }
+
+    /// An environment over any simulation; `build` makes a fresh world per episode.
+    pub fn from_fn(build: impl Fn() -> Box<dyn Simulation> + 'static) -> Self {
+        let sim = Rewarded::wrap(build());
+        Self {
+            build: Box::new(build),
+            sim,
+            params: Vec::new(),
+            seed_param: None,
+            terminations: Termination::DEFAULTS.to_vec(),
+            max_steps: Some(DEFAULT_MAX_STEPS),
+            episode: 0,
+            step: 0,
+            done: None,
+        }
+    }
+
+    pub fn with_param(mut self, name: &str, value: ParamValue) -> Self {
+        self.set_param(name, value);
+        self
+    }
+
+    /// `None` runs episodes until a termination condition fires.
+    pub fn with_max_steps(mut self, max_steps: Option<u64>) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    pub fn with_terminations(mut self, terminations: Vec<Termination>) -> Self {
+        self.terminations = terminations;
+        self
+    }
+
+    pub fn with_seed_param(mut self, name: &str) -> Self {
+        self.seed_param = Some(name.into());
+        self
+    }
+
+    /// Set a parameter now and on every later reset.
+    pub fn set_param(&mut self, name: &str, value: ParamValue) {
+        self.params.retain(|(n, _)| n != name);
+        self.params.push((name.into(), value.clone()));
+        self.sim.set_param(name, value);
+    }
+
+    /// A fresh world with the configured parameters (and `seed`, if the
+    /// simulation has a seed parameter).
+    pub fn reset(&mut self, seed: Option<u64>) -> (Observation, StepInfo) {
+        self.sim = Rewarded::wrap((self.build)());
+        for (name, value) in &self.params {
+            self.sim.set_param(name, value.clone());
+        }
+        if let (Some(seed), Some(name)) = (seed, &self.seed_param) {
+            self.sim.set_param(name, ParamValue::Int((seed & i64::MAX as u64) as i64));
+        }
+        self.episode += 1;
+        self.step = 0;
+        self.done = None;
+        (self.observe(), self.info(None))
+    }
+
+    pub fn step(&mut self, action: Action) -> Step {
+        let rejected = self.sim.as_experimentable().and_then(|exp| exp.try_apply(action).err());
+        self.sim.step();
+        self.step += 1;
+
+        let observation = self.observe();
+        let reward = self.sim.as_experimentable().map_or(0.0, |exp| exp.reward());
+        if self.done.is_none() {
+            let state = self.sim.get_state();
+            let terminated_by = self.terminations.iter().copied().find(|t| t.triggered(&state, &observation));
+            let truncated = self.max_steps.is_some_and(|max| self.step >= max);
+            if terminated_by.is_some() || truncated {
+                self.done = Some((terminated_by.is_some(), truncated && terminated_by.is_none(), terminated_by));
+            }
+        }
+        let (terminated, truncated, terminated_by) = self.done.unwrap_or((false, false, None));
+        let mut info = self.info(rejected);
+        info.terminated_by = terminated_by;
+        Step { observation, reward, terminated, truncated, info }
+    }
+
+    pub fn observation_space(&mut self) -> ObservationSpace {
+        self.sim.as_experimentable().map_or(ObservationSpace::None, |exp| exp.observation_space())
+    }
+
+    pub fn action_space(&mut self) -> ActionSpace {
+        self.sim.as_experimentable().map_or_else(ActionSpace::new, |exp| exp.action_space())
+    }
+
+    pub fn sim(&self) -> &dyn Simulation {
+        &self.sim
+    }
+
+    pub fn sim_mut(&mut self) -> &mut dyn Simulation {
+        &mut self.sim
+    }
+
+    /// The reward function's spec and the last bad `reward` param, if any.
+    pub fn reward(&self) -> (String, Option<&str>) {
+        (self.sim.reward_spec(), self.sim.error.as_deref())
+    }
+
+    pub fn episode(&self) -> u64 {
+        self.episode
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.step
+    }
+
+    fn observe(&mut self) -> Observation {
+        self.sim.as_experimentable().map_or(Observation::None, |exp| exp.observe())
+    }
+
+    fn info(&self, rejected: Option<ActionError>) -> StepInfo {
+        StepInfo { episode: self.episode, step: self.step, terminated_by: None, rejected }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn termination_parse_accepts_valid_specs() {
+        let cases = [
+            ("extinction", Termination::Extinction(1e-6)),
+            ("saturation", Termination::Saturation(0.98)),
+            ("divergence", Termination::Divergence(1e9)),
+            ("saturation:0.9", Termination::Saturation(0.9)),
+            (" extinction: 0.01 ", Termination::Extinction(0.01)),
+            ("divergence:1e6", Termination::Divergence(1e6)),
+            ("divergence:1e+3", Termination::Divergence(1e3)),
+        ];
+        for (spec, expected) in cases {
+            assert_eq!(Termination::parse(spec), Some(expected), "spec '{}'", spec);
+        }
+    }
+
+    #[test]
+    fn termination_parse_rejects_invalid_specs() {
+        for spec in ["", "explosion", "saturation:", "saturation:most", "divergence:1e+", "Extinction"] {
+            assert_eq!(Termination::parse(spec), None, "spec '{}'", spec);
+        }
+    }
+}
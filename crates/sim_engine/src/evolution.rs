@@ -0,0 +1,529 @@
+//! Headless parameter search: a separable CMA-ES over a simulation's schema,
+//! scored on reward and on novelty of the behaviour it ends up in.
+//!
+//! Every candidate is a fresh copy of the live run's `Setup` (by default the
+//! registry build) with its parameters set (seed-like parameters, whose range
+//! runs to i64::MAX, get a random seed, so initial conditions are searched
+//! too), run for `eval_steps` steps off screen. Fitness is its mean reward,
+//! under the setup's reward spec, over the second half of the run; its
+//! behaviour is where the final state sits in the elite archive's behaviour
+//! space (`archive::descriptor`; the observation's features for point sets,
+//! which have none), and novelty is the mean distance to the nearest
+//! behaviours seen so far. Candidates are ranked on fitness plus
+//! `novelty_weight` times novelty (as ranks, so neither scale matters), and
+//! the search distribution moves toward the best half.
+//!
+//! Evaluation is incremental (`advance`), so a brain can spread it over ticks.
+
+use crate::archive::{self, Elite, PeriodDetector, MAX_PERIOD};
+use crate::registry::SimInfo;
+use crate::reward::Rewarded;
+use crate::rng::SimRng;
+use crate::{ParamSpec, ParamValue, Simulation};
+use std::rc::Rc;
+
+#[derive(Clone, Debug)]
+pub struct SearchConfig {
+    /// Candidates per generation (0 = the usual 4 + 3 ln n)
+    pub population: usize,
+    /// Steps each candidate runs for
+    pub eval_steps: usize,
+    /// Starting step size, as a share of each parameter's range
+    pub sigma: f64,
+    /// Weight of the novelty rank against the fitness rank
+    pub novelty_weight: f64,
+    /// Nearest neighbours averaged for novelty
+    pub neighbours: usize,
+    /// Behaviours remembered for novelty (oldest dropped first)
+    pub archive: usize,
+    pub seed: u64,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self { population: 0, eval_steps: 200, sigma: 0.3, novelty_weight: 0.5, neighbours: 5, archive: 1000, seed: 0 }
+    }
+}
+
+/// How the live run was set up, so candidates run in copies of it rather than
+/// in the bare registry build: `build`, then `params` in order (sidebar picks,
+/// the "reward" spec...), then the candidate's own parameters on top.
+#[derive(Clone)]
+pub struct Setup {
+    pub build: Rc<dyn Fn() -> Box<dyn Simulation>>,
+    pub params: Vec<(String, ParamValue)>,
+}
+
+impl Setup {
+    /// The registry build, with its native reward
+    pub fn registry(info: &'static SimInfo) -> Self {
+        Self { build: Rc::new(move || info.build()), params: Vec::new() }
+    }
+}
+
+/// One evaluated configuration, with everything needed to reproduce it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    /// (name, value) for every searched parameter, in schema order
+    pub params: Vec<(String, f64)>,
+    pub fitness: f64,
+    pub novelty: f64,
+    pub behaviour: Vec<f64>,
+    /// Place in the elite archive, and a picture of the final state (None for point sets)
+    pub descriptor: Option<[f64; 2]>,
+    pub thumbnail: Option<String>,
+}
+
+impl Candidate {
+    /// "f=0.0367, k=0.0649, init_seed=1234"
+    pub fn describe(&self) -> String {
+        self.params
+            .iter()
+            .map(|(name, value)| if value.fract() == 0.0 { format!("{}={}", name, value) } else { format!("{}={:.4}", name, value) })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// A fresh copy of `setup` in this configuration, scored by its reward
+    pub fn build(&self, info: &SimInfo, setup: &Setup) -> Box<dyn Simulation> {
+        let mut sim = Rewarded::wrap((setup.build)());
+        for (name, value) in self.setup_params(info, setup) {
+            sim.set_param(&name, value);
+        }
+        Box::new(sim)
+    }
+
+    /// What to `set_param` on `setup`'s build to get here: its params the
+    /// genome doesn't override, then the genome
+    fn setup_params(&self, info: &SimInfo, setup: &Setup) -> Vec<(String, ParamValue)> {
+        let searched = |name: &str| self.params.iter().any(|(n, _)| n == name);
+        let base = setup.params.iter().filter(|(name, _)| !searched(name)).cloned();
+        base.chain(self.params.iter().map(|(name, value)| (name.clone(), crate::param_value(info.schema, name, *value)))).collect()
+    }
+}
+
+/// What a finished generation turned up.
+#[derive(Clone, Debug)]
+pub struct GenerationReport {
+    pub generation: u64,
+    /// Fittest of this generation
+    pub best: Candidate,
+    /// Most novel of this generation
+    pub most_novel: Candidate,
+    /// Whether `best` beat every earlier candidate
+    pub new_record: bool,
+    /// Mean step size, as a share of the parameter ranges
+    pub sigma: f64,
+}
+
+/// A run in progress
+struct Evaluation {
+    sim: Box<dyn Simulation>,
+    /// Index into the generation's samples
+    index: usize,
+    steps: usize,
+    reward: f64,
+    counted: usize,
+    /// Shortest repeat over the last `MAX_PERIOD` steps, for cell-grid descriptors
+    periods: PeriodDetector,
+    period: Option<usize>,
+}
+
+pub struct EvolutionSearch {
+    info: &'static SimInfo,
+    setup: Setup,
+    config: SearchConfig,
+    rng: SimRng,
+    /// Searched parameters (bounded ranges)
+    genes: Vec<&'static ParamSpec>,
+    /// Seed-like parameters, drawn at random per candidate
+    seeds: Vec<&'static ParamSpec>,
+    // Search distribution, in [0, 1] units per gene
+    mean: Vec<f64>,
+    sigma: f64,
+    diag: Vec<f64>,
+    path_sigma: Vec<f64>,
+    path_c: Vec<f64>,
+    /// This generation's samples: (z, y = sqrt(diag) z, candidate once evaluated)
+    samples: Vec<(Vec<f64>, Vec<f64>, Option<Candidate>)>,
+    current: Option<Evaluation>,
+    archive: Vec<Vec<f64>>,
+    best: Option<Candidate>,
+    generation: u64,
+}
+
+impl EvolutionSearch {
+    /// None for simulations with nothing to search or that take no actions
+    /// (no reward to score).
+    pub fn new(info: &'static SimInfo, config: SearchConfig) -> Option<Self> {
+        info.build().as_experimentable()?;
+        let numeric = info.schema.iter().filter(|spec| spec.is_numeric());
+        let (genes, seeds): (Vec<_>, Vec<_>) = numeric.partition(|spec| spec.max - spec.min < 1e12);
+        if genes.is_empty() && seeds.is_empty() {
+            return None;
+        }
+        // Start from the defaults
+        let mean = genes.iter().map(|spec| ((spec.default - spec.min) / (spec.max - spec.min).max(1e-12)).clamp(0.0, 1.0)).collect();
+        let n = genes.len();
+        Some(Self {
+            info,
+            setup: Setup::registry(info),
+            rng: SimRng::new(config.seed),
+            sigma: config.sigma,
+            config,
+            genes,
+            seeds,
+            mean,
+            diag: vec![1.0; n],
+            path_sigma: vec![0.0; n],
+            path_c: vec![0.0; n],
+            samples: Vec::new(),
+            current: None,
+            archive: Vec::new(),
+            best: None,
+            generation: 0,
+        })
+    }
+
+    pub fn info(&self) -> &'static SimInfo {
+        self.info
+    }
+
+    /// Evaluate the candidates still to start in copies of `setup`
+    pub fn set_setup(&mut self, setup: Setup) {
+        self.setup = setup;
+    }
+
+    /// Best candidate found so far
+    pub fn best(&self) -> Option<&Candidate> {
+        self.best.as_ref()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// `candidate` as an archive entry credited to `source`, reproducible from
+    /// a fresh build by its params (the setup's, then its own). None if it has
+    /// no descriptor.
+    pub fn elite(&self, candidate: &Candidate, source: &str) -> Option<Elite> {
+        Some(Elite {
+            sim: self.info.key.to_string(),
+            params: candidate.setup_params(self.info, &self.setup),
+            fitness: candidate.fitness,
+            descriptor: candidate.descriptor?,
+            source: source.to_string(),
+            step: self.config.eval_steps.max(1) as u64,
+            thumbnail: candidate.thumbnail.clone(),
+            view_only: false,
+        })
+    }
+
+    fn lambda(&self) -> usize {
+        match self.config.population {
+            0 => 4 + (3.0 * (self.genes.len().max(1) as f64).ln()).floor() as usize,
+            p => p.max(2),
+        }
+    }
+
+    /// Run up to `steps` simulation steps of evaluation; returns a report when
+    /// that finishes a generation.
+    pub fn advance(&mut self, mut steps: usize) -> Option<GenerationReport> {
+        let eval_steps = self.config.eval_steps.max(1);
+        while steps > 0 {
+            if self.samples.is_empty() {
+                self.sample_generation();
+            }
+            let Some(eval) = self.current.as_mut() else {
+                self.start_next();
+                continue;
+            };
+            let run = steps.min(eval_steps - eval.steps);
+            for _ in 0..run {
+                eval.sim.step();
+                eval.steps += 1;
+                if eval.steps + MAX_PERIOD >= eval_steps {
+                    if let Some(period) = eval.periods.push(&eval.sim.get_state()) {
+                        eval.period = Some(eval.period.map_or(period, |p| p.min(period)));
+                    }
+                }
+                if eval.steps * 2 > eval_steps {
+                    if let Some(exp) = eval.sim.as_experimentable() {
+                        eval.reward += exp.reward();
+                        eval.counted += 1;
+                    }
+                }
+            }
+            steps -= run;
+            if eval.steps >= eval_steps {
+                self.finish_current();
+                if self.samples.iter().all(|(_, _, c)| c.is_some()) {
+                    return Some(self.update());
+                }
+            }
+        }
+        None
+    }
+
+    fn sample_generation(&mut self) {
+        let lambda = self.lambda();
+        self.samples = (0..lambda)
+            .map(|_| {
+                let z: Vec<f64> = (0..self.genes.len()).map(|_| self.rng.gaussian()).collect();
+                let y = z.iter().zip(&self.diag).map(|(z, d)| z * d.sqrt()).collect();
+                (z, y, None)
+            })
+            .collect();
+    }
+
+    /// Build and configure the next unevaluated sample.
+    fn start_next(&mut self) {
+        let Some(index) = self.samples.iter().position(|(_, _, c)| c.is_none()) else { return };
+        let y = &self.samples[index].1;
+        let mut params: Vec<(String, f64)> = self
+            .genes
+            .iter()
+            .zip(&self.mean)
+            .zip(y)
+            .map(|((spec, m), y)| {
+                let t = (m + self.sigma * y).clamp(0.0, 1.0);
+                let value = spec.min + t * (spec.max - spec.min);
+                (spec.name.to_string(), if spec.integer { value.round() } else { value })
+            })
+            .collect();
+        for spec in &self.seeds {
+            params.push((spec.name.to_string(), (spec.min + self.rng.below(1 << 31) as f64).min(spec.max)));
+        }
+        let candidate = Candidate { params, fitness: 0.0, novelty: 0.0, behaviour: Vec::new(), descriptor: None, thumbnail: None };
+        let sim = candidate.build(self.info, &self.setup);
+        self.samples[index].2 = Some(candidate);
+        self.current = Some(Evaluation {
+            sim,
+            index,
+            steps: 0,
+            reward: 0.0,
+            counted: 0,
+            periods: PeriodDetector::default(),
+            period: None,
+        });
+    }
+
+    fn finish_current(&mut self) {
+        let Some(mut eval) = self.current.take() else { return };
+        let state = eval.sim.get_state();
+        let descriptor = archive::descriptor(&state, eval.period);
+        // Not the observation by default: features can include the genome itself (Gray-Scott's f, k)
+        let behaviour = match descriptor {
+            Some(descriptor) => descriptor.to_vec(),
+            None => eval.sim.as_experimentable().map(|exp| exp.observe().features()).unwrap_or_default(),
+        };
+        // A run that blew up earns nothing and sits at the origin, so the sorts stay total
+        let finite = |v: f64| if v.is_finite() { v } else { 0.0 };
+        if let Some(candidate) = self.samples[eval.index].2.as_mut() {
+            candidate.fitness = finite(if eval.counted > 0 { eval.reward / eval.counted as f64 } else { 0.0 });
+            candidate.behaviour = behaviour.into_iter().map(finite).collect();
+            candidate.descriptor = descriptor;
+            candidate.thumbnail = descriptor.and_then(|_| archive::thumbnail(&state));
+        }
+    }
+
+    /// Mean distance to the nearest archived behaviours, each feature scaled by its spread.
+    fn novelty(&self, behaviour: &[f64]) -> f64 {
+        let dims = behaviour.len();
+        let others: Vec<&Vec<f64>> = self.archive.iter().filter(|b| b.len() == dims).collect();
+        if others.is_empty() {
+            return 0.0;
+        }
+        let scale: Vec<f64> = (0..dims)
+            .map(|i| {
+                let mean = others.iter().map(|b| b[i]).sum::<f64>() / others.len() as f64;
+                let var = others.iter().map(|b| (b[i] - mean).powi(2)).sum::<f64>() / others.len() as f64;
+                var.sqrt().max(1e-9)
+            })
+            .collect();
+        let mut distances: Vec<f64> = others
+            .iter()
+            .map(|b| b.iter().zip(behaviour).zip(&scale).map(|((a, b), s)| ((a - b) / s).powi(2)).sum::<f64>().sqrt())
+            .collect();
+        distances.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let k = self.config.neighbours.clamp(1, distances.len());
+        distances[..k].iter().sum::<f64>() / k as f64
+    }
+
+    /// Score the finished generation and move the distribution (sep-CMA-ES).
+    fn update(&mut self) -> GenerationReport {
+        self.generation += 1;
+        let samples = std::mem::take(&mut self.samples);
+        let mut scored: Vec<(Vec<f64>, Vec<f64>, Candidate)> =
+            samples.into_iter().filter_map(|(z, y, c)| c.map(|c| (z, y, c))).collect();
+        for (_, _, c) in &mut scored {
+            c.novelty = self.novelty(&c.behaviour);
+        }
+        for (_, _, c) in &scored {
+            self.archive.push(c.behaviour.clone());
+        }
+        let overflow = self.archive.len().saturating_sub(self.config.archive.max(1));
+        self.archive.drain(..overflow);
+
+        // Rank on fitness and novelty, best first
+        let fitness_rank = ranks(&scored.iter().map(|s| s.2.fitness).collect::<Vec<_>>());
+        let novelty_rank = ranks(&scored.iter().map(|s| s.2.novelty).collect::<Vec<_>>());
+        let mut order: Vec<usize> = (0..scored.len()).collect();
+        let score = |i: usize| fitness_rank[i] + self.config.novelty_weight * novelty_rank[i];
+        order.sort_by(|&a, &b| score(b).partial_cmp(&score(a)).unwrap_or(std::cmp::Ordering::Equal));
+
+        let n = self.genes.len();
+        if n > 0 {
+            let mu = (scored.len() / 2).max(1);
+            let raw: Vec<f64> = (0..mu).map(|i| (mu as f64 + 0.5).ln() - ((i + 1) as f64).ln()).collect();
+            let total: f64 = raw.iter().sum();
+            let weights: Vec<f64> = raw.iter().map(|w| w / total).collect();
+            let mu_eff = 1.0 / weights.iter().map(|w| w * w).sum::<f64>();
+            let nf = n as f64;
+            let c_sigma = (mu_eff + 2.0) / (nf + mu_eff + 5.0);
+            let d_sigma = 1.0 + 2.0 * (((mu_eff - 1.0) / (nf + 1.0)).sqrt() - 1.0).max(0.0) + c_sigma;
+            let c_c = (4.0 + mu_eff / nf) / (nf + 4.0 + 2.0 * mu_eff / nf);
+            // Separable variant: the diagonal can learn (n + 2) / 3 times faster
+            let c_1 = (2.0 / ((nf + 1.3).powi(2) + mu_eff) * (nf + 2.0) / 3.0).min(1.0);
+            let c_mu = (2.0 * (mu_eff - 2.0 + 1.0 / mu_eff) / ((nf + 2.0).powi(2) + mu_eff) * (nf + 2.0) / 3.0)
+                .clamp(0.0, 1.0 - c_1);
+
+            let mut y_w = vec![0.0; n];
+            let mut z_w = vec![0.0; n];
+            for (w, &i) in weights.iter().zip(&order) {
+                let (z, y) = (&scored[i].0, &scored[i].1);
+                for (j, (yw, zw)) in y_w.iter_mut().zip(z_w.iter_mut()).enumerate() {
+                    *yw += w * y[j];
+                    *zw += w * z[j];
+                }
+            }
+            for (j, mean) in self.mean.iter_mut().enumerate() {
+                *mean = (*mean + self.sigma * y_w[j]).clamp(0.0, 1.0);
+                self.path_sigma[j] =
+                    (1.0 - c_sigma) * self.path_sigma[j] + (c_sigma * (2.0 - c_sigma) * mu_eff).sqrt() * z_w[j];
+                self.path_c[j] = (1.0 - c_c) * self.path_c[j] + (c_c * (2.0 - c_c) * mu_eff).sqrt() * y_w[j];
+                let rank_mu: f64 = weights.iter().zip(&order).map(|(w, &i)| w * scored[i].1[j].powi(2)).sum();
+                self.diag[j] = ((1.0 - c_1 - c_mu) * self.diag[j] + c_1 * self.path_c[j].powi(2) + c_mu * rank_mu).max(1e-12);
+            }
+            let norm = self.path_sigma.iter().map(|p| p * p).sum::<f64>().sqrt();
+            let expected = nf.sqrt() * (1.0 - 1.0 / (4.0 * nf) + 1.0 / (21.0 * nf * nf));
+            self.sigma = (self.sigma * ((c_sigma / d_sigma) * (norm / expected - 1.0)).exp()).clamp(1e-4, 1.0);
+        }
+
+        let best = scored
+            .iter()
+            .map(|s| &s.2)
+            .max_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap_or(std::cmp::Ordering::Equal))
+            .cloned()
+            .unwrap_or_else(|| Candidate { params: Vec::new(), fitness: 0.0, novelty: 0.0, behaviour: Vec::new(), descriptor: None, thumbnail: None });
+        let most_novel = scored
+            .iter()
+            .map(|s| &s.2)
+            .max_by(|a, b| a.novelty.partial_cmp(&b.novelty).unwrap_or(std::cmp::Ordering::Equal))
+            .cloned()
+            .unwrap_or_else(|| best.clone());
+        let new_record = self.best.as_ref().is_none_or(|b| best.fitness > b.fitness);
+        if new_record {
+            self.best = Some(best.clone());
+        }
+        let sigma = if n == 0 { 0.0 } else { self.sigma * self.diag.iter().map(|d| d.sqrt()).sum::<f64>() / n as f64 };
+        GenerationReport { generation: self.generation, best, most_novel, new_record, sigma }
+    }
+}
+
+/// Rank of each value, 0 for the lowest; tied values share their mean rank
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+    let mut out = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let tied = order[start..].iter().take_while(|&&i| values[i] == values[order[start]]).count().max(1);
+        let rank = start as f64 + (tied - 1) as f64 / 2.0;
+        for &i in &order[start..start + tied] {
+            out[i] = rank;
+        }
+        start += tied;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    fn lorenz(config: SearchConfig) -> EvolutionSearch {
+        EvolutionSearch::new(registry::find("lorenz").unwrap(), config).unwrap()
+    }
+
+    #[test]
+    fn ranks_share_ties() {
+        assert_eq!(ranks(&[3.0, 1.0, 3.0, 2.0]), [2.5, 0.0, 2.5, 1.0]);
+        assert_eq!(ranks(&[0.5; 4]), [1.5; 4]);
+        assert_eq!(ranks(&[2.0, 1.0, 0.0]), [2.0, 1.0, 0.0]);
+        assert!(ranks(&[]).is_empty());
+    }
+
+    #[test]
+    fn advance_reports_once_per_generation() {
+        let (population, eval_steps) = (5, 20);
+        let mut search = lorenz(SearchConfig { population, eval_steps, ..SearchConfig::default() });
+        let mut reports = Vec::new();
+        for step in 1..=3 * population * eval_steps {
+            if let Some(report) = search.advance(1) {
+                reports.push((step, report.generation));
+            }
+        }
+        let per_generation = population * eval_steps;
+        assert_eq!(reports, [(per_generation, 1), (2 * per_generation, 2), (3 * per_generation, 3)]);
+        // The same in one go
+        assert_eq!(search.advance(per_generation).map(|r| r.generation), Some(4));
+        assert_eq!(search.archive.len(), 4 * population);
+    }
+
+    #[test]
+    fn candidates_run_on_the_setup() {
+        let info = registry::find("lorenz").unwrap();
+        let setup = Setup {
+            build: Rc::new(move || info.build()),
+            params: vec![("rho".into(), ParamValue::Float(5.0)), ("reward".into(), ParamValue::String("target:x=1;scale=2".into()))],
+        };
+        let candidate = Candidate {
+            params: vec![("rho".into(), 9.0), ("sigma".into(), 3.0)],
+            fitness: 0.0,
+            novelty: 0.0,
+            behaviour: Vec::new(),
+            descriptor: None,
+            thumbnail: None,
+        };
+        let params: Vec<String> = candidate.setup_params(info, &setup).iter().map(|(k, v)| format!("{}={:?}", k, v)).collect();
+        assert_eq!(params, ["reward=String(\"target:x=1;scale=2\")", "rho=Float(9.0)", "sigma=Float(3.0)"]);
+        // Scored by the setup's reward, not the native one (20 near the origin)
+        let mut sim = candidate.build(info, &setup);
+        sim.step();
+        let reward = sim.as_experimentable().unwrap().reward();
+        assert!(reward > 0.0 && reward <= 1.0, "{}", reward);
+    }
+
+    #[test]
+    fn seeded_search_improves_fitness() {
+        let config = SearchConfig { eval_steps: 400, novelty_weight: 0.0, seed: 3, ..SearchConfig::default() };
+        let mut search = lorenz(config);
+        let info = search.info();
+        // Settle on x = 5: a stable fixed point with beta (rho - 1) = 25
+        search.set_setup(Setup {
+            build: Rc::new(move || info.build()),
+            params: vec![("reward".into(), ParamValue::String("target:x=5;scale=2".into()))],
+        });
+        let mut bests = Vec::new();
+        while bests.len() < 40 {
+            if let Some(report) = search.advance(1000) {
+                bests.push(report.best.fitness);
+            }
+        }
+        let late = bests[35..].iter().copied().fold(f64::MIN, f64::max);
+        assert!(late > bests[0], "first generation {:.3}, last five {:.3}", bests[0], late);
+        assert!(search.best().is_some_and(|best| best.fitness > 0.5), "{:?}", search.best());
+    }
+}
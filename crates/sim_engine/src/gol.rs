@@ -1,6 +1,8 @@
 //! High-performance Conway's Game of Life using macroquad-compatible HashLife via `ready`
 
-use super::{ParamValue, SimState, Simulation, Experimentable, Action, Observation};
+use super::{ParamValue, SimState, Simulation, Experimentable, Action, ActionSpace, Observation};
+use crate::observation::FIELD_SIDE;
+use crate::rng::SimRng;
 use ready::{
     CellPattern, HashLife, MacroCell, Node, Pattern, PatternID, Universe, UniverseExt,
 };
@@ -23,6 +25,39 @@ impl GameOfLife {
         ).unwrap();
         r_pentomino.id()
     }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Wipe the universe back to an empty plane.
+    pub fn clear(&mut self) {
+        self.universe = Universe::new();
+        self.generation = 0;
+    }
+
+    /// Replace the universe with a random `size`x`size` soup centred on the origin.
+    pub fn seed_soup(&mut self, size: u32, density: f64, rng: &mut SimRng) {
+        self.clear();
+        let half = size as i64 / 2;
+        for y in 0..size as i64 {
+            for x in 0..size as i64 {
+                if rng.chance(density) {
+                    self.universe.set_cell(x - half, y - half, true);
+                }
+            }
+        }
+    }
+
+    /// Row-major alive mask for an arbitrary world-space window.
+    pub fn render_window(&self, x: i64, y: i64, width: u32, height: u32) -> Vec<bool> {
+        self.universe
+            .render(x, y, width as i64, height as i64)
+            .iter()
+            .flatten()
+            .map(|&cell| cell == MacroCell::Alive)
+            .collect()
+    }
 }
 
 impl Simulation for GameOfLife {
@@ -118,14 +153,20 @@ fn apply_action(&mut self, action: Action) {
     }
 }
 
+    fn action_space(&self) -> ActionSpace {
+        // Cells are addressed within the view
+        ActionSpace::new().with_flip_cell(self.view_height_cells as usize, self.view_width_cells as usize)
+    }
+
     fn observe(&self) -> Observation {
-        // Return alive count estimate (very rough for hashlife, but usable)
-        // For now, just use view dimensions as dummy
-        Observation::GridSummary {
-            alive: 0, // Hashlife counting is expensive, assume 0 for mock
-            width: self.view_width_cells as usize,
-            height: self.view_height_cells as usize,
-        }
+        // Live cells in the view (counted off the render, not the whole universe)
+        // and a coarse density map of it
+        let SimState::Grid { cells, .. } = self.get_state() else { return Observation::None };
+        let stride = self.view_width_cells.next_power_of_two() as usize;
+        let values: Vec<f64> = cells.iter().map(|&alive| if alive { 1.0 } else { 0.0 }).collect();
+        let alive = values.iter().sum::<f64>();
+        Observation::named(&[("alive", alive), ("generation", self.generation as f64)])
+            .with("density", Observation::field(stride, values.len() / stride.max(1), &values, FIELD_SIDE))
     }
 
     fn reward(&self) -> f64 {
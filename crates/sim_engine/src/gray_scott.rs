@@ -1,6 +1,119 @@
-use super::{ParamValue, SimState, Simulation, Experimentable, Action, Observation};
+use super::{ParamValue, SimState, Simulation, Experimentable, Action, ActionSpace, Observation};
+use crate::registry::GRAY_SCOTT_SCHEMA;
+use crate::observation::FIELD_SIDE;
 use serde::Serialize;
 use std::f64::consts::PI;
+use crate::gray_scott_presets::{self, GrayScottPreset};
+use crate::rng::SimRng;
+use crate::stencil::{laplacian, Real, RowTriple};
+pub use crate::stencil::Boundary;
+
+/// How V is injected into the homogeneous U = 1 state.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InitialCondition {
+    /// Square of side 2*half in the middle of the grid
+    CenterSquare { half: usize },
+    /// `count` squares of side 2*radius at seeded random positions
+    Spots { count: usize, radius: usize, seed: u64 },
+    /// Random V in [0, amplitude) on `scale`x`scale` blocks.
+    /// Per-cell noise (scale 1) mostly diffuses away before anything nucleates,
+    /// and amplitudes much above ~0.3 starve U everywhere at once.
+    Noise { amplitude: f64, scale: usize, seed: u64 },
+    /// Parallel bands of V, `thickness` cells wide, every `spacing` cells
+    Stripes { spacing: usize, thickness: usize, vertical: bool },
+    /// Arbitrary 0..1 image, stretched over the grid (nearest neighbour)
+    Mask { width: usize, height: usize, values: Vec<f64> },
+}
+
+impl InitialCondition {
+    /// Default-parameter generator by name, for the string param API.
+    pub fn from_name(name: &str, seed: u64) -> Option<Self> {
+        match name {
+            "center" => Some(Self::CenterSquare { half: 10 }),
+            "spots" => Some(Self::Spots { count: 12, radius: 4, seed }),
+            "noise" => Some(Self::Noise { amplitude: 0.25, scale: 4, seed }),
+            "stripes" => Some(Self::Stripes { spacing: 16, thickness: 3, vertical: false }),
+            _ => None,
+        }
+    }
+
+    /// Build a mask from RGBA8 pixels (e.g. canvas `ImageData`) using luminance.
+    pub fn mask_from_rgba(width: usize, height: usize, rgba: &[u8]) -> Self {
+        Self::Mask { width, height, values: luminance(width, height, rgba) }
+    }
+
+    /// The same generator with its random seed (if it has one) replaced.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        if let Self::Spots { seed: s, .. } | Self::Noise { seed: s, .. } = &mut self {
+            *s = seed;
+        }
+        self
+    }
+}
+
+/// 0..1 luminance of RGBA8 pixels
+fn luminance(width: usize, height: usize, rgba: &[u8]) -> Vec<f64> {
+    rgba.chunks_exact(4)
+        .take(width * height)
+        .map(|px| (0.299 * px[0] as f64 + 0.587 * px[1] as f64 + 0.114 * px[2] as f64) / 255.0)
+        .collect()
+}
+
+/// A reaction parameter over space: one value everywhere, or one value per cell.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParamField {
+    Uniform(f64),
+    /// Linear ramp from the left edge (`from`) to the right edge (`to`)
+    LinearX { from: f64, to: f64 },
+    /// Linear ramp from the top edge (`from`) to the bottom edge (`to`)
+    LinearY { from: f64, to: f64 },
+    /// Arbitrary map, stretched over the grid (nearest neighbour)
+    Map { width: usize, height: usize, values: Vec<f64> },
+}
+
+impl ParamField {
+    /// "0.055", "x:0.03:0.07" or "y:0.0:0.08" (for the string param API)
+    pub fn parse(spec: &str) -> Option<Self> {
+        let parts: Vec<&str> = spec.split(':').map(str::trim).collect();
+        match parts.as_slice() {
+            [v] => v.parse().ok().map(Self::Uniform),
+            ["uniform", v] => v.parse().ok().map(Self::Uniform),
+            ["x", from, to] => Some(Self::LinearX { from: from.parse().ok()?, to: to.parse().ok()? }),
+            ["y", from, to] => Some(Self::LinearY { from: from.parse().ok()?, to: to.parse().ok()? }),
+            _ => None,
+        }
+    }
+
+    /// Map from RGBA8 pixels: luminance 0..1 stretched over `from..to`.
+    pub fn map_from_rgba(width: usize, height: usize, rgba: &[u8], from: f64, to: f64) -> Self {
+        let values = luminance(width, height, rgba).into_iter().map(|l| from + (to - from) * l).collect();
+        Self::Map { width, height, values }
+    }
+
+    /// Per-cell values for a `width`x`height` grid, or None if uniform.
+    fn bake(&self, width: usize, height: usize) -> Option<Vec<f64>> {
+        let ramp = |t: usize, n: usize, from: f64, to: f64| {
+            from + (to - from) * t as f64 / (n.max(2) - 1) as f64
+        };
+        match self {
+            ParamField::Uniform(_) => None,
+            ParamField::LinearX { from, to } => Some(
+                (0..width * height).map(|i| ramp(i % width, width, *from, *to)).collect(),
+            ),
+            ParamField::LinearY { from, to } => Some(
+                (0..width * height).map(|i| ramp(i / width, height, *from, *to)).collect(),
+            ),
+            ParamField::Map { width: mw, height: mh, values } => {
+                if *mw == 0 || *mh == 0 || values.len() < mw * mh { return None; }
+                Some(
+                    (0..width * height)
+                        .map(|i| values[((i / width) * mh / height) * mw + (i % width) * mw / width])
+                        .collect(),
+                )
+            }
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct GrayScott {
@@ -19,6 +132,18 @@ pub struct GrayScott {
     da: f64, // Diffusion A (U)
     db: f64, // Diffusion B (V)
     dt: f64, // Time step
+
+    // Per-cell overrides of f/k (None = uniform). `f`/`k` then hold the field mean.
+    f_map: Option<Vec<f64>>,
+    k_map: Option<Vec<f64>>,
+
+    // Configuration
+    boundary: Boundary,
+    precision: Precision,
+    initial: InitialCondition,
+    /// Seed from the "init_seed" param, applied to every random generator set
+    /// afterwards (None keeps each generator's own seed)
+    init_seed: Option<u64>,
 }
 
 impl GrayScott {
@@ -29,6 +154,12 @@ impl GrayScott {
     // Sum = 0.0
     
     pub fn init(width: usize, height: usize) -> Self {
+        let preset = gray_scott_presets::find(gray_scott_presets::DEFAULT_PRESET)
+            .expect("default preset is in the catalogue");
+        Self::from_preset(width, height, preset)
+    }
+
+    pub fn from_preset(width: usize, height: usize, preset: &GrayScottPreset) -> Self {
         let size = width * height;
         let mut sim = Self {
             width,
@@ -37,38 +168,205 @@ impl GrayScott {
             v: vec![0.0; size], // V starts empty
             next_u: vec![1.0; size],
             next_v: vec![0.0; size],
-            f: 0.055, // "Coral" preset
-            k: 0.062,
-            da: 1.0,
-            db: 0.5,
+            f: preset.f,
+            k: preset.k,
+            da: preset.da,
+            db: preset.db,
             dt: 1.0,
+            f_map: None,
+            k_map: None,
+            boundary: Boundary::Periodic,
+            precision: Precision::F64,
+            initial: preset.seed.clone(),
+            init_seed: None,
         };
-        sim.seed_center();
+        sim.reset();
         sim
     }
 
-    fn seed_center(&mut self) {
-        let cx = self.width / 2;
-        let cy = self.height / 2;
-        let r = 10;
-        for y in (cy - r)..(cy + r) {
-            for x in (cx - r)..(cx + r) {
-                let idx = y * self.width + x;
-                if idx < self.u.len() {
-                    self.v[idx] = 1.0; // Inject V
+    /// Munafo-style map of the whole (f, k) plane in one image: k varies along x,
+    /// f along y. Uses Pearson/Munafo diffusion rates and noise so every region
+    /// has something to grow from.
+    pub fn parameter_map(width: usize, height: usize, f_range: (f64, f64), k_range: (f64, f64)) -> Self {
+        let (da, db) = gray_scott_presets::PEARSON_DIFFUSION;
+        let mut sim = Self::init(width, height)
+            .with_initial_condition(InitialCondition::Noise { amplitude: 0.25, scale: 4, seed: 0 });
+        sim.da = da;
+        sim.db = db;
+        sim.set_f_field(ParamField::LinearY { from: f_range.0, to: f_range.1 });
+        sim.set_k_field(ParamField::LinearX { from: k_range.0, to: k_range.1 });
+        sim
+    }
+
+    pub fn set_f_field(&mut self, field: ParamField) {
+        if let Some((nominal, map)) = self.bake_field(&field) {
+            self.f = nominal;
+            self.f_map = map;
+        }
+    }
+
+    pub fn set_k_field(&mut self, field: ParamField) {
+        if let Some((nominal, map)) = self.bake_field(&field) {
+            self.k = nominal;
+            self.k_map = map;
+        }
+    }
+
+    // (nominal value, per-cell map). None if the field can't be used (e.g. empty map).
+    fn bake_field(&self, field: &ParamField) -> Option<(f64, Option<Vec<f64>>)> {
+        if let ParamField::Uniform(v) = field {
+            return Some((*v, None));
+        }
+        let map = field.bake(self.width, self.height)?;
+        let mean = map.iter().sum::<f64>() / map.len().max(1) as f64;
+        Some((mean, Some(map)))
+    }
+
+    pub fn with_boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    pub fn with_precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn with_initial_condition(mut self, initial: InitialCondition) -> Self {
+        self.reseed(initial);
+        self
+    }
+
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
+    }
+
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    pub fn set_precision(&mut self, precision: Precision) {
+        self.precision = precision;
+    }
+
+    pub fn precision(&self) -> Precision {
+        self.precision
+    }
+
+    pub fn initial_condition(&self) -> &InitialCondition {
+        &self.initial
+    }
+
+    /// Switch parameters to a preset and restart from its recommended seed
+    /// (reseeded if "init_seed" was set).
+    pub fn apply_preset(&mut self, preset: &GrayScottPreset) {
+        self.set_f_field(ParamField::Uniform(preset.f));
+        self.set_k_field(ParamField::Uniform(preset.k));
+        self.da = preset.da;
+        self.db = preset.db;
+        self.reseed_keeping_seed(preset.seed.clone());
+    }
+
+    /// `reseed`, with the "init_seed" param (if set) taking over the generator's seed
+    fn reseed_keeping_seed(&mut self, initial: InitialCondition) {
+        let initial = match self.init_seed {
+            Some(seed) => initial.with_seed(seed),
+            None => initial,
+        };
+        self.reseed(initial);
+    }
+
+    /// Make `initial` the current initial condition and restart from it.
+    pub fn reseed(&mut self, initial: InitialCondition) {
+        self.initial = initial;
+        self.reset();
+    }
+
+    /// Back to the homogeneous state (U = 1, V = 0), then inject V per the initial condition.
+    pub fn reset(&mut self) {
+        self.u.fill(1.0);
+        self.v.fill(0.0);
+        let (w, h) = (self.width, self.height);
+        match self.initial.clone() {
+            InitialCondition::CenterSquare { half } => {
+                self.fill_square(w / 2, h / 2, half);
+            }
+            InitialCondition::Spots { count, radius, seed } => {
+                let mut rng = SimRng::new(seed);
+                for _ in 0..count {
+                    let cx = rng.below(w);
+                    let cy = rng.below(h);
+                    self.fill_square(cx, cy, radius);
+                }
+            }
+            InitialCondition::Noise { amplitude, scale, seed } => {
+                let mut rng = SimRng::new(seed);
+                let scale = scale.max(1);
+                let blocks_x = w.div_ceil(scale);
+                let blocks: Vec<f64> = (0..blocks_x * h.div_ceil(scale))
+                    .map(|_| amplitude.clamp(0.0, 1.0) * rng.next_f64())
+                    .collect();
+                for y in 0..h {
+                    for x in 0..w {
+                        self.v[y * w + x] = blocks[(y / scale) * blocks_x + x / scale];
+                    }
                 }
             }
+            InitialCondition::Stripes { spacing, thickness, vertical } => {
+                let spacing = spacing.max(1);
+                for y in 0..h {
+                    for x in 0..w {
+                        let along = if vertical { x } else { y };
+                        if along % spacing < thickness {
+                            self.v[y * w + x] = 1.0;
+                        }
+                    }
+                }
+            }
+            InitialCondition::Mask { width: mw, height: mh, values } => {
+                if mw == 0 || mh == 0 || values.len() < mw * mh { return; }
+                for y in 0..h {
+                    for x in 0..w {
+                        let m = values[(y * mh / h) * mw + x * mw / w];
+                        self.v[y * w + x] = m.clamp(0.0, 1.0);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn params(&self) -> (f64, f64) {
+        (self.f, self.k)
+    }
+
+    fn fill_square(&mut self, cx: usize, cy: usize, r: usize) {
+        let y_end = (cy + r).min(self.height);
+        let x_end = (cx + r).min(self.width);
+        for y in cy.saturating_sub(r)..y_end {
+            for x in cx.saturating_sub(r)..x_end {
+                self.v[y * self.width + x] = 1.0; // Inject V
+            }
         }
     }
     
-    // Helper for toroidal wrapping (wrapping around edges)
+    // Neighbour lookup honouring the boundary condition.
+    // Returns (u, v) so Dirichlet can answer for cells that don't exist.
     #[inline(always)]
-    fn idx(&self, x: isize, y: isize) -> usize {
+    fn sample(&self, x: isize, y: isize) -> (f64, f64) {
         let w = self.width as isize;
         let h = self.height as isize;
-        let wrapped_x = (x + w) % w;
-        let wrapped_y = (y + h) % h;
-        (wrapped_y * w + wrapped_x) as usize
+        let (x, y) = match self.boundary {
+            Boundary::Periodic => ((x + w) % w, (y + h) % h),
+            Boundary::Neumann => (x.clamp(0, w - 1), y.clamp(0, h - 1)),
+            Boundary::Dirichlet { u, v } => {
+                if x < 0 || y < 0 || x >= w || y >= h {
+                    return (u, v);
+                }
+                (x, y)
+            }
+        };
+        let i = (y * w + x) as usize;
+        (self.u[i], self.v[i])
     }
 }
 
@@ -78,13 +376,90 @@ impl Simulation for GrayScott {
     }
 
     fn step(&mut self) {
+        self.step_rows();
+    }
+
+    fn get_state(&self) -> SimState {
+        // We map the 'V' chemical to a 0.0-1.0 intensity grid for rendering
+        SimState::FloatGrid {
+            width: self.width as u32,
+            height: self.height as u32,
+            values: self.v.clone(),
+        }
+    }
+
+    fn set_param(&mut self, key: &str, value: ParamValue) {
+        match (key, value) {
+            ("f", ParamValue::Float(v)) => self.set_f_field(ParamField::Uniform(v)),
+            ("k", ParamValue::Float(v)) => self.set_k_field(ParamField::Uniform(v)),
+            // Spatial fields as strings, e.g. "y:0.0:0.08" (see ParamField::parse)
+            ("f_field", ParamValue::String(spec)) => {
+                if let Some(field) = ParamField::parse(&spec) { self.set_f_field(field); }
+            }
+            ("k_field", ParamValue::String(spec)) => {
+                if let Some(field) = ParamField::parse(&spec) { self.set_k_field(field); }
+            }
+            // Loaded maps as images: black is the bottom of the param's schema range, white the top
+            ("f_field" | "k_field", ParamValue::Image { width, height, rgba }) => {
+                let name = &key[..1];
+                let Some(spec) = GRAY_SCOTT_SCHEMA.iter().find(|spec| spec.name == name) else { return };
+                let field = ParamField::map_from_rgba(width, height, &rgba, spec.min, spec.max);
+                if name == "f" { self.set_f_field(field) } else { self.set_k_field(field) }
+            }
+            ("preset", ParamValue::String(name)) => {
+                if let Some(preset) = gray_scott_presets::find(&name) {
+                    self.apply_preset(preset);
+                }
+            }
+            // "periodic", "neumann", "dirichlet" or "dirichlet:U:V"
+            ("boundary", ParamValue::String(name)) => {
+                if let Some(boundary) = Boundary::from_name(&name) {
+                    self.boundary = boundary;
+                }
+            }
+            // "init" restarts from a named generator or an image mask; "init_seed"
+            // reseeds the random ones, whichever order the two arrive in
+            ("init", ParamValue::String(name)) => {
+                if let Some(initial) = InitialCondition::from_name(&name, 0) {
+                    self.reseed_keeping_seed(initial);
+                }
+            }
+            ("init", ParamValue::Image { width, height, rgba }) => {
+                self.reseed(InitialCondition::mask_from_rgba(width, height, &rgba));
+            }
+            ("init_seed", ParamValue::Int(seed)) => {
+                self.init_seed = Some(seed as u64);
+                self.reseed_keeping_seed(self.initial.clone());
+            }
+            _ => {}
+        }
+    }
+
+    fn as_experimentable(&mut self) -> Option<&mut dyn Experimentable> {
+        Some(self)
+    }
+}
+
+// --- Stepping Kernel ---
+//
+// `step` works a row at a time on slices: the interior of each row is one
+// branch-free loop over equal-length slices (bounds checks vanish, LLVM
+// vectorises it), and only the two edge columns go through the boundary
+// lookup. Rows are independent, so with the `parallel` feature they are
+// spread over rayon's pool on native targets.
+//
+// Sums are accumulated in exactly the reference order (stencil row by row,
+// left to right, starting from 0.0) so results stay bit-identical to
+// `step_reference`. The same kernel also runs in f32 (`Precision::F32`),
+// which only agrees with the reference to within rounding.
+
+impl GrayScott {
+    /// Straightforward per-cell step, every neighbour fetched through `sample`.
+    /// This is the numerical reference: `step` in f64 must match it bit for bit.
+    pub fn step_reference(&mut self) {
         let w = self.width as isize;
         let h = self.height as isize;
 
-        // OPTIMIZATION: We explicitly iterate indices to avoid bounds checking overhead 
-        // in a tight loop if possible, but for safety + wrapping we use helper.
-        // (For maximum speed in Rust, we would use unsafe pointers or 1D iterators with windowing, 
-        // but this index math is sufficient for < 500x500 grids).
         for y in 0..h {
             for x in 0..w {
                 let i = (y * w + x) as usize;
@@ -104,16 +479,18 @@ impl Simulation for GrayScott {
                 ];
 
                 for &(dx, dy, weight) in &neighbors {
-                    let ni = self.idx(x + dx, y + dy);
-                    lap_u += self.u[ni] * weight;
-                    lap_v += self.v[ni] * weight;
+                    let (nu, nv) = self.sample(x + dx, y + dy);
+                    lap_u += nu * weight;
+                    lap_v += nv * weight;
                 }
 
                 let uvv = u * v * v;
+                let f = self.f_map.as_ref().map_or(self.f, |m| m[i]);
+                let k = self.k_map.as_ref().map_or(self.k, |m| m[i]);
                 
                 // Gray-Scott Reaction Equations
-                let du = (self.da * lap_u - uvv + self.f * (1.0 - u)) * self.dt;
-                let dv = (self.db * lap_v + uvv - (self.f + self.k) * v) * self.dt;
+                let du = (self.da * lap_u - uvv + f * (1.0 - u)) * self.dt;
+                let dv = (self.db * lap_v + uvv - (f + k) * v) * self.dt;
 
                 self.next_u[i] = (u + du).clamp(0.0, 1.0);
                 self.next_v[i] = (v + dv).clamp(0.0, 1.0);
@@ -124,27 +501,167 @@ impl Simulation for GrayScott {
         std::mem::swap(&mut self.v, &mut self.next_v);
     }
 
-    fn get_state(&self) -> SimState {
-        // We map the 'V' chemical to a 0.0-1.0 intensity grid for rendering
-        SimState::FloatGrid {
-            width: self.width as u32,
-            height: self.height as u32,
-            values: self.v.clone(),
-        }
+    /// Raw (U, V) fields, row-major.
+    pub fn fields(&self) -> (&[f64], &[f64]) {
+        (&self.u, &self.v)
     }
 
-    fn set_param(&mut self, key: &str, value: ParamValue) {
-        if let ParamValue::Float(v) = value {
-            match key {
-                "f" => self.f = v,
-                "k" => self.k = v,
-                _ => {}
+    fn step_rows(&mut self) {
+        let (w, h) = (self.width, self.height);
+        if w < 3 || h == 0 {
+            // No interior to speak of
+            return self.step_reference();
+        }
+        let boundary = self.boundary;
+
+        match self.precision {
+            Precision::F64 => {
+                let coeffs = Coeffs { da: self.da, db: self.db, dt: self.dt };
+                let rates = Rates { f: self.f, k: self.k, f_map: self.f_map.as_deref(), k_map: self.k_map.as_deref() };
+                sweep(&self.u, &self.v, &rates, &coeffs, boundary, w, &mut self.next_u, &mut self.next_v);
+            }
+            Precision::F32 => {
+                // Narrow on the way in, widen on the way out: the state stays f64 for everyone else
+                let narrow = |field: &[f64]| field.iter().map(|&x| x as f32).collect::<Vec<f32>>();
+                let (u, v) = (narrow(&self.u), narrow(&self.v));
+                let (f_map, k_map) = (self.f_map.as_deref().map(narrow), self.k_map.as_deref().map(narrow));
+                let coeffs = Coeffs { da: self.da as f32, db: self.db as f32, dt: self.dt as f32 };
+                let rates = Rates { f: self.f as f32, k: self.k as f32, f_map: f_map.as_deref(), k_map: k_map.as_deref() };
+                let (mut next_u, mut next_v) = (vec![0.0f32; w * h], vec![0.0f32; w * h]);
+                sweep(&u, &v, &rates, &coeffs, boundary, w, &mut next_u, &mut next_v);
+                for (out, x) in self.next_u.iter_mut().zip(&next_u).chain(self.next_v.iter_mut().zip(&next_v)) {
+                    *out = *x as f64;
+                }
             }
         }
+
+        std::mem::swap(&mut self.u, &mut self.next_u);
+        std::mem::swap(&mut self.v, &mut self.next_v);
     }
+}
 
-    fn as_experimentable(&mut self) -> Option<&mut dyn Experimentable> {
-        Some(self)
+/// Arithmetic precision of `step`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Precision {
+    /// Bit-identical to `step_reference`
+    #[default]
+    F64,
+    /// Stencil and reaction in f32 (twice the SIMD lanes). The fields are
+    /// still stored as f64, and agree with `step_reference` to f32 rounding.
+    F32,
+}
+
+#[derive(Clone, Copy)]
+struct Coeffs<T> {
+    da: T,
+    db: T,
+    dt: T,
+}
+
+/// Feed and kill rates: uniform, or per cell where a map is set
+struct Rates<'a, T> {
+    f: T,
+    k: T,
+    f_map: Option<&'a [T]>,
+    k_map: Option<&'a [T]>,
+}
+
+/// One step of the row kernel: reads `u`/`v`, writes `next_u`/`next_v`.
+#[allow(clippy::too_many_arguments)]
+fn sweep<T: Real>(
+    u: &[T],
+    v: &[T],
+    rates: &Rates<T>,
+    coeffs: &Coeffs<T>,
+    boundary: Boundary,
+    w: usize,
+    next_u: &mut [T],
+    next_v: &mut [T],
+) {
+    let h = u.len() / w;
+    let (ghost_u, ghost_v) = match boundary {
+        Boundary::Dirichlet { u, v } => (vec![T::of(u); w], vec![T::of(v); w]),
+        _ => (Vec::new(), Vec::new()),
+    };
+    let f_uniform = vec![rates.f; w];
+    let k_uniform = vec![rates.k; w];
+
+    let kernel = |y: usize, out_u: &mut [T], out_v: &mut [T]| {
+        let u_rows = RowTriple::at(u, &ghost_u, y, w, h, boundary);
+        let v_rows = RowTriple::at(v, &ghost_v, y, w, h, boundary);
+        let f = coeff_row(rates.f_map, &f_uniform, y, w);
+        let k = coeff_row(rates.k_map, &k_uniform, y, w);
+        react_row(&u_rows, &v_rows, f, k, coeffs, boundary, out_u, out_v);
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        next_u
+            .par_chunks_mut(w)
+            .zip(next_v.par_chunks_mut(w))
+            .enumerate()
+            .for_each(|(y, (out_u, out_v))| kernel(y, out_u, out_v));
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        for (y, (out_u, out_v)) in next_u.chunks_mut(w).zip(next_v.chunks_mut(w)).enumerate() {
+            kernel(y, out_u, out_v);
+        }
+    }
+}
+
+fn coeff_row<'a, T>(map: Option<&'a [T]>, uniform: &'a [T], y: usize, w: usize) -> &'a [T] {
+    match map {
+        Some(m) => &m[y * w..(y + 1) * w],
+        None => uniform,
+    }
+}
+
+/// Gray-Scott reaction for one cell -> clamped (u', v').
+#[inline(always)]
+fn react<T: Real>(u: T, v: T, lap_u: T, lap_v: T, f: T, k: T, c: &Coeffs<T>) -> (T, T) {
+    let uvv = u * v * v;
+    let du = (c.da * lap_u - uvv + f * (T::ONE - u)) * c.dt;
+    let dv = (c.db * lap_v + uvv - (f + k) * v) * c.dt;
+    ((u + du).clamp_unit(), (v + dv).clamp_unit())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn react_row<T: Real>(
+    u: &RowTriple<T>,
+    v: &RowTriple<T>,
+    f: &[T],
+    k: &[T],
+    c: &Coeffs<T>,
+    boundary: Boundary,
+    out_u: &mut [T],
+    out_v: &mut [T],
+) {
+    let w = out_u.len();
+    let (ghost_u, ghost_v) = match boundary {
+        Boundary::Dirichlet { u, v } => (T::of(u), T::of(v)),
+        _ => (T::ZERO, T::ZERO),
+    };
+
+    // Edge columns through the boundary lookup
+    for x in [0, w - 1] {
+        let nu = u.edge_neighbourhood(x, boundary, ghost_u);
+        let nv = v.edge_neighbourhood(x, boundary, ghost_v);
+        (out_u[x], out_v[x]) = react(nu[4], nv[4], laplacian(nu), laplacian(nv), f[x], k[x], c);
+    }
+
+    // Interior: equal-length shifted slices, no branches
+    let n = w - 2;
+    let (uu, um, ud) = (&u.up[..w], &u.mid[..w], &u.down[..w]);
+    let (vu, vm, vd) = (&v.up[..w], &v.mid[..w], &v.down[..w]);
+    let (f, k) = (&f[1..n + 1], &k[1..n + 1]);
+    let (out_u, out_v) = (&mut out_u[1..n + 1], &mut out_v[1..n + 1]);
+
+    for j in 0..n {
+        let lap_u = laplacian([uu[j], uu[j + 1], uu[j + 2], um[j], um[j + 1], um[j + 2], ud[j], ud[j + 1], ud[j + 2]]);
+        let lap_v = laplacian([vu[j], vu[j + 1], vu[j + 2], vm[j], vm[j + 1], vm[j + 2], vd[j], vd[j + 1], vd[j + 2]]);
+        (out_u[j], out_v[j]) = react(um[j + 1], vm[j + 1], lap_u, lap_v, f[j], k[j], c);
     }
 }
 
@@ -172,17 +689,23 @@ impl Experimentable for GrayScott {
                 }
             },
             Action::SetParam { name, value } => {
-                if name == "f" { self.f = value; }
-                if name == "k" { self.k = value; }
+                if name == "f" { self.set_f_field(ParamField::Uniform(value)); }
+                if name == "k" { self.set_k_field(ParamField::Uniform(value)); }
             }
             _ => {}
         }
     }
 
+    fn action_space(&self) -> ActionSpace {
+        // One injection target (delta picks the spot); only the rates are tunable
+        ActionSpace::new().with_perturb("injection", 1, -1.0, 1.0).with_params_named(GRAY_SCOTT_SCHEMA, &["f", "k"])
+    }
+
     fn observe(&self) -> Observation {
-        // The agent sees the "Total Mass" of V and the "Entropy" (rough approx)
+        // The agent sees the "Total Mass" of V, the current rates and a coarse V map
         let total_v: f64 = self.v.iter().sum();
-        Observation::StateVec([total_v, self.f, self.k])
+        Observation::named(&[("total_v", total_v), ("f", self.f), ("k", self.k)])
+            .with("v", Observation::field(self.width, self.height, &self.v, FIELD_SIDE))
     }
 
     fn reward(&self) -> f64 {
@@ -196,3 +719,38 @@ impl Experimentable for GrayScott {
         (-((coverage - 0.2).powi(2)) * 100.0).exp() * 10.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The fast kernel must track `step_reference` on every boundary and with
+    /// both per-cell and uniform rates: exactly in f64, to rounding in f32.
+    #[test]
+    fn kernel_matches_reference() {
+        let boundaries = [
+            Boundary::Periodic,
+            Boundary::Neumann,
+            Boundary::Dirichlet { u: 1.0, v: 0.0 },
+            Boundary::Dirichlet { u: 0.5, v: 0.25 },
+        ];
+        for (precision, tolerance) in [(Precision::F64, 0.0), (Precision::F32, 1e-3)] {
+            for boundary in boundaries {
+                let mut fast = GrayScott::parameter_map(67, 45, (0.0, 0.08), (0.03, 0.07))
+                    .with_boundary(boundary)
+                    .with_precision(precision);
+                fast.set_k_field(ParamField::Uniform(0.06));
+                let mut reference = fast.clone();
+                for _ in 0..200 {
+                    fast.step();
+                    reference.step_reference();
+                }
+                let (fast, reference) = (fast.fields(), reference.fields());
+                let worst = fast.0.iter().chain(fast.1).zip(reference.0.iter().chain(reference.1))
+                    .map(|(a, b)| (a - b).abs())
+                    .fold(0.0, f64::max);
+                assert!(worst <= tolerance, "{:?} kernel off the reference by {:e} ({:?})", precision, worst, boundary);
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Named Gray-Scott presets, following Pearson's α–μ classification (Science, 1993)
+//! plus the popular names from Munafo's / Karl Sims' galleries.
+//!
+//! Values are for this crate's kernel (9-point Laplacian, dt = 1). The Pearson
+//! classes use Munafo's diffusion scale (Da = 0.2097, Db = 0.105); the named
+//! gallery presets use Karl Sims' Da = 1.0, Db = 0.5. Low-feed classes like α
+//! die out immediately at the larger rates, so the pair matters.
+
+use crate::gray_scott::InitialCondition;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GrayScottPreset {
+    pub name: &'static str,
+    /// Pearson class letter (e.g. "κ"), or "" for presets outside his scheme
+    pub class: &'static str,
+    pub description: &'static str,
+    pub f: f64,
+    pub k: f64,
+    pub da: f64,
+    pub db: f64,
+    pub seed: InitialCondition,
+}
+
+/// (Da, Db) for Pearson / Munafo parameter values, see module docs
+pub const PEARSON_DIFFUSION: (f64, f64) = (0.2097, 0.105);
+/// (Da, Db) for Karl Sims' gallery values
+pub const SIMS_DIFFUSION: (f64, f64) = (1.0, 0.5);
+
+const PEARSON_DA: f64 = PEARSON_DIFFUSION.0;
+const PEARSON_DB: f64 = PEARSON_DIFFUSION.1;
+const SIMS_DA: f64 = SIMS_DIFFUSION.0;
+const SIMS_DB: f64 = SIMS_DIFFUSION.1;
+
+const CENTER: InitialCondition = InitialCondition::CenterSquare { half: 10 };
+const FEW_SPOTS: InitialCondition = InitialCondition::Spots { count: 6, radius: 4, seed: 1 };
+const MANY_SPOTS: InitialCondition = InitialCondition::Spots { count: 24, radius: 3, seed: 2 };
+
+pub const PRESETS: &[GrayScottPreset] = &[
+    GrayScottPreset { name: "alpha", class: "α", description: "Spiral waves breaking into chaos", f: 0.010, k: 0.047, da: PEARSON_DA, db: PEARSON_DB, seed: MANY_SPOTS },
+    GrayScottPreset { name: "beta", class: "β", description: "Chaotic fronts, spots constantly created and destroyed", f: 0.014, k: 0.039, da: PEARSON_DA, db: PEARSON_DB, seed: MANY_SPOTS },
+    GrayScottPreset { name: "gamma", class: "γ", description: "Chaotic stripes and worm fragments", f: 0.022, k: 0.051, da: PEARSON_DA, db: PEARSON_DB, seed: MANY_SPOTS },
+    GrayScottPreset { name: "delta", class: "δ", description: "Hexagonal spot lattice filling from the seed", f: 0.030, k: 0.055, da: PEARSON_DA, db: PEARSON_DB, seed: FEW_SPOTS },
+    GrayScottPreset { name: "epsilon", class: "ε", description: "Chaos with pulsing spots", f: 0.018, k: 0.055, da: PEARSON_DA, db: PEARSON_DB, seed: MANY_SPOTS },
+    GrayScottPreset { name: "zeta", class: "ζ", description: "Spots and worms pulsating in place", f: 0.022, k: 0.061, da: PEARSON_DA, db: PEARSON_DB, seed: FEW_SPOTS },
+    GrayScottPreset { name: "eta", class: "η", description: "Worms that grow and stop", f: 0.034, k: 0.063, da: PEARSON_DA, db: PEARSON_DB, seed: FEW_SPOTS },
+    GrayScottPreset { name: "theta", class: "θ", description: "Labyrinth of stripes", f: 0.030, k: 0.057, da: PEARSON_DA, db: PEARSON_DB, seed: CENTER },
+    GrayScottPreset { name: "iota", class: "ι", description: "Stripes and holes (negatons)", f: 0.046, k: 0.0594, da: PEARSON_DA, db: PEARSON_DB, seed: CENTER },
+    GrayScottPreset { name: "kappa", class: "κ", description: "Slowly growing labyrinth", f: 0.050, k: 0.063, da: PEARSON_DA, db: PEARSON_DB, seed: CENTER },
+    GrayScottPreset { name: "lambda", class: "λ", description: "Self-replicating spots that fill the plane", f: 0.026, k: 0.061, da: PEARSON_DA, db: PEARSON_DB, seed: FEW_SPOTS },
+    GrayScottPreset { name: "mu", class: "μ", description: "Worms and solitons that stop growing", f: 0.046, k: 0.065, da: PEARSON_DA, db: PEARSON_DB, seed: FEW_SPOTS },
+    GrayScottPreset { name: "coral", class: "", description: "Branching coral growth", f: 0.055, k: 0.062, da: SIMS_DA, db: SIMS_DB, seed: CENTER },
+    GrayScottPreset { name: "mitosis", class: "", description: "Spots that divide like cells", f: 0.0367, k: 0.0649, da: SIMS_DA, db: SIMS_DB, seed: CENTER },
+    GrayScottPreset { name: "solitons", class: "", description: "Stable isolated spots", f: 0.030, k: 0.062, da: SIMS_DA, db: SIMS_DB, seed: FEW_SPOTS },
+    GrayScottPreset { name: "worms", class: "", description: "Long non-branching worms", f: 0.078, k: 0.061, da: SIMS_DA, db: SIMS_DB, seed: FEW_SPOTS },
+    GrayScottPreset { name: "u-skate", class: "", description: "Gliding U-shaped skaters", f: 0.062, k: 0.0609, da: SIMS_DA, db: SIMS_DB, seed: FEW_SPOTS },
+    GrayScottPreset { name: "maze", class: "", description: "Dense maze", f: 0.029, k: 0.057, da: SIMS_DA, db: SIMS_DB, seed: CENTER },
+    GrayScottPreset { name: "holes", class: "", description: "Expanding field with holes", f: 0.039, k: 0.058, da: SIMS_DA, db: SIMS_DB, seed: CENTER },
+];
+
+/// The preset `GrayScott::init` starts from.
+pub const DEFAULT_PRESET: &str = "coral";
+
+/// Look up by name ("mitosis") or Pearson class ("κ" / "kappa"), case-insensitive.
+pub fn find(name: &str) -> Option<&'static GrayScottPreset> {
+    let name = name.trim();
+    PRESETS
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name) || (!p.class.is_empty() && p.class == name))
+}
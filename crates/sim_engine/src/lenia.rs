@@ -0,0 +1,488 @@
+//! Lenia (Bert Chan, 2019): continuous-state Life with smooth ring kernels and a
+//! growth mapping, including multi-channel rules and a small creature library.
+//!
+//! A world is a set of channels in 0..1. Each kernel convolves one channel
+//! (its source), passes the result through its growth function and adds the
+//! weighted growth to another channel (its target):
+//!   A_c ← clip(A_c + dt · Σ_k h_k·G_k(K_k ∗ A_src) / Σ_k h_k, 0, 1)
+
+use super::{param_value, Action, ActionSpace, Experimentable, Observation, ParamValue, SimState, Simulation};
+use crate::registry::LENIA_SCHEMA;
+use crate::observation::FIELD_SIDE;
+use crate::convolution::{Convolution, Fft2d};
+use crate::rng::SimRng;
+
+/// Shape of each kernel ring, as a function of the ring-relative radius 0..1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KernelCore {
+    /// exp(4 - 1/(r(1-r))), Chan's default
+    Exponential,
+    /// (4r(1-r))^4
+    Polynomial,
+    /// 1 on the middle half of the ring
+    Rectangular,
+}
+
+impl KernelCore {
+    fn at(self, r: f64) -> f64 {
+        if r <= 0.0 || r >= 1.0 {
+            return 0.0;
+        }
+        match self {
+            KernelCore::Exponential => (4.0 - 1.0 / (r * (1.0 - r))).exp(),
+            KernelCore::Polynomial => (4.0 * r * (1.0 - r)).powi(4),
+            KernelCore::Rectangular => if (0.25..=0.75).contains(&r) { 1.0 } else { 0.0 },
+        }
+    }
+}
+
+/// Maps a neighbourhood potential to a growth rate in -1..1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Growth {
+    /// 2·exp(-(u-μ)²/2σ²) - 1
+    Gaussian,
+    /// 2·max(0, 1 - (u-μ)²/9σ²)^4 - 1
+    Polynomial,
+    /// +1 within σ of μ, -1 elsewhere
+    Step,
+}
+
+impl Growth {
+    pub fn at(self, u: f64, mu: f64, sigma: f64) -> f64 {
+        let d = u - mu;
+        match self {
+            Growth::Gaussian => 2.0 * (-(d * d) / (2.0 * sigma * sigma)).exp() - 1.0,
+            Growth::Polynomial => 2.0 * (1.0 - d * d / (9.0 * sigma * sigma)).max(0.0).powi(4) - 1.0,
+            Growth::Step => if d.abs() <= sigma { 1.0 } else { -1.0 },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KernelSpec {
+    /// Kernel radius R in cells
+    pub radius: f64,
+    /// Relative height of each concentric ring (Chan's β)
+    pub peaks: &'static [f64],
+    pub core: KernelCore,
+    pub growth: Growth,
+    pub mu: f64,
+    pub sigma: f64,
+    /// Weight h of this kernel in its target channel
+    pub weight: f64,
+    pub source: usize,
+    pub target: usize,
+}
+
+impl KernelSpec {
+    /// Single-channel kernel with Chan's defaults (exponential core, Gaussian growth).
+    pub const fn simple(radius: f64, peaks: &'static [f64], mu: f64, sigma: f64) -> Self {
+        Self {
+            radius,
+            peaks,
+            core: KernelCore::Exponential,
+            growth: Growth::Gaussian,
+            mu,
+            sigma,
+            weight: 1.0,
+            source: 0,
+            target: 0,
+        }
+    }
+
+    /// Kernel weight at offset (dx, dy); normalisation happens in `Convolution`.
+    fn weight_at(&self, dx: f64, dy: f64) -> f64 {
+        let r = (dx * dx + dy * dy).sqrt() / self.radius;
+        if r >= 1.0 || self.peaks.is_empty() {
+            return 0.0;
+        }
+        let br = r * self.peaks.len() as f64;
+        let ring = (br as usize).min(self.peaks.len() - 1);
+        self.peaks[ring] * self.core.at(br - ring as f64)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CreatureSeed {
+    /// Recorded pattern for channel 0, rows of cell values, placed at the centre
+    Cells(&'static [&'static [f64]]),
+    /// Random square patch of side `size` in every channel; the creature
+    /// self-organises out of it (most of the time)
+    Soup { size: usize, density: f64 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LeniaCreature {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub channels: usize,
+    pub dt: f64,
+    pub kernels: &'static [KernelSpec],
+    pub seed: CreatureSeed,
+}
+
+// Orbium unicaudatus, from Chan's Lenia notebook (R = 13, T = 10)
+const ORBIUM_CELLS: &[&[f64]] = &[
+    &[0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.1, 0.14, 0.1, 0.0, 0.0, 0.03, 0.03, 0.0, 0.0, 0.3, 0.0, 0.0, 0.0, 0.0],
+    &[0.0, 0.0, 0.0, 0.0, 0.0, 0.08, 0.24, 0.3, 0.3, 0.18, 0.14, 0.15, 0.16, 0.15, 0.09, 0.2, 0.0, 0.0, 0.0, 0.0],
+    &[0.0, 0.0, 0.0, 0.0, 0.0, 0.15, 0.34, 0.44, 0.46, 0.38, 0.18, 0.14, 0.11, 0.13, 0.19, 0.18, 0.45, 0.0, 0.0, 0.0],
+    &[0.0, 0.0, 0.0, 0.0, 0.06, 0.13, 0.39, 0.5, 0.5, 0.37, 0.06, 0.0, 0.0, 0.0, 0.02, 0.16, 0.68, 0.0, 0.0, 0.0],
+    &[0.0, 0.0, 0.0, 0.11, 0.17, 0.17, 0.33, 0.4, 0.38, 0.28, 0.14, 0.0, 0.0, 0.0, 0.0, 0.0, 0.18, 0.42, 0.0, 0.0],
+    &[0.0, 0.0, 0.09, 0.18, 0.13, 0.06, 0.08, 0.26, 0.32, 0.32, 0.27, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.82, 0.0, 0.0],
+    &[0.27, 0.0, 0.16, 0.12, 0.0, 0.0, 0.0, 0.25, 0.38, 0.44, 0.45, 0.34, 0.0, 0.0, 0.0, 0.0, 0.0, 0.22, 0.17, 0.0],
+    &[0.0, 0.07, 0.2, 0.02, 0.0, 0.0, 0.0, 0.31, 0.48, 0.57, 0.6, 0.57, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.49, 0.0],
+    &[0.0, 0.59, 0.19, 0.0, 0.0, 0.0, 0.0, 0.2, 0.57, 0.69, 0.76, 0.76, 0.49, 0.0, 0.0, 0.0, 0.0, 0.0, 0.36, 0.0],
+    &[0.0, 0.58, 0.19, 0.0, 0.0, 0.0, 0.0, 0.0, 0.67, 0.83, 0.9, 0.92, 0.87, 0.12, 0.0, 0.0, 0.0, 0.0, 0.22, 0.07],
+    &[0.0, 0.0, 0.46, 0.0, 0.0, 0.0, 0.0, 0.0, 0.7, 0.93, 1.0, 1.0, 1.0, 0.61, 0.0, 0.0, 0.0, 0.0, 0.18, 0.11],
+    &[0.0, 0.0, 0.82, 0.0, 0.0, 0.0, 0.0, 0.0, 0.47, 1.0, 1.0, 0.98, 1.0, 0.96, 0.27, 0.0, 0.0, 0.0, 0.19, 0.1],
+    &[0.0, 0.0, 0.46, 0.0, 0.0, 0.0, 0.0, 0.0, 0.25, 1.0, 1.0, 0.84, 0.92, 0.97, 0.54, 0.14, 0.04, 0.1, 0.21, 0.05],
+    &[0.0, 0.0, 0.0, 0.4, 0.0, 0.0, 0.0, 0.0, 0.09, 0.8, 1.0, 0.82, 0.8, 0.85, 0.63, 0.31, 0.18, 0.19, 0.2, 0.01],
+    &[0.0, 0.0, 0.0, 0.36, 0.1, 0.0, 0.0, 0.0, 0.05, 0.54, 0.86, 0.79, 0.74, 0.72, 0.6, 0.39, 0.28, 0.24, 0.13, 0.0],
+    &[0.0, 0.0, 0.0, 0.01, 0.3, 0.07, 0.0, 0.0, 0.08, 0.36, 0.64, 0.7, 0.64, 0.6, 0.51, 0.39, 0.29, 0.19, 0.04, 0.0],
+    &[0.0, 0.0, 0.0, 0.0, 0.1, 0.24, 0.14, 0.1, 0.15, 0.29, 0.45, 0.53, 0.52, 0.46, 0.4, 0.31, 0.21, 0.08, 0.0, 0.0],
+    &[0.0, 0.0, 0.0, 0.0, 0.0, 0.08, 0.21, 0.21, 0.22, 0.29, 0.36, 0.39, 0.37, 0.33, 0.26, 0.18, 0.09, 0.0, 0.0, 0.0],
+    &[0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.03, 0.13, 0.19, 0.22, 0.24, 0.24, 0.23, 0.18, 0.13, 0.05, 0.0, 0.0, 0.0, 0.0],
+    &[0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.02, 0.06, 0.08, 0.09, 0.07, 0.05, 0.01, 0.0, 0.0, 0.0, 0.0, 0.0],
+];
+
+const ORBIUM_KERNEL: KernelSpec = KernelSpec::simple(13.0, &[1.0], 0.15, 0.015);
+
+pub const CREATURES: &[LeniaCreature] = &[
+    LeniaCreature {
+        name: "orbium",
+        description: "Orbium unicaudatus: the classic glider",
+        channels: 1,
+        dt: 0.1,
+        kernels: &[ORBIUM_KERNEL],
+        seed: CreatureSeed::Cells(ORBIUM_CELLS),
+    },
+    LeniaCreature {
+        name: "orbium quad4",
+        description: "Orbium under the polynomial kernel and growth of Chan's original Lenia",
+        channels: 1,
+        dt: 0.1,
+        kernels: &[KernelSpec { core: KernelCore::Polynomial, growth: Growth::Polynomial, ..ORBIUM_KERNEL }],
+        seed: CreatureSeed::Cells(ORBIUM_CELLS),
+    },
+    LeniaCreature {
+        name: "orbium sea",
+        description: "Orbium rule from a large random patch: spreads into a turbulent sea",
+        channels: 1,
+        dt: 0.1,
+        kernels: &[ORBIUM_KERNEL],
+        seed: CreatureSeed::Soup { size: 64, density: 1.0 },
+    },
+    LeniaCreature {
+        name: "haloed orbium",
+        description: "Two channels: an orbium grows a halo in channel 1 that feeds weakly back",
+        channels: 2,
+        dt: 0.1,
+        kernels: &[
+            ORBIUM_KERNEL,
+            KernelSpec { source: 0, target: 1, ..KernelSpec::simple(13.0, &[1.0], 0.12, 0.05) },
+            KernelSpec { source: 1, target: 0, weight: 0.1, ..KernelSpec::simple(13.0, &[1.0], 0.3, 0.2) },
+        ],
+        seed: CreatureSeed::Cells(ORBIUM_CELLS),
+    },
+];
+
+pub const DEFAULT_CREATURE: &str = "orbium";
+
+pub fn find_creature(name: &str) -> Option<&'static LeniaCreature> {
+    CREATURES.iter().find(|c| c.name.eq_ignore_ascii_case(name.trim()))
+}
+
+#[derive(Clone)]
+pub struct Lenia {
+    width: usize,
+    height: usize,
+    channels: Vec<Vec<f64>>,
+    dt: f64,
+    kernels: Vec<KernelSpec>,
+    convolutions: Vec<Convolution>,
+    fft: Fft2d,
+    seed_pattern: CreatureSeed,
+    seed: u64,
+    generation: u64,
+    /// Centroid before the last step, for the speed observation
+    last_centroid: Option<(f64, f64)>,
+    speed: f64,
+}
+
+impl Lenia {
+    pub fn init(width: usize, height: usize) -> Self {
+        let creature = find_creature(DEFAULT_CREATURE).expect("default creature exists");
+        let mut lenia = Self {
+            width,
+            height,
+            channels: Vec::new(),
+            dt: 0.1,
+            kernels: Vec::new(),
+            convolutions: Vec::new(),
+            fft: Fft2d::new(width, height),
+            seed_pattern: creature.seed,
+            seed: 0,
+            generation: 0,
+            last_centroid: None,
+            speed: 0.0,
+        };
+        lenia.load_creature(creature);
+        lenia
+    }
+
+    /// Start from a library creature (unknown names keep the default).
+    pub fn with_creature(mut self, name: &str) -> Self {
+        if let Some(creature) = find_creature(name) {
+            self.load_creature(creature);
+        }
+        self
+    }
+
+    pub fn load_creature(&mut self, creature: &LeniaCreature) {
+        self.seed_pattern = creature.seed;
+        self.set_rule(creature.channels, creature.dt, creature.kernels.to_vec());
+        self.reset();
+    }
+
+    /// Replace the update rule. Channel contents are kept where the channel count allows.
+    pub fn set_rule(&mut self, channels: usize, dt: f64, kernels: Vec<KernelSpec>) {
+        let channels = channels.max(1);
+        self.channels.resize(channels, vec![0.0; self.width * self.height]);
+        self.dt = dt;
+        self.kernels = kernels
+            .into_iter()
+            .filter(|k| k.source < channels && k.target < channels)
+            .collect();
+        self.rebuild_kernels();
+    }
+
+    fn rebuild_kernels(&mut self) {
+        self.convolutions = self
+            .kernels
+            .iter()
+            .map(|k| Convolution::new(k.radius.ceil() as usize, &self.fft, |dx, dy| k.weight_at(dx, dy)))
+            .collect();
+    }
+
+    /// Switch the initial pattern (keeping the rule) and restart from it.
+    pub fn reseed(&mut self, pattern: CreatureSeed) {
+        self.seed_pattern = pattern;
+        self.reset();
+    }
+
+    /// Clear the world and re-seed it from the current creature's pattern.
+    pub fn reset(&mut self) {
+        for channel in &mut self.channels {
+            channel.fill(0.0);
+        }
+        match self.seed_pattern {
+            CreatureSeed::Cells(rows) => {
+                let x0 = (self.width / 2).saturating_sub(rows.first().map_or(0, |r| r.len()) / 2);
+                let y0 = (self.height / 2).saturating_sub(rows.len() / 2);
+                self.place(0, x0, y0, rows);
+            }
+            CreatureSeed::Soup { size, density } => {
+                let mut rng = SimRng::new(self.seed);
+                let size = size.min(self.width).min(self.height);
+                let (x0, y0) = ((self.width - size) / 2, (self.height - size) / 2);
+                for channel in &mut self.channels {
+                    for y in y0..y0 + size {
+                        for x in x0..x0 + size {
+                            if rng.chance(density) {
+                                channel[y * self.width + x] = rng.next_f64();
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        self.generation = 0;
+        self.last_centroid = None;
+        self.speed = 0.0;
+    }
+
+    /// Stamp a pattern into a channel with its top-left corner at (x, y), wrapping.
+    pub fn place(&mut self, channel: usize, x: usize, y: usize, rows: &[&[f64]]) {
+        let Some(field) = self.channels.get_mut(channel) else { return };
+        for (dy, row) in rows.iter().enumerate() {
+            for (dx, &value) in row.iter().enumerate() {
+                let cx = (x + dx) % self.width;
+                let cy = (y + dy) % self.height;
+                field[cy * self.width + cx] = value.clamp(0.0, 1.0);
+            }
+        }
+    }
+
+    pub fn channel(&self, index: usize) -> Option<&[f64]> {
+        self.channels.get(index).map(|c| c.as_slice())
+    }
+
+    pub fn kernels(&self) -> &[KernelSpec] {
+        &self.kernels
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Total mass over all channels, as a fraction of the grid
+    pub fn mass(&self) -> f64 {
+        let n = (self.width * self.height * self.channels.len()).max(1) as f64;
+        self.channels.iter().flatten().sum::<f64>() / n
+    }
+
+    /// Mass centroid of channel 0 on the torus (circular means), or None when empty
+    fn centroid(&self) -> Option<(f64, f64)> {
+        use std::f64::consts::TAU;
+        let (mut xs, mut xc, mut ys, mut yc, mut total) = (0.0, 0.0, 0.0, 0.0, 0.0);
+        for (i, &a) in self.channels[0].iter().enumerate() {
+            if a == 0.0 { continue; }
+            let tx = TAU * (i % self.width) as f64 / self.width as f64;
+            let ty = TAU * (i / self.width) as f64 / self.height as f64;
+            xs += a * tx.sin();
+            xc += a * tx.cos();
+            ys += a * ty.sin();
+            yc += a * ty.cos();
+            total += a;
+        }
+        if total < 1e-9 {
+            return None;
+        }
+        let x = xs.atan2(xc).rem_euclid(TAU) / TAU * self.width as f64;
+        let y = ys.atan2(yc).rem_euclid(TAU) / TAU * self.height as f64;
+        Some((x, y))
+    }
+}
+
+impl Simulation for Lenia {
+    fn new() -> Self {
+        Self::init(128, 128)
+    }
+
+    fn step(&mut self) {
+        let n = self.width * self.height;
+
+        // One forward FFT per channel that any FFT kernel reads
+        let spectra: Vec<_> = (0..self.channels.len())
+            .map(|c| {
+                let needed = self.kernels.iter().zip(&self.convolutions).any(|(k, conv)| k.source == c && conv.uses_fft());
+                needed.then(|| self.fft.forward(&self.channels[c]))
+            })
+            .collect();
+
+        let mut growth = vec![vec![0.0; n]; self.channels.len()];
+        let mut weights = vec![0.0; self.channels.len()];
+        for (kernel, conv) in self.kernels.iter().zip(&self.convolutions) {
+            let potential = conv.apply(&self.channels[kernel.source], spectra[kernel.source].as_deref(), &self.fft);
+            for (g, u) in growth[kernel.target].iter_mut().zip(potential) {
+                *g += kernel.weight * kernel.growth.at(u, kernel.mu, kernel.sigma);
+            }
+            weights[kernel.target] += kernel.weight;
+        }
+
+        for ((channel, growth), total) in self.channels.iter_mut().zip(growth).zip(weights) {
+            if total == 0.0 { continue; }
+            let rate = self.dt / total;
+            for (a, g) in channel.iter_mut().zip(growth) {
+                *a = (*a + rate * g).clamp(0.0, 1.0);
+            }
+        }
+
+        self.generation += 1;
+        let centroid = self.centroid();
+        self.speed = match (self.last_centroid, centroid) {
+            (Some((x0, y0)), Some((x1, y1))) => {
+                // Shortest way round the torus
+                let wrap = |d: f64, size: f64| d - size * (d / size).round();
+                wrap(x1 - x0, self.width as f64).hypot(wrap(y1 - y0, self.height as f64))
+            }
+            _ => 0.0,
+        };
+        self.last_centroid = centroid;
+    }
+
+    fn get_state(&self) -> SimState {
+        // Several channels share one intensity map: show the strongest
+        let values = if self.channels.len() == 1 {
+            self.channels[0].clone()
+        } else {
+            (0..self.width * self.height)
+                .map(|i| self.channels.iter().map(|c| c[i]).fold(0.0, f64::max))
+                .collect()
+        };
+        SimState::FloatGrid { width: self.width as u32, height: self.height as u32, values }
+    }
+
+    fn set_param(&mut self, key: &str, value: ParamValue) {
+        match (key, value) {
+            ("creature", ParamValue::String(name)) => {
+                if let Some(creature) = find_creature(&name) {
+                    self.load_creature(creature);
+                }
+            }
+            ("seed", ParamValue::Int(seed)) => {
+                self.seed = seed as u64;
+                self.reset();
+            }
+            ("dt", ParamValue::Float(dt)) => self.dt = dt.clamp(0.001, 1.0),
+            // Growth centre / width for every kernel (single-kernel creatures are the usual case)
+            ("mu", ParamValue::Float(mu)) => self.kernels.iter_mut().for_each(|k| k.mu = mu),
+            ("sigma", ParamValue::Float(sigma)) => self.kernels.iter_mut().for_each(|k| k.sigma = sigma.max(1e-4)),
+            ("radius", ParamValue::Float(radius)) => {
+                // Rescale all kernels so the first one has this radius
+                if let Some(first) = self.kernels.first().map(|k| k.radius) {
+                    let scale = radius.max(1.0) / first;
+                    self.kernels.iter_mut().for_each(|k| k.radius *= scale);
+                    self.rebuild_kernels();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn as_experimentable(&mut self) -> Option<&mut dyn Experimentable> {
+        Some(self)
+    }
+}
+
+impl Experimentable for Lenia {
+    fn apply_action(&mut self, action: Action) {
+        match action {
+            // Drop a small random blob into a channel; delta picks the spot like Gray-Scott
+            Action::Perturb { which, delta } => {
+                let channel = which as usize % self.channels.len();
+                let cx = (self.width as f64 * (delta.abs() % 1.0)) as usize;
+                let cy = (self.height as f64 * ((delta * 10.0).abs() % 1.0)) as usize;
+                let mut rng = SimRng::new(delta.to_bits());
+                let patch: Vec<Vec<f64>> = (0..12).map(|_| (0..12).map(|_| rng.next_f64()).collect()).collect();
+                let rows: Vec<&[f64]> = patch.iter().map(|r| r.as_slice()).collect();
+                self.place(channel, cx, cy, &rows);
+            }
+            Action::SetParam { name, value } => self.set_param(&name, param_value(LENIA_SCHEMA, &name, value)),
+            _ => {}
+        }
+    }
+
+    fn action_space(&self) -> ActionSpace {
+        // Drop a blob into a channel; delta picks the spot
+        ActionSpace::new().with_perturb("channel", self.channels.len(), -1.0, 1.0).with_params(LENIA_SCHEMA)
+    }
+
+    fn observe(&self) -> Observation {
+        // Mass, centroid speed (cells/step), the generation count and the world itself
+        let mut obs = Observation::named(&[("mass", self.mass()), ("speed", self.speed), ("generation", self.generation as f64)]);
+        if let SimState::FloatGrid { values, .. } = self.get_state() {
+            obs = obs.with("field", Observation::field(self.width, self.height, &values, FIELD_SIDE));
+        }
+        obs
+    }
+
+    fn reward(&self) -> f64 {
+        // A creature is alive, bounded and moving; empty or saturated worlds are failures
+        let mass = self.mass();
+        if mass <= 1e-4 || mass > 0.5 {
+            return -1.0;
+        }
+        1.0 + self.speed * 10.0
+    }
+}
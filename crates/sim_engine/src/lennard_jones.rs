@@ -0,0 +1,250 @@
+//! 2D Lennard-Jones fluid in reduced units (σ = m = k_B = 1), velocity Verlet
+//! with an optional Berendsen thermostat. Each species pair has its own well
+//! depth ε; weaker cross terms make a mixture demix as it cools.
+
+use super::{param_value, Action, ActionSpace, Experimentable, Observation, ParamValue, SimState, Simulation};
+use crate::registry::LENNARD_JONES_SCHEMA;
+use crate::observation::FIELD_SIDE;
+use crate::particles::{parse_pair_key, ParticleSet, SpatialHash};
+use crate::rng::SimRng;
+
+/// Interactions are cut off at 2.5σ (the usual truncation)
+const CUTOFF: f64 = 2.5;
+/// Overlapping pairs are treated as if this far apart, so a bad kick can't explode
+const MIN_DISTANCE: f64 = 0.7;
+
+#[derive(Clone)]
+pub struct LennardJones {
+    particles: ParticleSet,
+    hash: SpatialHash,
+    forces: Vec<(f64, f64)>,
+    /// epsilon[i][j]: well depth between species i and j (kept symmetric)
+    epsilon: Vec<Vec<f64>>,
+    count: usize,
+    density: f64,
+    /// Thermostat target temperature
+    pub temperature: f64,
+    /// Berendsen coupling time; 0 runs at constant energy
+    pub thermostat_tau: f64,
+    pub dt: f64,
+    /// Verlet steps per `Simulation::step`
+    pub substeps: usize,
+    potential: f64,
+    seed: u64,
+}
+
+impl LennardJones {
+    pub fn init(count: usize, species: usize, density: f64, temperature: f64, seed: u64) -> Self {
+        let species = species.clamp(1, 16);
+        let mut sim = Self {
+            particles: ParticleSet::new(1.0, 1.0),
+            hash: SpatialHash::new(1.0, 1.0, CUTOFF),
+            forces: Vec::new(),
+            // Like attracts like twice as strongly as unlike
+            epsilon: (0..species).map(|i| (0..species).map(|j| if i == j { 1.0 } else { 0.5 }).collect()).collect(),
+            count,
+            density: density.clamp(0.01, 0.9),
+            temperature,
+            thermostat_tau: 0.5,
+            dt: 0.005,
+            substeps: 10,
+            potential: 0.0,
+            seed,
+        };
+        sim.reset();
+        sim
+    }
+
+    /// Square lattice filling the box, Maxwell-Boltzmann velocities at the target temperature.
+    pub fn reset(&mut self) {
+        let size = (self.count as f64 / self.density).sqrt();
+        let per_row = (self.count as f64).sqrt().ceil() as usize;
+        let spacing = size / per_row as f64;
+        let species = self.epsilon.len();
+        let mut rng = SimRng::new(self.seed);
+
+        let mut particles = ParticleSet::new(size, size);
+        for i in 0..self.count {
+            let pos = (((i % per_row) as f64 + 0.5) * spacing, ((i / per_row) as f64 + 0.5) * spacing);
+            let sd = self.temperature.max(0.0).sqrt();
+            particles.push(pos, (sd * rng.gaussian(), sd * rng.gaussian()), rng.below(species) as u8);
+        }
+        // No net drift
+        let n = self.count.max(1) as f64;
+        let (mx, my) = particles.velocities.iter().fold((0.0, 0.0), |(x, y), v| (x + v.0 / n, y + v.1 / n));
+        for v in &mut particles.velocities {
+            v.0 -= mx;
+            v.1 -= my;
+        }
+
+        self.particles = particles;
+        self.hash = SpatialHash::new(size, size, CUTOFF);
+        self.compute_forces();
+    }
+
+    pub fn particles(&self) -> &ParticleSet {
+        &self.particles
+    }
+
+    pub fn epsilon(&self) -> &[Vec<f64>] {
+        &self.epsilon
+    }
+
+    /// Sets both (i, j) and (j, i): forces have to stay equal and opposite.
+    pub fn set_epsilon(&mut self, i: usize, j: usize, value: f64) {
+        let s = self.epsilon.len();
+        if i < s && j < s {
+            let value = value.clamp(0.0, 5.0);
+            self.epsilon[i][j] = value;
+            self.epsilon[j][i] = value;
+        }
+    }
+
+    /// Kinetic temperature: two degrees of freedom per particle
+    pub fn kinetic_temperature(&self) -> f64 {
+        self.particles.kinetic_energy()
+    }
+
+    /// Potential energy per particle
+    pub fn potential_energy(&self) -> f64 {
+        self.potential / self.particles.len().max(1) as f64
+    }
+
+    fn compute_forces(&mut self) {
+        self.hash.rebuild(&self.particles.positions);
+        let n = self.particles.len();
+        let mut forces = vec![(0.0, 0.0); n];
+        let mut potential = 0.0;
+
+        for i in 0..n {
+            let si = self.particles.species[i] as usize;
+            for j in self.hash.neighbours(self.particles.positions[i]) {
+                // Each pair once
+                if j <= i { continue; }
+                let (dx, dy) = self.particles.delta(i, j);
+                let r2 = dx * dx + dy * dy;
+                if r2 >= CUTOFF * CUTOFF { continue; }
+                let eps = self.epsilon[si][self.particles.species[j] as usize];
+                let inv_r2 = 1.0 / r2.max(MIN_DISTANCE * MIN_DISTANCE);
+                let inv_r6 = inv_r2 * inv_r2 * inv_r2;
+                potential += 4.0 * eps * (inv_r6 * inv_r6 - inv_r6);
+                // F·r / r²: positive pushes j away from i
+                let f = 24.0 * eps * (2.0 * inv_r6 * inv_r6 - inv_r6) * inv_r2;
+                forces[i].0 -= f * dx;
+                forces[i].1 -= f * dy;
+                forces[j].0 += f * dx;
+                forces[j].1 += f * dy;
+            }
+        }
+
+        self.forces = forces;
+        self.potential = potential;
+    }
+
+    fn verlet_step(&mut self) {
+        let half = 0.5 * self.dt;
+        for (v, f) in self.particles.velocities.iter_mut().zip(&self.forces) {
+            v.0 += half * f.0;
+            v.1 += half * f.1;
+        }
+        self.particles.drift(self.dt);
+        self.compute_forces();
+        for (v, f) in self.particles.velocities.iter_mut().zip(&self.forces) {
+            v.0 += half * f.0;
+            v.1 += half * f.1;
+        }
+
+        if self.thermostat_tau > 0.0 {
+            let t = self.kinetic_temperature().max(1e-9);
+            let lambda = (1.0 + self.dt / self.thermostat_tau * (self.temperature / t - 1.0)).max(0.0).sqrt();
+            for v in &mut self.particles.velocities {
+                v.0 *= lambda;
+                v.1 *= lambda;
+            }
+        }
+    }
+}
+
+impl Simulation for LennardJones {
+    fn new() -> Self {
+        Self::init(400, 2, 0.35, 0.45, 0)
+    }
+
+    fn step(&mut self) {
+        for _ in 0..self.substeps {
+            self.verlet_step();
+        }
+    }
+
+    fn get_state(&self) -> SimState {
+        self.particles.state()
+    }
+
+    fn set_param(&mut self, key: &str, value: ParamValue) {
+        match (key, value) {
+            ("seed", ParamValue::Int(seed)) => {
+                self.seed = seed as u64;
+                self.reset();
+            }
+            ("species", ParamValue::Int(n)) => {
+                let n = n.clamp(1, 16) as usize;
+                self.epsilon = (0..n).map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.5 }).collect()).collect();
+                self.reset();
+            }
+            ("count", ParamValue::Int(n)) => {
+                self.count = n.clamp(1, 20_000) as usize;
+                self.reset();
+            }
+            ("density", ParamValue::Float(v)) => {
+                self.density = v.clamp(0.01, 0.9);
+                self.reset();
+            }
+            ("temperature", ParamValue::Float(v)) => self.temperature = v.max(0.0),
+            ("thermostat_tau", ParamValue::Float(v)) => self.thermostat_tau = v.max(0.0),
+            ("dt", ParamValue::Float(v)) => self.dt = v.clamp(1e-4, 0.01),
+            (key, ParamValue::Float(v)) => {
+                if let Some((i, j)) = parse_pair_key(key, "epsilon") {
+                    self.set_epsilon(i, j, v);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn as_experimentable(&mut self) -> Option<&mut dyn Experimentable> {
+        Some(self)
+    }
+}
+
+impl Experimentable for LennardJones {
+    fn apply_action(&mut self, action: Action) {
+        match action {
+            // Nudge one ε entry (and its mirror); `which` indexes the flattened matrix
+            Action::Perturb { which, delta } => {
+                let s = self.epsilon.len();
+                let k = which as usize % (s * s);
+                let current = self.epsilon[k / s][k % s];
+                self.set_epsilon(k / s, k % s, current + delta);
+            }
+            Action::SetParam { name, value } => self.set_param(&name, param_value(LENNARD_JONES_SCHEMA, &name, value)),
+            _ => {}
+        }
+    }
+
+    fn action_space(&self) -> ActionSpace {
+        let s = self.epsilon.len();
+        ActionSpace::new().with_perturb("epsilon", (s * s).min(256), -1.0, 1.0).with_params(LENNARD_JONES_SCHEMA)
+    }
+
+    fn observe(&self) -> Observation {
+        let t = self.kinetic_temperature();
+        let u = self.potential_energy();
+        Observation::named(&[("temperature", t), ("potential", u), ("energy", t + u)])
+            .with("density", self.particles.density_map(FIELD_SIDE))
+    }
+
+    fn reward(&self) -> f64 {
+        // Binding: lower potential energy means more condensed structure
+        -self.potential_energy()
+    }
+}
@@ -2,11 +2,37 @@
 
 use serde::{Deserialize, Serialize};
 pub use ready::{CellPattern, MacroCell}; 
+pub use action::{Action, ActionError, ActionSpace};
+pub use observation::{Observation, ObservationSpace};
 
 // --- Module Registration ---
+pub mod action;
+pub mod archive;
+pub mod boids;
+pub mod composite;
+pub mod convolution;
+pub mod elementary;
+pub mod env;
+pub mod evolution;
 pub mod gol;
 pub mod ode;
 pub mod gray_scott; // <--- DON'T FORGET THIS LINE (Registers the new file)
+pub mod gray_scott_presets;
+pub mod lenia;
+pub mod lennard_jones;
+pub mod nbody;
+pub mod observation;
+pub mod particle_life;
+pub mod particles;
+pub mod rd_models;
+pub mod reaction_diffusion;
+pub mod registry;
+pub mod reward;
+pub mod rng;
+pub mod smoothlife;
+pub mod soup;
+pub mod stencil;
+pub mod thumbnail;
 
 // --- Shared Trait ---
 pub trait Simulation {
@@ -41,6 +67,106 @@ pub enum SimState {
         width: u32,
         height: u32,
         values: Vec<f64>,
+    },
+
+    /// Many-body systems in a periodic box [0, width) × [0, height).
+    /// The three vectors are parallel, one entry per particle.
+    Particles {
+        width: f64,
+        height: f64,
+        positions: Vec<(f64, f64)>,
+        velocities: Vec<(f64, f64)>,
+        species: Vec<u8>,
+    },
+}
+
+/// Describes one parameter a simulation accepts through `set_param`.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub default: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Set through `ParamValue::Int` rather than `ParamValue::Float`
+    pub integer: bool,
+    pub kind: ParamKind,
+    /// Default of a choice / text parameter ("" = none, e.g. decided by a preset)
+    pub default_text: &'static str,
+}
+
+/// What a parameter is set with.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum ParamKind {
+    /// A number in `min..=max`
+    Number,
+    /// One of these names, as a `ParamValue::String`
+    Choice(&'static [&'static str]),
+    /// A `ParamValue::String` spec the simulation parses (preset names, field specs...)
+    Text,
+}
+
+impl ParamSpec {
+    pub const fn float(name: &'static str, description: &'static str, default: f64, min: f64, max: f64) -> Self {
+        Self { name, description, default, min, max, integer: false, kind: ParamKind::Number, default_text: "" }
+    }
+
+    pub const fn int(name: &'static str, description: &'static str, default: i64, min: i64, max: i64) -> Self {
+        Self {
+            name,
+            description,
+            default: default as f64,
+            min: min as f64,
+            max: max as f64,
+            integer: true,
+            kind: ParamKind::Number,
+            default_text: "",
+        }
+    }
+
+    pub const fn choice(
+        name: &'static str,
+        description: &'static str,
+        choices: &'static [&'static str],
+        default: &'static str,
+    ) -> Self {
+        Self::string(name, description, ParamKind::Choice(choices), default)
+    }
+
+    pub const fn text(name: &'static str, description: &'static str, default: &'static str) -> Self {
+        Self::string(name, description, ParamKind::Text, default)
+    }
+
+    const fn string(name: &'static str, description: &'static str, kind: ParamKind, default: &'static str) -> Self {
+        Self { name, description, default: 0.0, min: 0.0, max: 0.0, integer: false, kind, default_text: default }
+    }
+
+    /// Numeric parameters are the ones agents, sliders and searches can move.
+    pub fn is_numeric(&self) -> bool {
+        self.kind == ParamKind::Number
+    }
+
+    /// Clamp `value` into range and wrap it the way `set_param` expects.
+    pub fn value(&self, value: f64) -> ParamValue {
+        let value = value.clamp(self.min, self.max);
+        if self.integer { ParamValue::Int(value.round() as i64) } else { ParamValue::Float(value) }
+    }
+
+    /// The default as `set_param` takes it (None for a string parameter without one).
+    pub fn default_value(&self) -> Option<ParamValue> {
+        match self.kind {
+            ParamKind::Number => Some(self.value(self.default)),
+            _ if self.default_text.is_empty() => None,
+            _ => Some(ParamValue::String(self.default_text.into())),
+        }
+    }
+}
+
+/// `value` typed the way `schema` says `name` takes it (Float if it isn't a listed number).
+pub fn param_value(schema: &[ParamSpec], name: &str, value: f64) -> ParamValue {
+    match schema.iter().find(|spec| spec.name == name && spec.is_numeric()) {
+        Some(spec) => spec.value(value),
+        None => ParamValue::Float(value),
     }
 }
 
@@ -51,28 +177,30 @@ pub enum ParamValue {
     Float(f64),
     String(String),
     Pattern(CellPattern),
+    /// RGBA8 pixels, row-major (e.g. canvas `ImageData`): masks and parameter maps
+    Image { width: usize, height: usize, rgba: Vec<u8> },
 }
 
 // --- EXPERIMENTAL INTERFACE (RL / Agent Hooks) ---
 
-#[derive(Clone, Debug)]
-pub enum Action {
-    FlipCell { r: usize, c: usize },
-    Perturb { which: u8, delta: f64 },
-    SetParam { name: String, value: f64 },
-    Noop,
-}
-
-#[derive(Clone, Debug)]
-pub enum Observation {
-    GridSummary { alive: usize, width: usize, height: usize },
-    StateVec([f64; 3]),
-    Text(String),
-    None,
-}
-
 pub trait Experimentable {
     fn apply_action(&mut self, action: Action);
     fn observe(&self) -> Observation;
     fn reward(&self) -> f64;
+
+    /// Which actions `apply_action` acts on right now.
+    fn action_space(&self) -> ActionSpace;
+
+    /// Shape of `observe`'s output; by default whatever it returns right now.
+    fn observation_space(&self) -> ObservationSpace {
+        self.observe().space()
+    }
+
+    /// `apply_action`, but an action outside `action_space` is refused with
+    /// the reason instead of being silently dropped.
+    fn try_apply(&mut self, action: Action) -> Result<(), ActionError> {
+        self.action_space().check(&action)?;
+        self.apply_action(action);
+        Ok(())
+    }
 }
@@ -0,0 +1,605 @@
+//! Gravitational N-body in the plane (G = 1, Plummer-softened), integrated
+//! with the symplectic schemes from `ode.rs` and a Barnes–Hut quadtree once
+//! N is large enough for O(N²) forces to hurt.
+//!
+//! Total energy and angular momentum are conserved by the physics, so their
+//! drift is a direct readout of integrator / tree error.
+
+use super::{param_value, Action, ActionSpace, Experimentable, Observation, ParamValue, SimState, Simulation};
+use crate::registry::NBODY_SCHEMA;
+use crate::ode::{second_order_step, Integrator};
+use crate::rng::SimRng;
+
+/// Up to this many bodies, forces are summed directly
+const DIRECT_MAX: usize = 64;
+/// Potential energy is exact up to this many bodies, tree-estimated beyond
+const EXACT_POTENTIAL_MAX: usize = 2048;
+const MAX_TREE_DEPTH: usize = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NBodyPreset {
+    /// Chenciner–Montgomery three-body choreography
+    FigureEight,
+    /// Eccentric equal-mass binary
+    Binary,
+    /// Star with five light planets on circular orbits
+    Solar,
+    /// Uniform disc of N bodies released from (almost) rest
+    ColdCollapse,
+    /// Central mass with a rotating disc of N light bodies
+    Disk,
+}
+
+impl NBodyPreset {
+    pub const ALL: [NBodyPreset; 5] = [
+        NBodyPreset::FigureEight,
+        NBodyPreset::Binary,
+        NBodyPreset::Solar,
+        NBodyPreset::ColdCollapse,
+        NBodyPreset::Disk,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            NBodyPreset::FigureEight => "figure8",
+            NBodyPreset::Binary => "binary",
+            NBodyPreset::Solar => "solar",
+            NBodyPreset::ColdCollapse => "collapse",
+            NBodyPreset::Disk => "disk",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+#[derive(Clone)]
+pub struct NBody {
+    /// Flattened (x, y) per body
+    positions: Vec<f64>,
+    velocities: Vec<f64>,
+    accelerations: Vec<f64>,
+    masses: Vec<f64>,
+    pub integrator: Integrator,
+    pub dt: f64,
+    /// Barnes–Hut opening angle; 0 forces direct summation
+    pub theta: f64,
+    softening: f64,
+    preset: NBodyPreset,
+    count: usize,
+    seed: u64,
+    /// Side of the square shown by `get_state`, centred on the centre of mass
+    view_size: f64,
+    initial_energy: f64,
+    initial_angular_momentum: f64,
+    /// Energy / angular momentum added by agent kicks since the baseline,
+    /// so they don't count as drift
+    injected_energy: f64,
+    injected_angular_momentum: f64,
+    energy: f64,
+    angular_momentum: f64,
+    time: f64,
+}
+
+impl NBody {
+    /// `count` only matters for the many-body presets.
+    pub fn init(preset: NBodyPreset, count: usize, seed: u64) -> Self {
+        let mut sim = Self {
+            positions: Vec::new(),
+            velocities: Vec::new(),
+            accelerations: Vec::new(),
+            masses: Vec::new(),
+            integrator: Integrator::Leapfrog,
+            // dt, softening and view_size are the preset's, set by reset()
+            dt: 0.001,
+            theta: 0.5,
+            softening: 0.0,
+            preset,
+            count,
+            seed,
+            view_size: 4.0,
+            initial_energy: 0.0,
+            initial_angular_momentum: 0.0,
+            injected_energy: 0.0,
+            injected_angular_momentum: 0.0,
+            energy: 0.0,
+            angular_momentum: 0.0,
+            time: 0.0,
+        };
+        sim.reset();
+        sim
+    }
+
+    pub fn reset(&mut self) {
+        let mut rng = SimRng::new(self.seed);
+        let mut bodies: Vec<(f64, f64, f64, f64, f64)> = Vec::new(); // (m, x, y, vx, vy)
+        match self.preset {
+            NBodyPreset::FigureEight => {
+                let (x, y) = (0.970_004_36, -0.243_087_53);
+                let (vx, vy) = (0.466_203_685, 0.432_365_73);
+                bodies.push((1.0, x, y, vx, vy));
+                bodies.push((1.0, 0.0, 0.0, -2.0 * vx, -2.0 * vy));
+                bodies.push((1.0, -x, -y, vx, vy));
+                (self.dt, self.softening, self.view_size) = (0.001, 0.0, 3.0);
+            }
+            NBodyPreset::Binary => {
+                // Circular speed for separation 1 is sqrt(0.5); slower makes it eccentric
+                let v = 0.5f64.sqrt() * 0.8;
+                bodies.push((1.0, -0.5, 0.0, 0.0, -v));
+                bodies.push((1.0, 0.5, 0.0, 0.0, v));
+                (self.dt, self.softening, self.view_size) = (0.001, 0.0, 2.5);
+            }
+            NBodyPreset::Solar => {
+                bodies.push((1.0, 0.0, 0.0, 0.0, 0.0));
+                for (r, m) in [(0.4, 1e-5), (0.7, 2e-5), (1.0, 3e-5), (1.5, 1e-3), (2.5, 3e-4)] {
+                    let phase = rng.range_f64(0.0, std::f64::consts::TAU);
+                    let v = (1.0f64 / r).sqrt();
+                    bodies.push((m, r * phase.cos(), r * phase.sin(), -v * phase.sin(), v * phase.cos()));
+                }
+                (self.dt, self.softening, self.view_size) = (0.002, 0.0, 6.0);
+            }
+            NBodyPreset::ColdCollapse => {
+                let n = self.count.max(2);
+                for _ in 0..n {
+                    let r = rng.next_f64().sqrt();
+                    let a = rng.range_f64(0.0, std::f64::consts::TAU);
+                    // A little solid-body rotation so it doesn't collapse to a point
+                    bodies.push((1.0 / n as f64, r * a.cos(), r * a.sin(), -0.2 * r * a.sin(), 0.2 * r * a.cos()));
+                }
+                (self.dt, self.softening, self.view_size) = (0.002, 0.05, 4.0);
+            }
+            NBodyPreset::Disk => {
+                let n = self.count.max(1);
+                let disk_mass = 0.1;
+                let (r_in, r_out) = (0.3f64, 2.0f64);
+                bodies.push((1.0, 0.0, 0.0, 0.0, 0.0));
+                for _ in 0..n {
+                    // Uniform surface density between r_in and r_out
+                    let r = (r_in * r_in + rng.next_f64() * (r_out * r_out - r_in * r_in)).sqrt();
+                    let a = rng.range_f64(0.0, std::f64::consts::TAU);
+                    let enclosed = 1.0 + disk_mass * (r * r - r_in * r_in) / (r_out * r_out - r_in * r_in);
+                    let v = (enclosed / r).sqrt();
+                    bodies.push((disk_mass / n as f64, r * a.cos(), r * a.sin(), -v * a.sin(), v * a.cos()));
+                }
+                (self.dt, self.softening, self.view_size) = (0.002, 0.02, 5.0);
+            }
+        }
+
+        // Centre-of-mass frame
+        let total: f64 = bodies.iter().map(|b| b.0).sum();
+        let (mut cx, mut cy, mut px, mut py) = (0.0, 0.0, 0.0, 0.0);
+        for &(m, x, y, vx, vy) in &bodies {
+            cx += m * x / total;
+            cy += m * y / total;
+            px += m * vx / total;
+            py += m * vy / total;
+        }
+
+        self.masses = bodies.iter().map(|b| b.0).collect();
+        self.positions = bodies.iter().flat_map(|b| [b.1 - cx, b.2 - cy]).collect();
+        self.velocities = bodies.iter().flat_map(|b| [b.3 - px, b.4 - py]).collect();
+        self.accelerations = vec![0.0; self.positions.len()];
+        accelerations(&self.positions, &self.masses, self.theta, self.softening, &mut self.accelerations);
+        self.time = 0.0;
+        self.rebaseline();
+    }
+
+    /// Take the current energy / angular momentum as the reference for drift.
+    fn rebaseline(&mut self) {
+        self.update_diagnostics();
+        self.initial_energy = self.energy;
+        self.initial_angular_momentum = self.angular_momentum;
+        self.injected_energy = 0.0;
+        self.injected_angular_momentum = 0.0;
+    }
+
+    fn update_diagnostics(&mut self) {
+        let kinetic: f64 = self
+            .masses
+            .iter()
+            .zip(self.velocities.chunks(2))
+            .map(|(m, v)| 0.5 * m * (v[0] * v[0] + v[1] * v[1]))
+            .sum();
+        self.energy = kinetic + potential_energy(&self.positions, &self.masses, self.theta, self.softening);
+        self.angular_momentum = self
+            .masses
+            .iter()
+            .zip(self.positions.chunks(2).zip(self.velocities.chunks(2)))
+            .map(|(m, (p, v))| m * (p[0] * v[1] - p[1] * v[0]))
+            .sum();
+    }
+
+    pub fn len(&self) -> usize {
+        self.masses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.masses.is_empty()
+    }
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn energy(&self) -> f64 {
+        self.energy
+    }
+
+    pub fn angular_momentum(&self) -> f64 {
+        self.angular_momentum
+    }
+
+    /// (E - E0 - injected) / |E0|
+    pub fn energy_drift(&self) -> f64 {
+        (self.energy - self.initial_energy - self.injected_energy) / self.initial_energy.abs().max(1e-12)
+    }
+
+    /// (L - L0 - injected) / |L0|, or absolute when L0 is ~0 (the figure-eight has none)
+    pub fn angular_momentum_drift(&self) -> f64 {
+        let d = self.angular_momentum - self.initial_angular_momentum - self.injected_angular_momentum;
+        if self.initial_angular_momentum.abs() < 1e-9 { d } else { d / self.initial_angular_momentum.abs() }
+    }
+
+    /// 2K / |U|: 1 for a system in virial equilibrium
+    pub fn virial_ratio(&self) -> f64 {
+        let potential = potential_energy(&self.positions, &self.masses, self.theta, self.softening);
+        let kinetic = self.energy - potential;
+        2.0 * kinetic / potential.abs().max(1e-12)
+    }
+
+    pub fn set_softening(&mut self, softening: f64) {
+        // Softening changes the Hamiltonian itself, so drift restarts from here
+        self.softening = softening.max(0.0);
+        accelerations(&self.positions, &self.masses, self.theta, self.softening, &mut self.accelerations);
+        self.rebaseline();
+    }
+}
+
+impl Simulation for NBody {
+    fn new() -> Self {
+        Self::init(NBodyPreset::Disk, 800, 0)
+    }
+
+    fn step(&mut self) {
+        let (theta, softening) = (self.theta, self.softening);
+        let masses = &self.masses;
+        let mut accel = |x: &[f64], out: &mut [f64]| accelerations(x, masses, theta, softening, out);
+        // A few substeps per frame keeps dt small without crawling on screen
+        for _ in 0..10 {
+            second_order_step(self.integrator, &mut self.positions, &mut self.velocities, &mut self.accelerations, self.dt, &mut accel);
+        }
+        self.time += 10.0 * self.dt;
+        self.update_diagnostics();
+    }
+
+    fn get_state(&self) -> SimState {
+        let total: f64 = self.masses.iter().sum::<f64>().max(1e-12);
+        let (mut cx, mut cy) = (0.0, 0.0);
+        for (m, p) in self.masses.iter().zip(self.positions.chunks(2)) {
+            cx += m * p[0] / total;
+            cy += m * p[1] / total;
+        }
+        let half = self.view_size / 2.0;
+        // Heavy bodies (stars) get their own colour
+        let typical = total / self.len().max(1) as f64;
+        SimState::Particles {
+            width: self.view_size,
+            height: self.view_size,
+            positions: self.positions.chunks(2).map(|p| (p[0] - cx + half, p[1] - cy + half)).collect(),
+            velocities: self.velocities.chunks(2).map(|v| (v[0], v[1])).collect(),
+            species: self.masses.iter().map(|&m| u8::from(m > 10.0 * typical)).collect(),
+        }
+    }
+
+    fn set_param(&mut self, key: &str, value: ParamValue) {
+        match (key, value) {
+            ("preset", ParamValue::String(name)) => {
+                if let Some(preset) = NBodyPreset::from_name(&name) {
+                    self.preset = preset;
+                    self.reset();
+                }
+            }
+            ("integrator", ParamValue::String(name)) => {
+                if let Some(integrator) = Integrator::from_name(&name) {
+                    self.integrator = integrator;
+                }
+            }
+            ("count", ParamValue::Int(n)) => {
+                self.count = n.clamp(2, 100_000) as usize;
+                self.reset();
+            }
+            ("seed", ParamValue::Int(seed)) => {
+                self.seed = seed as u64;
+                self.reset();
+            }
+            ("dt", ParamValue::Float(v)) => self.dt = v.clamp(1e-5, 0.05),
+            ("theta", ParamValue::Float(v)) => self.theta = v.clamp(0.0, 1.5),
+            ("softening", ParamValue::Float(v)) => self.set_softening(v),
+            ("view_size", ParamValue::Float(v)) => self.view_size = v.max(1e-3),
+            _ => {}
+        }
+    }
+
+    fn as_experimentable(&mut self) -> Option<&mut dyn Experimentable> {
+        Some(self)
+    }
+}
+
+impl Experimentable for NBody {
+    fn apply_action(&mut self, action: Action) {
+        match action {
+            // Speed body `which` up or down by a factor (1 + delta). That's an
+            // outside kick, not integration error, so what it adds is booked
+            // separately and drift keeps accumulating across kicks.
+            Action::Perturb { which, delta } => {
+                if self.is_empty() { return; }
+                let i = which as usize % self.len();
+                let (m, x, y) = (self.masses[i], self.positions[2 * i], self.positions[2 * i + 1]);
+                let (vx, vy) = (self.velocities[2 * i], self.velocities[2 * i + 1]);
+                let (nx, ny) = (vx * (1.0 + delta), vy * (1.0 + delta));
+                self.injected_energy += 0.5 * m * (nx * nx + ny * ny - vx * vx - vy * vy);
+                self.injected_angular_momentum += m * (x * (ny - vy) - y * (nx - vx));
+                self.velocities[2 * i] = nx;
+                self.velocities[2 * i + 1] = ny;
+                self.update_diagnostics();
+            }
+            Action::SetParam { name, value } => self.set_param(&name, param_value(NBODY_SCHEMA, &name, value)),
+            _ => {}
+        }
+    }
+
+    fn action_space(&self) -> ActionSpace {
+        // Only the first 256 bodies can be addressed through a u8
+        ActionSpace::new().with_perturb("body", self.len().min(256), -0.5, 0.5).with_params(NBODY_SCHEMA)
+    }
+
+    fn observe(&self) -> Observation {
+        Observation::named(&[
+            ("energy_drift", self.energy_drift()),
+            ("angular_momentum_drift", self.angular_momentum_drift()),
+            ("virial_ratio", self.virial_ratio()),
+        ])
+    }
+
+    fn reward(&self) -> f64 {
+        // Good settings keep the invariants invariant
+        -((self.energy_drift().abs() + self.angular_momentum_drift().abs()) * 1000.0).min(10.0)
+    }
+}
+
+// --- Forces ---
+
+fn accelerations(positions: &[f64], masses: &[f64], theta: f64, softening: f64, out: &mut [f64]) {
+    let n = masses.len();
+    let eps2 = softening * softening;
+    if theta <= 0.0 || n <= DIRECT_MAX {
+        out.iter_mut().for_each(|a| *a = 0.0);
+        for i in 0..n {
+            for j in i + 1..n {
+                let dx = positions[2 * j] - positions[2 * i];
+                let dy = positions[2 * j + 1] - positions[2 * i + 1];
+                let r2 = dx * dx + dy * dy + eps2;
+                let inv_r3 = 1.0 / (r2 * r2.sqrt());
+                out[2 * i] += masses[j] * dx * inv_r3;
+                out[2 * i + 1] += masses[j] * dy * inv_r3;
+                out[2 * j] -= masses[i] * dx * inv_r3;
+                out[2 * j + 1] -= masses[i] * dy * inv_r3;
+            }
+        }
+        return;
+    }
+
+    let tree = QuadTree::build(positions, masses);
+    let walk = |(i, a): (usize, &mut [f64])| {
+        let (ax, ay, _) = tree.field_at(i, positions, masses, theta, eps2);
+        a[0] = ax;
+        a[1] = ay;
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        out.par_chunks_mut(2).enumerate().for_each(walk);
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        out.chunks_mut(2).enumerate().for_each(walk);
+    }
+}
+
+fn potential_energy(positions: &[f64], masses: &[f64], theta: f64, softening: f64) -> f64 {
+    let n = masses.len();
+    let eps2 = softening * softening;
+    if theta <= 0.0 || n <= EXACT_POTENTIAL_MAX {
+        let mut total = 0.0;
+        for i in 0..n {
+            for j in i + 1..n {
+                let dx = positions[2 * j] - positions[2 * i];
+                let dy = positions[2 * j + 1] - positions[2 * i + 1];
+                total -= masses[i] * masses[j] / (dx * dx + dy * dy + eps2).sqrt();
+            }
+        }
+        return total;
+    }
+    let tree = QuadTree::build(positions, masses);
+    // Every pair is seen from both ends
+    0.5 * (0..n).map(|i| masses[i] * tree.field_at(i, positions, masses, theta, eps2).2).sum::<f64>()
+}
+
+// --- Barnes–Hut quadtree ---
+
+const NO_CHILD: usize = usize::MAX;
+
+struct Node {
+    mass: f64,
+    com: (f64, f64),
+    /// Side length of the node's square
+    size: f64,
+    children: [usize; 4],
+    /// Bodies in this node are order[start..end]
+    start: usize,
+    end: usize,
+}
+
+struct QuadTree {
+    nodes: Vec<Node>,
+    /// Body indices, grouped so every node's bodies are contiguous
+    order: Vec<usize>,
+    /// slot[i] = position of body i in `order`
+    slot: Vec<usize>,
+}
+
+impl QuadTree {
+    fn build(positions: &[f64], masses: &[f64]) -> Self {
+        let n = masses.len();
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for p in positions.chunks(2) {
+            min_x = min_x.min(p[0]);
+            max_x = max_x.max(p[0]);
+            min_y = min_y.min(p[1]);
+            max_y = max_y.max(p[1]);
+        }
+        let size = (max_x - min_x).max(max_y - min_y).max(1e-9) * 1.0001;
+        let mut tree = Self { nodes: Vec::with_capacity(2 * n), order: (0..n).collect(), slot: vec![0; n] };
+        tree.subdivide(positions, masses, 0, n, (min_x, min_y), size, 0);
+        for (s, &i) in tree.order.iter().enumerate() {
+            tree.slot[i] = s;
+        }
+        tree
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn subdivide(&mut self, positions: &[f64], masses: &[f64], start: usize, end: usize, corner: (f64, f64), size: f64, depth: usize) -> usize {
+        let (mut mass, mut mx, mut my) = (0.0, 0.0, 0.0);
+        for &i in &self.order[start..end] {
+            mass += masses[i];
+            mx += masses[i] * positions[2 * i];
+            my += masses[i] * positions[2 * i + 1];
+        }
+        let com = if mass > 0.0 { (mx / mass, my / mass) } else { (corner.0 + size / 2.0, corner.1 + size / 2.0) };
+        let index = self.nodes.len();
+        self.nodes.push(Node { mass, com, size, children: [NO_CHILD; 4], start, end });
+
+        // Leaves: single bodies, or piles of coincident ones at the depth limit
+        if end - start <= 1 || depth >= MAX_TREE_DEPTH {
+            return index;
+        }
+
+        let half = size / 2.0;
+        let (mid_x, mid_y) = (corner.0 + half, corner.1 + half);
+        let quadrant = |i: usize| usize::from(positions[2 * i] >= mid_x) + 2 * usize::from(positions[2 * i + 1] >= mid_y);
+        self.order[start..end].sort_unstable_by_key(|&i| quadrant(i));
+
+        let mut lo = start;
+        for q in 0..4 {
+            let hi = lo + self.order[lo..end].iter().take_while(|&&i| quadrant(i) == q).count();
+            if hi > lo {
+                let child_corner = (corner.0 + half * (q % 2) as f64, corner.1 + half * (q / 2) as f64);
+                let child = self.subdivide(positions, masses, lo, hi, child_corner, half, depth + 1);
+                self.nodes[index].children[q] = child;
+            }
+            lo = hi;
+        }
+        index
+    }
+
+    /// (ax, ay, potential) at body `i` from everything else
+    fn field_at(&self, i: usize, positions: &[f64], masses: &[f64], theta: f64, eps2: f64) -> (f64, f64, f64) {
+        let (px, py) = (positions[2 * i], positions[2 * i + 1]);
+        let slot = self.slot[i];
+        let (mut ax, mut ay, mut phi) = (0.0, 0.0, 0.0);
+        let mut add = |m: f64, x: f64, y: f64| {
+            let (dx, dy) = (x - px, y - py);
+            let r2 = dx * dx + dy * dy + eps2;
+            let inv_r = 1.0 / r2.sqrt();
+            ax += m * dx * inv_r * inv_r * inv_r;
+            ay += m * dy * inv_r * inv_r * inv_r;
+            phi -= m * inv_r;
+        };
+
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let contains_self = (node.start..node.end).contains(&slot);
+            let is_leaf = node.children.iter().all(|&c| c == NO_CHILD);
+            if is_leaf {
+                for &j in &self.order[node.start..node.end] {
+                    if j != i {
+                        add(masses[j], positions[2 * j], positions[2 * j + 1]);
+                    }
+                }
+                continue;
+            }
+            let (dx, dy) = (node.com.0 - px, node.com.1 - py);
+            if !contains_self && node.size * node.size < theta * theta * (dx * dx + dy * dy) {
+                add(node.mass, node.com.0, node.com.1);
+            } else {
+                stack.extend(node.children.iter().copied().filter(|&c| c != NO_CHILD));
+            }
+        }
+        (ax, ay, phi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Largest |energy drift| and |angular momentum drift| over `frames` frames
+    fn worst_drift(sim: &mut NBody, frames: usize) -> (f64, f64) {
+        let (mut energy, mut angular) = (0.0f64, 0.0f64);
+        for _ in 0..frames {
+            sim.step();
+            energy = energy.max(sim.energy_drift().abs());
+            angular = angular.max(sim.angular_momentum_drift().abs());
+        }
+        (energy, angular)
+    }
+
+    #[test]
+    fn symplectic_integrators_conserve_a_binary() {
+        // The eccentric binary has a period of about 2.8, so 2000 frames (t = 20) is ~7 orbits
+        for (integrator, energy_tolerance) in [(Integrator::Leapfrog, 5e-4), (Integrator::Yoshida4, 1e-6)] {
+            let mut sim = NBody::init(NBodyPreset::Binary, 2, 0);
+            sim.integrator = integrator;
+            let (energy, angular) = worst_drift(&mut sim, 2000);
+            assert!(energy < energy_tolerance, "{:?}: energy drift {:e}", integrator, energy);
+            assert!(angular < 1e-9, "{:?}: angular momentum drift {:e}", integrator, angular);
+        }
+    }
+
+    #[test]
+    fn barnes_hut_matches_direct_summation_as_theta_vanishes() {
+        let mut rng = SimRng::new(5);
+        let n = 4 * DIRECT_MAX;
+        let positions: Vec<f64> = (0..2 * n).map(|_| rng.range_f64(-1.0, 1.0)).collect();
+        let masses: Vec<f64> = (0..n).map(|_| rng.range_f64(0.5, 1.5) / n as f64).collect();
+        let mut direct = vec![0.0; 2 * n];
+        let mut tree = vec![0.0; 2 * n];
+        accelerations(&positions, &masses, 0.0, 0.05, &mut direct);
+        accelerations(&positions, &masses, 1e-3, 0.05, &mut tree);
+        let scale = direct.iter().fold(0.0f64, |m, a| m.max(a.abs()));
+        for (i, (d, t)) in direct.iter().zip(&tree).enumerate() {
+            assert!((d - t).abs() < 1e-9 * scale, "component {}: direct {} tree {}", i, d, t);
+        }
+        // And a real opening angle stays close
+        accelerations(&positions, &masses, 0.5, 0.05, &mut tree);
+        let error = direct.iter().zip(&tree).map(|(d, t)| (d - t).abs()).fold(0.0f64, f64::max);
+        assert!(error < 0.05 * scale, "theta 0.5 error {} of {}", error, scale);
+    }
+
+    #[test]
+    fn kicks_are_not_drift() {
+        let mut sim = NBody::init(NBodyPreset::Binary, 2, 0);
+        worst_drift(&mut sim, 100);
+        let before = (sim.energy_drift(), sim.angular_momentum_drift());
+        sim.apply_action(Action::Perturb { which: 0, delta: 0.1 });
+        assert!((sim.energy_drift() - before.0).abs() < 1e-12, "{} -> {}", before.0, sim.energy_drift());
+        assert!((sim.angular_momentum_drift() - before.1).abs() < 1e-12, "{} -> {}", before.1, sim.angular_momentum_drift());
+        sim.apply_action(Action::Perturb { which: 1, delta: -0.1 });
+        let (energy, angular) = worst_drift(&mut sim, 500);
+        assert!(energy < 5e-4, "energy drift after kicks {:e}", energy);
+        assert!(angular < 1e-9, "angular momentum drift after kicks {:e}", angular);
+    }
+}
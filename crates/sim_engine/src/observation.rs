@@ -0,0 +1,174 @@
+//! What an agent sees each tick, and a descriptor of its shape.
+//!
+//! Simulations usually return a `Dict` of named scalars, plus a downsampled
+//! `Tensor` of the field when there is one. Tabular agents use `features()`
+//! (every scalar and vector, tensors skipped); agents that want to look at
+//! the field use `flatten()` or pick the tensor out by name.
+
+use serde::{Deserialize, Serialize};
+
+/// Longest side of the field tensors simulations attach to their observations
+pub const FIELD_SIDE: usize = 16;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Observation {
+    Scalar(f64),
+    /// Flat vector of any length
+    Vector(Vec<f64>),
+    /// Dense row-major tensor; fields are `[height, width]`
+    Tensor { shape: Vec<usize>, data: Vec<f64> },
+    /// Named entries, in a fixed order
+    Dict(Vec<(String, Observation)>),
+    Text(String),
+    None,
+}
+
+impl Observation {
+    /// A dict of named scalars, e.g. `Observation::named(&[("mass", m), ("speed", v)])`.
+    pub fn named(features: &[(&str, f64)]) -> Self {
+        Observation::Dict(features.iter().map(|&(name, v)| (name.to_string(), Observation::Scalar(v))).collect())
+    }
+
+    /// Add an entry to a dict (other variants are returned unchanged).
+    pub fn with(mut self, name: &str, value: Observation) -> Self {
+        if let Observation::Dict(entries) = &mut self {
+            entries.push((name.to_string(), value));
+        }
+        self
+    }
+
+    /// A `width`×`height` row-major field, block-averaged so neither side
+    /// exceeds `max_side`.
+    pub fn field(width: usize, height: usize, values: &[f64], max_side: usize) -> Self {
+        if width == 0 || height == 0 || values.len() < width * height {
+            return Observation::Tensor { shape: vec![0, 0], data: Vec::new() };
+        }
+        let factor = width.max(height).div_ceil(max_side.max(1)).max(1);
+        let (w, h) = (width.div_ceil(factor), height.div_ceil(factor));
+        let mut sums = vec![0.0; w * h];
+        let mut counts = vec![0usize; w * h];
+        for y in 0..height {
+            for x in 0..width {
+                let i = (y / factor) * w + x / factor;
+                sums[i] += values[y * width + x];
+                counts[i] += 1;
+            }
+        }
+        let data = sums.iter().zip(&counts).map(|(s, &c)| s / c.max(1) as f64).collect();
+        Observation::Tensor { shape: vec![h, w], data }
+    }
+
+    /// Dict entry by name; dotted paths ("child.mass") walk nested dicts.
+    pub fn get(&self, path: &str) -> Option<&Observation> {
+        let (head, rest) = match path.split_once('.') {
+            Some((head, rest)) => (head, Some(rest)),
+            None => (path, None),
+        };
+        let Observation::Dict(entries) = self else { return None };
+        let (_, value) = entries.iter().find(|(name, _)| name == head)?;
+        match rest {
+            Some(rest) => value.get(rest),
+            None => Some(value),
+        }
+    }
+
+    pub fn as_scalar(&self) -> Option<f64> {
+        match self {
+            Observation::Scalar(v) => Some(*v),
+            Observation::Vector(v) if v.len() == 1 => Some(v[0]),
+            _ => None,
+        }
+    }
+
+    /// Every number, depth first in dict order.
+    pub fn flatten(&self) -> Vec<f64> {
+        let mut out = Vec::new();
+        self.collect(&mut out, true);
+        out
+    }
+
+    /// Scalars and vectors only (tensors skipped): the compact summary that
+    /// tabular agents discretise.
+    pub fn features(&self) -> Vec<f64> {
+        let mut out = Vec::new();
+        self.collect(&mut out, false);
+        out
+    }
+
+    fn collect(&self, out: &mut Vec<f64>, tensors: bool) {
+        match self {
+            Observation::Scalar(v) => out.push(*v),
+            Observation::Vector(v) => out.extend_from_slice(v),
+            Observation::Tensor { data, .. } if tensors => out.extend_from_slice(data),
+            Observation::Dict(entries) => entries.iter().for_each(|(_, value)| value.collect(out, tensors)),
+            _ => {}
+        }
+    }
+
+    pub fn space(&self) -> ObservationSpace {
+        match self {
+            Observation::Scalar(_) => ObservationSpace::Scalar,
+            Observation::Vector(v) => ObservationSpace::Vector(v.len()),
+            Observation::Tensor { shape, .. } => ObservationSpace::Tensor(shape.clone()),
+            Observation::Dict(entries) => {
+                ObservationSpace::Dict(entries.iter().map(|(name, value)| (name.clone(), value.space())).collect())
+            }
+            Observation::Text(_) => ObservationSpace::Text,
+            Observation::None => ObservationSpace::None,
+        }
+    }
+}
+
+/// The shape of an `Observation`, for agents to size themselves before the first tick.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum ObservationSpace {
+    Scalar,
+    Vector(usize),
+    Tensor(Vec<usize>),
+    Dict(Vec<(String, ObservationSpace)>),
+    Text,
+    None,
+}
+
+impl ObservationSpace {
+    /// Length of `Observation::flatten`
+    pub fn flat_len(&self) -> usize {
+        self.len(true)
+    }
+
+    /// Length of `Observation::features`
+    pub fn feature_len(&self) -> usize {
+        self.len(false)
+    }
+
+    fn len(&self, tensors: bool) -> usize {
+        match self {
+            ObservationSpace::Scalar => 1,
+            ObservationSpace::Vector(n) => *n,
+            ObservationSpace::Tensor(shape) if tensors => shape.iter().product(),
+            ObservationSpace::Dict(entries) => entries.iter().map(|(_, space)| space.len(tensors)).sum(),
+            _ => 0,
+        }
+    }
+
+    /// One name per entry of `features()`: "mass", "row[3]", "child.speed"...
+    pub fn feature_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        self.collect_names("", &mut names);
+        names
+    }
+
+    fn collect_names(&self, prefix: &str, names: &mut Vec<String>) {
+        match self {
+            ObservationSpace::Scalar => names.push(prefix.to_string()),
+            ObservationSpace::Vector(n) => names.extend((0..*n).map(|i| format!("{}[{}]", prefix, i))),
+            ObservationSpace::Dict(entries) => {
+                for (name, space) in entries {
+                    let path = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+                    space.collect_names(&path, names);
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,4 +1,5 @@
-use super::{ParamValue, SimState, Simulation, Experimentable, Action, Observation};
+use super::{param_value, ParamValue, SimState, Simulation, Experimentable, Action, ActionSpace, Observation};
+use crate::registry::LORENZ_SCHEMA;
 use diffeq_rs::prelude::*;
 use serde::Serialize;
 
@@ -54,8 +55,21 @@ impl Simulation for ODESim {
         SimState::Points(self.tail.clone())
     }
 
-    fn set_param(&mut self, _name: &str, _value: ParamValue) {
-        // Standard UI parameter setting (optional stub)
+    fn set_param(&mut self, name: &str, value: ParamValue) {
+        match (name, value) {
+            ("sigma", ParamValue::Float(v)) => self.params.sigma = v,
+            ("rho", ParamValue::Float(v)) => self.params.rho = v,
+            ("beta", ParamValue::Float(v)) => self.params.beta = v,
+            ("a", ParamValue::Float(v)) => self.params.a = v,
+            ("b", ParamValue::Float(v)) => self.params.b = v,
+            ("c", ParamValue::Float(v)) => self.params.c = v,
+            ("dt", ParamValue::Float(v)) => self.dt = v.clamp(1e-4, 0.05),
+            ("system", ParamValue::String(s)) => {
+                self.system = if s.eq_ignore_ascii_case("rossler") { ODESystem::Rossler } else { ODESystem::Lorenz };
+                self.reset_state();
+            }
+            _ => {}
+        }
     }
     
     fn as_experimentable(&mut self) -> Option<&mut dyn Experimentable> {
@@ -92,20 +106,17 @@ impl Experimentable for ODESim {
                 if which < 3 { self.state[which as usize] += delta; }
             }
             // --- NEW: Allow AI to tune constants ---
-            Action::SetParam { name, value } => {
-                match name.as_str() {
-                    "sigma" => self.params.sigma = value,
-                    "rho"   => self.params.rho   = value,
-                    "beta"  => self.params.beta  = value,
-                    _ => {}
-                }
-            }
+            Action::SetParam { name, value } => self.set_param(&name, param_value(LORENZ_SCHEMA, &name, value)),
             _ => {}
         }
     }
 
+    fn action_space(&self) -> ActionSpace {
+        ActionSpace::new().with_perturb("axis", 3, -10.0, 10.0).with_params(LORENZ_SCHEMA)
+    }
+
     fn observe(&self) -> Observation {
-        Observation::StateVec(self.state)
+        Observation::named(&[("x", self.state[0]), ("y", self.state[1]), ("z", self.state[2])])
     }
 
     fn reward(&self) -> f64 {
@@ -134,3 +145,74 @@ fn add_arrays<const N: usize>(a: [f64; N], b: [f64; N]) -> [f64; N] {
 fn mul_array<const N: usize>(a: [f64; N], k: f64) -> [f64; N] {
     let mut out = [0.0; N]; for i in 0..N { out[i] = a[i] * k; } out
 }
+
+// --- Second-order integrators (shared with nbody.rs) ---
+
+/// Scheme for advancing x'' = a(x) on flattened state vectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Integrator {
+    /// Kick-drift-kick leapfrog: symplectic, 2nd order, one force evaluation per step
+    Leapfrog,
+    /// Yoshida's 4th-order composition of three leapfrog steps, still symplectic
+    Yoshida4,
+    /// Classic RK4: more accurate per step, but energy drifts secularly
+    Rk4,
+}
+
+impl Integrator {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "leapfrog" | "verlet" => Some(Integrator::Leapfrog),
+            "yoshida" | "yoshida4" => Some(Integrator::Yoshida4),
+            "rk4" => Some(Integrator::Rk4),
+            _ => None,
+        }
+    }
+}
+
+/// One step of x'' = a(x). `acc` must hold a(x) on entry and holds a(x) at the
+/// new positions on return, so leapfrog stages reuse the last force evaluation.
+pub fn second_order_step<A>(integrator: Integrator, x: &mut [f64], v: &mut [f64], acc: &mut [f64], dt: f64, accel: &mut A)
+where A: FnMut(&[f64], &mut [f64]) {
+    match integrator {
+        Integrator::Leapfrog => leapfrog_step(x, v, acc, dt, accel),
+        Integrator::Yoshida4 => {
+            let cbrt2 = 2f64.cbrt();
+            let w1 = 1.0 / (2.0 - cbrt2);
+            let w0 = -cbrt2 * w1;
+            for w in [w1, w0, w1] {
+                leapfrog_step(x, v, acc, w * dt, accel);
+            }
+        }
+        Integrator::Rk4 => {
+            // State (x, v), derivative (v, a(x))
+            let n = x.len();
+            let (x0, v0) = (x.to_vec(), v.to_vec());
+            let mut kx = [vec![0.0; n], vec![0.0; n], vec![0.0; n], vec![0.0; n]];
+            let mut kv = [vec![0.0; n], vec![0.0; n], vec![0.0; n], vec![0.0; n]];
+            kx[0].copy_from_slice(v);
+            kv[0].copy_from_slice(acc);
+            let mut xs = vec![0.0; n];
+            for (stage, h) in [(1, 0.5 * dt), (2, 0.5 * dt), (3, dt)] {
+                for i in 0..n {
+                    xs[i] = x0[i] + h * kx[stage - 1][i];
+                    kx[stage][i] = v0[i] + h * kv[stage - 1][i];
+                }
+                accel(&xs, &mut kv[stage]);
+            }
+            for i in 0..n {
+                x[i] = x0[i] + dt / 6.0 * (kx[0][i] + 2.0 * kx[1][i] + 2.0 * kx[2][i] + kx[3][i]);
+                v[i] = v0[i] + dt / 6.0 * (kv[0][i] + 2.0 * kv[1][i] + 2.0 * kv[2][i] + kv[3][i]);
+            }
+            accel(x, acc);
+        }
+    }
+}
+
+fn leapfrog_step<A>(x: &mut [f64], v: &mut [f64], acc: &mut [f64], dt: f64, accel: &mut A)
+where A: FnMut(&[f64], &mut [f64]) {
+    for (vi, ai) in v.iter_mut().zip(acc.iter()) { *vi += 0.5 * dt * ai; }
+    for (xi, vi) in x.iter_mut().zip(v.iter()) { *xi += dt * vi; }
+    accel(x, acc);
+    for (vi, ai) in v.iter_mut().zip(acc.iter()) { *vi += 0.5 * dt * ai; }
+}
@@ -0,0 +1,229 @@
+//! Particle life: a few species with an asymmetric attraction matrix. Species i
+//! can chase species j while j flees i, which is enough to get cells,
+//! membranes and travelling clusters out of random initial conditions.
+
+use super::{param_value, Action, ActionSpace, Experimentable, Observation, ParamValue, SimState, Simulation};
+use crate::registry::PARTICLE_LIFE_SCHEMA;
+use crate::observation::FIELD_SIDE;
+use crate::particles::{parse_pair_key, ParticleSet, SpatialHash};
+use crate::rng::SimRng;
+
+#[derive(Clone)]
+pub struct ParticleLife {
+    particles: ParticleSet,
+    hash: SpatialHash,
+    /// attraction[i][j]: how strongly species i is pulled towards species j, -1..1
+    attraction: Vec<Vec<f64>>,
+    count: usize,
+    /// Interaction radius
+    pub r_max: f64,
+    /// Fraction of r_max that is a universal repulsive core
+    pub beta: f64,
+    pub force: f64,
+    /// Velocity half-life, in time units
+    pub friction_half_life: f64,
+    pub dt: f64,
+    seed: u64,
+    mean_neighbours: f64,
+}
+
+const SIZE: f64 = 256.0;
+
+impl ParticleLife {
+    pub fn init(count: usize, species: usize, seed: u64) -> Self {
+        let r_max = 24.0;
+        let mut sim = Self {
+            particles: ParticleSet::new(SIZE, SIZE),
+            hash: SpatialHash::new(SIZE, SIZE, r_max),
+            attraction: vec![Vec::new(); species.clamp(1, 16)],
+            count,
+            r_max,
+            beta: 0.3,
+            force: 10.0,
+            friction_half_life: 0.04,
+            dt: 0.02,
+            seed,
+            mean_neighbours: 0.0,
+        };
+        sim.reset();
+        sim
+    }
+
+    pub fn species(&self) -> usize {
+        self.attraction.len()
+    }
+
+    pub fn attraction(&self) -> &[Vec<f64>] {
+        &self.attraction
+    }
+
+    pub fn set_attraction(&mut self, i: usize, j: usize, value: f64) {
+        if let Some(a) = self.attraction.get_mut(i).and_then(|row| row.get_mut(j)) {
+            *a = value.clamp(-1.0, 1.0);
+        }
+    }
+
+    pub fn particles(&self) -> &ParticleSet {
+        &self.particles
+    }
+
+    /// Respawn particles and draw a fresh matrix from the current seed.
+    pub fn reset(&mut self) {
+        let mut rng = SimRng::new(self.seed);
+        let species = self.species();
+        self.particles = ParticleSet::scatter(SIZE, SIZE, self.count, species, 0.0, &mut rng);
+        self.attraction = random_matrix(species, &mut rng);
+        self.mean_neighbours = 0.0;
+    }
+
+    /// Force profile over r / r_max: linear repulsion inside beta, then a tent
+    /// of height `a` peaking halfway through the remaining range.
+    fn profile(&self, r: f64, a: f64) -> f64 {
+        if r < self.beta {
+            r / self.beta - 1.0
+        } else if r < 1.0 {
+            a * (1.0 - (2.0 * r - 1.0 - self.beta).abs() / (1.0 - self.beta))
+        } else {
+            0.0
+        }
+    }
+
+    /// Mean number of others within r_max, relative to a uniform scatter
+    fn clustering(&self) -> f64 {
+        let n = self.particles.len().max(1) as f64;
+        let uniform = n * std::f64::consts::PI * self.r_max * self.r_max / (SIZE * SIZE);
+        self.mean_neighbours / uniform.max(1e-9)
+    }
+
+    fn asymmetry(&self) -> f64 {
+        let s = self.species();
+        let mut total = 0.0;
+        for i in 0..s {
+            for j in 0..s {
+                total += (self.attraction[i][j] - self.attraction[j][i]).abs();
+            }
+        }
+        total / (s * s) as f64
+    }
+}
+
+fn random_matrix(species: usize, rng: &mut SimRng) -> Vec<Vec<f64>> {
+    (0..species).map(|_| (0..species).map(|_| rng.range_f64(-1.0, 1.0)).collect()).collect()
+}
+
+impl Simulation for ParticleLife {
+    fn new() -> Self {
+        Self::init(800, 6, 0)
+    }
+
+    fn step(&mut self) {
+        self.hash.rebuild(&self.particles.positions);
+        let friction = 0.5f64.powf(self.dt / self.friction_half_life);
+        let mut neighbours = 0usize;
+
+        let forces: Vec<(f64, f64)> = (0..self.particles.len())
+            .map(|i| {
+                let si = self.particles.species[i] as usize;
+                let mut f = (0.0, 0.0);
+                for j in self.hash.neighbours(self.particles.positions[i]) {
+                    if j == i { continue; }
+                    let (dx, dy) = self.particles.delta(i, j);
+                    let r = dx.hypot(dy);
+                    if r <= 0.0 || r >= self.r_max { continue; }
+                    neighbours += 1;
+                    let sj = self.particles.species[j] as usize;
+                    let magnitude = self.profile(r / self.r_max, self.attraction[si][sj]);
+                    f.0 += dx / r * magnitude;
+                    f.1 += dy / r * magnitude;
+                }
+                (f.0 * self.r_max * self.force, f.1 * self.r_max * self.force)
+            })
+            .collect();
+
+        for (v, f) in self.particles.velocities.iter_mut().zip(forces) {
+            v.0 = v.0 * friction + f.0 * self.dt;
+            v.1 = v.1 * friction + f.1 * self.dt;
+        }
+        self.particles.drift(self.dt);
+        self.mean_neighbours = neighbours as f64 / self.particles.len().max(1) as f64;
+    }
+
+    fn get_state(&self) -> SimState {
+        self.particles.state()
+    }
+
+    fn set_param(&mut self, key: &str, value: ParamValue) {
+        match (key, value) {
+            ("seed", ParamValue::Int(seed)) => {
+                self.seed = seed as u64;
+                self.reset();
+            }
+            ("species", ParamValue::Int(n)) => {
+                self.attraction = vec![Vec::new(); n.clamp(1, 16) as usize];
+                self.reset();
+            }
+            ("count", ParamValue::Int(n)) => {
+                self.count = n.clamp(1, 20_000) as usize;
+                self.reset();
+            }
+            // New random matrix, same particles
+            ("randomize", ParamValue::Bool(true)) => {
+                let mut rng = SimRng::from_entropy();
+                self.attraction = random_matrix(self.species(), &mut rng);
+            }
+            ("r_max", ParamValue::Float(v)) => {
+                self.r_max = v.clamp(2.0, SIZE / 3.0);
+                self.hash = SpatialHash::new(SIZE, SIZE, self.r_max);
+            }
+            ("beta", ParamValue::Float(v)) => self.beta = v.clamp(0.05, 0.95),
+            ("force", ParamValue::Float(v)) => self.force = v,
+            ("friction", ParamValue::Float(v)) => self.friction_half_life = v.max(1e-3),
+            ("dt", ParamValue::Float(v)) => self.dt = v.clamp(1e-4, 0.1),
+            (key, ParamValue::Float(v)) => {
+                if let Some((i, j)) = parse_pair_key(key, "attraction") {
+                    self.set_attraction(i, j, v);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn as_experimentable(&mut self) -> Option<&mut dyn Experimentable> {
+        Some(self)
+    }
+}
+
+impl Experimentable for ParticleLife {
+    fn apply_action(&mut self, action: Action) {
+        match action {
+            // Nudge one matrix entry; `which` indexes the flattened matrix row by row
+            Action::Perturb { which, delta } => {
+                let s = self.species();
+                let k = which as usize % (s * s);
+                let current = self.attraction[k / s][k % s];
+                self.set_attraction(k / s, k % s, current + delta);
+            }
+            Action::SetParam { name, value } => self.set_param(&name, param_value(PARTICLE_LIFE_SCHEMA, &name, value)),
+            _ => {}
+        }
+    }
+
+    fn action_space(&self) -> ActionSpace {
+        let s = self.species();
+        ActionSpace::new().with_perturb("attraction", (s * s).min(256), -1.0, 1.0).with_params(PARTICLE_LIFE_SCHEMA)
+    }
+
+    fn observe(&self) -> Observation {
+        Observation::named(&[
+            ("mean_speed", self.particles.mean_speed()),
+            ("clustering", self.clustering()),
+            ("asymmetry", self.asymmetry()),
+        ])
+        .with("density", self.particles.density_map(FIELD_SIDE))
+    }
+
+    fn reward(&self) -> f64 {
+        // Structure: particles gathered into clusters rather than a uniform gas
+        self.clustering() - 1.0
+    }
+}
@@ -0,0 +1,235 @@
+//! Shared pieces for the particle simulations (boids, particle life,
+//! Lennard-Jones): a periodic box of particles and a spatial hash for
+//! neighbour queries.
+
+use crate::rng::SimRng;
+use crate::{Observation, SimState};
+
+/// Positions, velocities and species of every particle in a periodic box.
+#[derive(Clone, Debug, Default)]
+pub struct ParticleSet {
+    pub width: f64,
+    pub height: f64,
+    pub positions: Vec<(f64, f64)>,
+    pub velocities: Vec<(f64, f64)>,
+    pub species: Vec<u8>,
+}
+
+impl ParticleSet {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self { width, height, ..Default::default() }
+    }
+
+    /// `count` particles at uniform random positions, species assigned round-robin,
+    /// velocities uniform in a disc of radius `speed`.
+    pub fn scatter(width: f64, height: f64, count: usize, species: usize, speed: f64, rng: &mut SimRng) -> Self {
+        let mut set = Self::new(width, height);
+        let species = species.clamp(1, u8::MAX as usize + 1);
+        for i in 0..count {
+            let pos = (rng.range_f64(0.0, width), rng.range_f64(0.0, height));
+            let angle = rng.range_f64(0.0, std::f64::consts::TAU);
+            let s = speed * rng.next_f64().sqrt();
+            set.push(pos, (s * angle.cos(), s * angle.sin()), (i % species) as u8);
+        }
+        set
+    }
+
+    pub fn push(&mut self, position: (f64, f64), velocity: (f64, f64), species: u8) {
+        self.positions.push(position);
+        self.velocities.push(velocity);
+        self.species.push(species);
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Shortest vector from particle `i` to particle `j` across the periodic edges.
+    pub fn delta(&self, i: usize, j: usize) -> (f64, f64) {
+        let (a, b) = (self.positions[i], self.positions[j]);
+        (min_image(b.0 - a.0, self.width), min_image(b.1 - a.1, self.height))
+    }
+
+    /// x += v·dt, wrapped back into the box.
+    pub fn drift(&mut self, dt: f64) {
+        for (p, v) in self.positions.iter_mut().zip(&self.velocities) {
+            p.0 = (p.0 + v.0 * dt).rem_euclid(self.width);
+            p.1 = (p.1 + v.1 * dt).rem_euclid(self.height);
+        }
+    }
+
+    pub fn mean_speed(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        self.velocities.iter().map(|v| v.0.hypot(v.1)).sum::<f64>() / self.len() as f64
+    }
+
+    /// Mean kinetic energy per particle (unit mass)
+    pub fn kinetic_energy(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        self.velocities.iter().map(|v| 0.5 * (v.0 * v.0 + v.1 * v.1)).sum::<f64>() / self.len() as f64
+    }
+
+    /// Particle counts on a `side`×`side` grid over the box, relative to a
+    /// uniform spread (1.0 everywhere when evenly mixed).
+    pub fn density_map(&self, side: usize) -> Observation {
+        let side = side.max(1);
+        let mut counts = vec![0.0; side * side];
+        for p in &self.positions {
+            let x = ((p.0 / self.width * side as f64) as usize).min(side - 1);
+            let y = ((p.1 / self.height * side as f64) as usize).min(side - 1);
+            counts[y * side + x] += 1.0;
+        }
+        let expected = (self.len() as f64 / counts.len() as f64).max(f64::MIN_POSITIVE);
+        Observation::Tensor { shape: vec![side, side], data: counts.iter().map(|c| c / expected).collect() }
+    }
+
+    pub fn state(&self) -> SimState {
+        SimState::Particles {
+            width: self.width,
+            height: self.height,
+            positions: self.positions.clone(),
+            velocities: self.velocities.clone(),
+            species: self.species.clone(),
+        }
+    }
+}
+
+fn min_image(d: f64, size: f64) -> f64 {
+    d - size * (d / size).round()
+}
+
+/// Uniform grid over a periodic box. Cells are at least `cell_size` wide, so
+/// every neighbour within that distance is in the 3×3 block around a point.
+#[derive(Clone, Debug)]
+pub struct SpatialHash {
+    cell_w: f64,
+    cell_h: f64,
+    cols: usize,
+    rows: usize,
+    /// entries[starts[c]..starts[c + 1]] are the particles in cell c
+    starts: Vec<usize>,
+    entries: Vec<usize>,
+}
+
+impl SpatialHash {
+    pub fn new(width: f64, height: f64, cell_size: f64) -> Self {
+        let cols = ((width / cell_size.max(1e-9)).floor() as usize).max(1);
+        let rows = ((height / cell_size.max(1e-9)).floor() as usize).max(1);
+        Self {
+            cell_w: width / cols as f64,
+            cell_h: height / rows as f64,
+            cols,
+            rows,
+            starts: vec![0; cols * rows + 1],
+            entries: Vec::new(),
+        }
+    }
+
+    fn cell_of(&self, (x, y): (f64, f64)) -> (usize, usize) {
+        let cx = ((x / self.cell_w) as usize).min(self.cols - 1);
+        let cy = ((y / self.cell_h) as usize).min(self.rows - 1);
+        (cx, cy)
+    }
+
+    /// Re-bucket all positions (counting sort, no per-cell allocations).
+    pub fn rebuild(&mut self, positions: &[(f64, f64)]) {
+        self.starts.iter_mut().for_each(|s| *s = 0);
+        let cells: Vec<usize> = positions
+            .iter()
+            .map(|&p| {
+                let (cx, cy) = self.cell_of(p);
+                cy * self.cols + cx
+            })
+            .collect();
+        for &c in &cells {
+            self.starts[c + 1] += 1;
+        }
+        for c in 0..self.cols * self.rows {
+            self.starts[c + 1] += self.starts[c];
+        }
+        let mut fill = self.starts.clone();
+        self.entries.resize(positions.len(), 0);
+        for (i, &c) in cells.iter().enumerate() {
+            self.entries[fill[c]] = i;
+            fill[c] += 1;
+        }
+    }
+
+    /// Candidate neighbours of a point: everything in its 3×3 block of cells
+    /// (including the particle itself, if it is one). Callers check the distance.
+    pub fn neighbours(&self, point: (f64, f64)) -> impl Iterator<Item = usize> + '_ {
+        let (cx, cy) = self.cell_of(point);
+        let xs = wrapped_span(cx, self.cols);
+        let ys = wrapped_span(cy, self.rows);
+        ys.into_iter()
+            .flatten()
+            .flat_map(move |y| xs.into_iter().flatten().map(move |x| y * self.cols + x))
+            .flat_map(move |c| self.entries[self.starts[c]..self.starts[c + 1]].iter().copied())
+    }
+}
+
+/// The cells at c-1, c, c+1 with wrap-around, without repeats on tiny grids
+fn wrapped_span(c: usize, n: usize) -> [Option<usize>; 3] {
+    match n {
+        1 => [Some(0), None, None],
+        2 => [Some(0), Some(1), None],
+        _ => [Some((c + n - 1) % n), Some(c), Some((c + 1) % n)],
+    }
+}
+
+/// Parse pair keys like "attraction:2:0" into (2, 0) for the given prefix.
+pub fn parse_pair_key(key: &str, prefix: &str) -> Option<(usize, usize)> {
+    let mut parts = key.strip_prefix(prefix)?.strip_prefix(':')?.split(':');
+    let i = parts.next()?.parse().ok()?;
+    let j = parts.next()?.parse().ok()?;
+    parts.next().is_none().then_some((i, j))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spatial_hash_finds_what_brute_force_finds() {
+        let mut set = ParticleSet::scatter(10.0, 7.0, 300, 1, 0.0, &mut SimRng::new(4));
+        // Pairs that only meet across the periodic edges
+        for p in [(0.05, 3.0), (9.95, 3.0), (5.0, 0.02), (5.0, 6.99), (0.0, 0.0), (9.99, 6.99)] {
+            set.push(p, (0.0, 0.0), 0);
+        }
+        // Down to one or two cells a side, where the 3×3 block wraps onto itself
+        for radius in [0.7, 1.3, 4.0, 20.0] {
+            let mut hash = SpatialHash::new(set.width, set.height, radius);
+            hash.rebuild(&set.positions);
+            for i in 0..set.len() {
+                let within = |j: usize| {
+                    let (dx, dy) = set.delta(i, j);
+                    j != i && dx.hypot(dy) <= radius
+                };
+                let mut candidates: Vec<usize> = hash.neighbours(set.positions[i]).collect();
+                candidates.sort_unstable();
+                let unique = candidates.len();
+                candidates.dedup();
+                assert_eq!(unique, candidates.len(), "radius {}: particle {} has repeated candidates", radius, i);
+                let found: Vec<usize> = candidates.into_iter().filter(|&j| within(j)).collect();
+                let expected: Vec<usize> = (0..set.len()).filter(|&j| within(j)).collect();
+                assert_eq!(found, expected, "radius {}: neighbours of particle {} at {:?}", radius, i, set.positions[i]);
+            }
+        }
+    }
+
+    #[test]
+    fn pair_keys_parse() {
+        assert_eq!(parse_pair_key("attraction:2:0", "attraction"), Some((2, 0)));
+        assert_eq!(parse_pair_key("attraction:2", "attraction"), None);
+        assert_eq!(parse_pair_key("attraction:2:0:1", "attraction"), None);
+        assert_eq!(parse_pair_key("affinity:2:0", "attraction"), None);
+    }
+}
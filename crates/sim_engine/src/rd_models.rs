@@ -0,0 +1,492 @@
+//! Reaction-diffusion models for `ReactionDiffusion<M>`: FitzHugh–Nagumo,
+//! Brusselator, Schnakenberg, Oregonator (BZ) and Turing's 1952 morphogens.
+//!
+//! Diffusion rates are in this crate's stencil units (the 9-point kernel is
+//! roughly 0.3·∇² for h = 1), so they are not directly comparable to papers.
+
+use crate::reaction_diffusion::{RdModel, RdPreset, ReactionDiffusion};
+use crate::rng::SimRng;
+use crate::{ParamSpec, Simulation};
+
+pub type FitzHughNagumoSim = ReactionDiffusion<FitzHughNagumo>;
+pub type BrusselatorSim = ReactionDiffusion<Brusselator>;
+pub type SchnakenbergSim = ReactionDiffusion<Schnakenberg>;
+pub type OregonatorSim = ReactionDiffusion<Oregonator>;
+pub type TuringSim = ReactionDiffusion<TuringMorphogens>;
+
+/// Type-erased entry for menus: everything a UI needs without naming `M`.
+#[derive(Clone, Copy)]
+pub struct RdModelInfo {
+    /// Short id used in URLs / session types, e.g. "brusselator"
+    pub key: &'static str,
+    pub name: &'static str,
+    pub schema: &'static [ParamSpec],
+    pub presets: &'static [RdPreset],
+    build: fn(usize, usize, &str) -> Box<dyn Simulation>,
+}
+
+impl RdModelInfo {
+    /// Grid of the given size started from `preset` (unknown presets keep the defaults).
+    pub fn build(&self, width: usize, height: usize, preset: &str) -> Box<dyn Simulation> {
+        (self.build)(width, height, preset)
+    }
+}
+
+const fn info<M: RdModel + 'static>(key: &'static str) -> RdModelInfo {
+    RdModelInfo {
+        key,
+        name: M::NAME,
+        schema: M::SCHEMA,
+        presets: M::PRESETS,
+        build: |w, h, preset| Box::new(ReactionDiffusion::<M>::init(w, h).with_preset(preset)),
+    }
+}
+
+pub const MODELS: &[RdModelInfo] = &[
+    info::<FitzHughNagumo>("fitzhugh-nagumo"),
+    info::<Brusselator>("brusselator"),
+    info::<Schnakenberg>("schnakenberg"),
+    info::<Oregonator>("oregonator"),
+    info::<TuringMorphogens>("turing"),
+];
+
+pub fn find(key: &str) -> Option<&'static RdModelInfo> {
+    MODELS.iter().find(|m| m.key.eq_ignore_ascii_case(key.trim()))
+}
+
+// Saves repeating the field list in every model
+macro_rules! param_accessors {
+    ($($name:literal => $field:ident),* $(,)?) => {
+        fn get(&self, name: &str) -> Option<f64> {
+            match name {
+                $($name => Some(self.$field),)*
+                _ => None,
+            }
+        }
+
+        fn set(&mut self, name: &str, value: f64) -> bool {
+            match name {
+                $($name => self.$field = value,)*
+                _ => return false,
+            }
+            true
+        }
+    };
+}
+
+const fn spec(name: &'static str, description: &'static str, default: f64, min: f64, max: f64) -> ParamSpec {
+    ParamSpec::float(name, description, default, min, max)
+}
+
+// String params `ReactionDiffusion` handles for every model. The preset has no
+// default: each model's defaults are its first preset.
+const PRESET: ParamSpec = ParamSpec::text("preset", "Named preset (restarts the grid)", "");
+const BOUNDARY: ParamSpec =
+    ParamSpec::text("boundary", "\"periodic\", \"neumann\", \"dirichlet\" or \"dirichlet:U:V\"", "periodic");
+
+/// Squash an unbounded value into 0..1 around `center` (for display)
+fn squash(x: f64, center: f64, scale: f64) -> f64 {
+    0.5 + 0.5 * ((x - center) / scale).tanh()
+}
+
+// --- FitzHugh–Nagumo ---
+// du/dt = Du∇²u + u - u³ - v
+// dv/dt = Dv∇²v + ε(u - a1·v - a0)
+
+#[derive(Clone, Debug)]
+pub struct FitzHughNagumo {
+    pub du: f64,
+    pub dv: f64,
+    pub epsilon: f64,
+    pub a0: f64,
+    pub a1: f64,
+}
+
+const FHN_SCHEMA: &[ParamSpec] = &[
+    spec("du", "Diffusion of the activator u", 1.0, 0.0, 5.0),
+    spec("dv", "Diffusion of the inhibitor v", 20.0, 0.0, 60.0),
+    spec("epsilon", "Time-scale ratio of v to u", 0.05, 0.001, 1.0),
+    spec("a0", "Inhibitor offset (breaks ±u symmetry)", -0.1, -1.0, 1.0),
+    spec("a1", "Inhibitor self-damping", 2.0, 0.0, 5.0),
+    PRESET,
+    BOUNDARY,
+];
+
+const FHN_PRESETS: &[RdPreset] = &[
+    RdPreset { name: "spots", description: "Turing spots", values: &[("a0", -0.1), ("dv", 20.0)] },
+    RdPreset { name: "labyrinth", description: "Symmetric stripes / maze", values: &[("a0", 0.0), ("dv", 20.0)] },
+    RdPreset { name: "holes", description: "Inverted spots", values: &[("a0", 0.1), ("dv", 20.0)] },
+    RdPreset {
+        name: "waves",
+        description: "Excitable travelling waves (equal diffusion, slow inhibitor)",
+        values: &[("du", 1.0), ("dv", 0.0), ("epsilon", 0.02), ("a1", 0.5), ("a0", 0.0)],
+    },
+];
+
+impl Default for FitzHughNagumo {
+    fn default() -> Self {
+        Self { du: 1.0, dv: 20.0, epsilon: 0.05, a0: -0.1, a1: 2.0 }
+    }
+}
+
+impl RdModel for FitzHughNagumo {
+    const NAME: &'static str = "FitzHugh-Nagumo";
+
+    const SCHEMA: &'static [ParamSpec] = FHN_SCHEMA;
+    const PRESETS: &'static [RdPreset] = FHN_PRESETS;
+
+    param_accessors!("du" => du, "dv" => dv, "epsilon" => epsilon, "a0" => a0, "a1" => a1);
+
+    fn diffusion(&self) -> (f64, f64) { (self.du, self.dv) }
+
+    fn reaction(&self, u: f64, v: f64) -> (f64, f64) {
+        (u - u * u * u - v, self.epsilon * (u - self.a1 * v - self.a0))
+    }
+
+    fn rest_state(&self) -> (f64, f64) {
+        // v = (u - a0)/a1 on the v-nullcline; Newton on u - u³ - v = 0 from u = 0
+        let mut u: f64 = 0.0;
+        for _ in 0..20 {
+            let g = u - u * u * u - (u - self.a0) / self.a1;
+            let dg = 1.0 - 3.0 * u * u - 1.0 / self.a1;
+            if dg.abs() < 1e-12 { break; }
+            u -= g / dg;
+        }
+        (u, (u - self.a0) / self.a1)
+    }
+
+    fn time_step(&self) -> (f64, usize) {
+        // Explicit Euler: keep D·dt comfortably under ~1.2 for this stencil
+        let d_max = self.du.max(self.dv).max(1e-9);
+        ((0.8 / d_max).min(0.1), 10)
+    }
+
+    fn display(&self, u: f64, _v: f64) -> f64 { squash(u, 0.0, 0.8) }
+
+    fn seed(&self, _width: usize, _height: usize, u: &mut [f64], v: &mut [f64], rng: &mut SimRng) {
+        // Rest state is ~0, so multiplicative noise would do nothing: add it instead
+        let (u0, v0) = self.rest_state();
+        for (cu, cv) in u.iter_mut().zip(v.iter_mut()) {
+            *cu = u0 + 0.1 * (rng.next_f64() - 0.5);
+            *cv = v0 + 0.1 * (rng.next_f64() - 0.5);
+        }
+    }
+}
+
+// --- Brusselator ---
+// du/dt = Du∇²u + a - (b + 1)u + u²v
+// dv/dt = Dv∇²v + bu - u²v
+// Turing when b > (1 + a·sqrt(Du/Dv))², Hopf (bulk oscillation) when b > 1 + a².
+
+#[derive(Clone, Debug)]
+pub struct Brusselator {
+    pub a: f64,
+    pub b: f64,
+    pub du: f64,
+    pub dv: f64,
+}
+
+const BRUSSELATOR_SCHEMA: &[ParamSpec] = &[
+    spec("a", "Feed of u", 3.0, 0.1, 10.0),
+    spec("b", "Conversion rate u -> v (bifurcation parameter)", 5.0, 0.0, 20.0),
+    spec("du", "Diffusion of u", 1.0, 0.0, 10.0),
+    spec("dv", "Diffusion of v", 8.0, 0.0, 30.0),
+    PRESET,
+    BOUNDARY,
+];
+
+const BRUSSELATOR_PRESETS: &[RdPreset] = &[
+    RdPreset { name: "spots", description: "Hexagonal Turing spots just past onset", values: &[("b", 5.0)] },
+    RdPreset { name: "stripes", description: "Turing stripes further from onset", values: &[("b", 7.0)] },
+    RdPreset {
+        name: "oscillating",
+        description: "Past the Hopf point with equal diffusion: phase waves",
+        values: &[("a", 1.0), ("b", 2.5), ("du", 1.0), ("dv", 1.0)],
+    },
+];
+
+impl Default for Brusselator {
+    fn default() -> Self {
+        Self { a: 3.0, b: 5.0, du: 1.0, dv: 8.0 }
+    }
+}
+
+impl RdModel for Brusselator {
+    const NAME: &'static str = "Brusselator";
+
+    const SCHEMA: &'static [ParamSpec] = BRUSSELATOR_SCHEMA;
+    const PRESETS: &'static [RdPreset] = BRUSSELATOR_PRESETS;
+
+    param_accessors!("a" => a, "b" => b, "du" => du, "dv" => dv);
+
+    fn diffusion(&self) -> (f64, f64) { (self.du, self.dv) }
+
+    fn reaction(&self, u: f64, v: f64) -> (f64, f64) {
+        let uuv = u * u * v;
+        (self.a - (self.b + 1.0) * u + uuv, self.b * u - uuv)
+    }
+
+    fn rest_state(&self) -> (f64, f64) {
+        (self.a, self.b / self.a.max(1e-9))
+    }
+
+    fn time_step(&self) -> (f64, usize) {
+        let d_max = self.du.max(self.dv).max(1e-9);
+        ((0.8 / d_max).min(0.02), 20)
+    }
+
+    fn display(&self, u: f64, _v: f64) -> f64 { squash(u, self.a, self.a * 0.5) }
+}
+
+// --- Schnakenberg ---
+// du/dt = Du∇²u + a - u + u²v
+// dv/dt = Dv∇²v + b - u²v
+
+#[derive(Clone, Debug)]
+pub struct Schnakenberg {
+    pub a: f64,
+    pub b: f64,
+    pub du: f64,
+    pub dv: f64,
+}
+
+const SCHNAKENBERG_SCHEMA: &[ParamSpec] = &[
+    spec("a", "Production of u", 0.1, 0.0, 2.0),
+    spec("b", "Production of v", 0.9, 0.0, 2.0),
+    spec("du", "Diffusion of u", 1.0, 0.0, 5.0),
+    spec("dv", "Diffusion of v", 40.0, 0.0, 80.0),
+    PRESET,
+    BOUNDARY,
+];
+
+const SCHNAKENBERG_PRESETS: &[RdPreset] = &[
+    RdPreset { name: "spots", description: "Classic Schnakenberg spots", values: &[] },
+    RdPreset { name: "stripes", description: "Stripes / labyrinth", values: &[("a", 0.2), ("b", 1.3)] },
+    RdPreset { name: "fine spots", description: "Smaller, denser spots", values: &[("dv", 20.0)] },
+];
+
+impl Default for Schnakenberg {
+    fn default() -> Self {
+        Self { a: 0.1, b: 0.9, du: 1.0, dv: 40.0 }
+    }
+}
+
+impl RdModel for Schnakenberg {
+    const NAME: &'static str = "Schnakenberg";
+
+    const SCHEMA: &'static [ParamSpec] = SCHNAKENBERG_SCHEMA;
+    const PRESETS: &'static [RdPreset] = SCHNAKENBERG_PRESETS;
+
+    param_accessors!("a" => a, "b" => b, "du" => du, "dv" => dv);
+
+    fn diffusion(&self) -> (f64, f64) { (self.du, self.dv) }
+
+    fn reaction(&self, u: f64, v: f64) -> (f64, f64) {
+        let uuv = u * u * v;
+        (self.a - u + uuv, self.b - uuv)
+    }
+
+    fn rest_state(&self) -> (f64, f64) {
+        let s = (self.a + self.b).max(1e-9);
+        (s, self.b / (s * s))
+    }
+
+    fn time_step(&self) -> (f64, usize) {
+        let d_max = self.du.max(self.dv).max(1e-9);
+        ((0.8 / d_max).min(0.05), 20)
+    }
+
+    fn display(&self, u: f64, _v: f64) -> f64 {
+        let (u0, _) = self.rest_state();
+        squash(u, u0, u0 * 0.5)
+    }
+}
+
+// --- Oregonator (Belousov–Zhabotinsky, two-variable Tyson–Fife form) ---
+// du/dt = Du∇²u + (u - u² - f·v·(u - q)/(u + q)) / ε
+// dv/dt = Dv∇²v + u - v
+
+#[derive(Clone, Debug)]
+pub struct Oregonator {
+    pub epsilon: f64,
+    pub f: f64,
+    pub q: f64,
+    pub du: f64,
+    pub dv: f64,
+}
+
+const OREGONATOR_SCHEMA: &[ParamSpec] = &[
+    spec("epsilon", "Time-scale separation (smaller = sharper fronts)", 0.05, 0.005, 0.5),
+    spec("f", "Stoichiometric factor", 1.4, 0.5, 3.0),
+    spec("q", "Scaling constant", 0.002, 0.0001, 0.05),
+    spec("du", "Diffusion of the activator (HBrO2)", 1.0, 0.0, 5.0),
+    spec("dv", "Diffusion of the catalyst", 0.0, 0.0, 5.0),
+    PRESET,
+    BOUNDARY,
+];
+
+const OREGONATOR_PRESETS: &[RdPreset] = &[
+    RdPreset { name: "spirals", description: "Rotating spiral waves from broken fronts", values: &[] },
+    RdPreset { name: "tight spirals", description: "Faster, tighter spirals", values: &[("epsilon", 0.02), ("f", 1.0)] },
+    RdPreset { name: "broad waves", description: "Weakly excitable: broad, slow fronts", values: &[("f", 2.6)] },
+];
+
+impl Default for Oregonator {
+    fn default() -> Self {
+        Self { epsilon: 0.05, f: 1.4, q: 0.002, du: 1.0, dv: 0.0 }
+    }
+}
+
+impl RdModel for Oregonator {
+    const NAME: &'static str = "Oregonator (BZ)";
+
+    const SCHEMA: &'static [ParamSpec] = OREGONATOR_SCHEMA;
+    const PRESETS: &'static [RdPreset] = OREGONATOR_PRESETS;
+
+    param_accessors!("epsilon" => epsilon, "f" => f, "q" => q, "du" => du, "dv" => dv);
+
+    fn diffusion(&self) -> (f64, f64) { (self.du, self.dv) }
+
+    fn reaction(&self, u: f64, v: f64) -> (f64, f64) {
+        let u = u.max(0.0);
+        let du = (u - u * u - self.f * v * (u - self.q) / (u + self.q)) / self.epsilon;
+        (du, u - v)
+    }
+
+    fn rest_state(&self) -> (f64, f64) {
+        // u = v on the v-nullcline: (1 - u)(u + q) = f(u - q), positive root
+        let p = self.q + self.f - 1.0;
+        let u = 0.5 * (-p + (p * p + 4.0 * self.q * (1.0 + self.f)).sqrt());
+        (u, u)
+    }
+
+    fn time_step(&self) -> (f64, usize) {
+        // Stiff reaction: dt well below epsilon
+        ((self.epsilon * 0.05).min(0.8 / self.du.max(self.dv).max(1e-9)), 40)
+    }
+
+    fn display(&self, u: f64, _v: f64) -> f64 { (u * 1.5).sqrt() }
+
+    fn seed(&self, width: usize, height: usize, u: &mut [f64], v: &mut [f64], _rng: &mut SimRng) {
+        // Broken wave fronts: an excited half-line with a refractory strip behind it
+        // curls into a spiral pair at its free end. Two of them, opposite orientation.
+        let (u0, v0) = self.rest_state();
+        u.fill(u0);
+        v.fill(v0);
+        for (row, dir) in [(height / 3, 1isize), (2 * height / 3, -1)] {
+            for x in width / 4..3 * width / 4 {
+                for t in 0..3isize {
+                    let excited = (row as isize + t * dir).rem_euclid(height as isize) as usize;
+                    let refractory = (row as isize - (t + 1) * dir).rem_euclid(height as isize) as usize;
+                    u[excited * width + x] = 0.8;
+                    v[refractory * width + x] = 0.5;
+                }
+            }
+        }
+    }
+}
+
+// --- Turing's two morphogens (Turing 1952) ---
+// da/dt = Da∇²a + s(16 - ab)
+// db/dt = Db∇²b + s(ab - b - β)
+
+#[derive(Clone, Debug)]
+pub struct TuringMorphogens {
+    pub s: f64,
+    pub beta: f64,
+    pub da: f64,
+    pub db: f64,
+}
+
+const TURING_SCHEMA: &[ParamSpec] = &[
+    spec("s", "Reaction rate", 0.0625, 0.001, 0.5),
+    spec("beta", "Decay of b (rest state b = 16 - beta)", 12.0, 0.0, 15.9),
+    spec("da", "Diffusion of a", 1.0, 0.0, 5.0),
+    spec("db", "Diffusion of b", 0.0625, 0.0, 5.0),
+    PRESET,
+    BOUNDARY,
+];
+
+const TURING_PRESETS: &[RdPreset] = &[
+    RdPreset { name: "spots", description: "Turing's dappled pattern", values: &[] },
+    RdPreset { name: "large spots", description: "Slower reaction, larger wavelength", values: &[("s", 0.02)] },
+    RdPreset { name: "stripes", description: "Closer to the rest state's symmetric point", values: &[("beta", 12.5)] },
+];
+
+impl Default for TuringMorphogens {
+    fn default() -> Self {
+        Self { s: 0.0625, beta: 12.0, da: 1.0, db: 0.0625 }
+    }
+}
+
+impl RdModel for TuringMorphogens {
+    const NAME: &'static str = "Turing morphogens";
+
+    const SCHEMA: &'static [ParamSpec] = TURING_SCHEMA;
+    const PRESETS: &'static [RdPreset] = TURING_PRESETS;
+
+    param_accessors!("s" => s, "beta" => beta, "da" => da, "db" => db);
+
+    fn diffusion(&self) -> (f64, f64) { (self.da, self.db) }
+
+    fn reaction(&self, a: f64, b: f64) -> (f64, f64) {
+        // Concentrations can't go negative; Turing's model blows up if they do
+        let (a, b) = (a.max(0.0), b.max(0.0));
+        (self.s * (16.0 - a * b), self.s * (a * b - b - self.beta))
+    }
+
+    fn rest_state(&self) -> (f64, f64) {
+        let b = (16.0 - self.beta).max(1e-9);
+        (16.0 / b, b)
+    }
+
+    fn time_step(&self) -> (f64, usize) {
+        let d_max = self.da.max(self.db).max(1e-9);
+        ((0.8 / d_max).min(0.5), 20)
+    }
+
+    fn display(&self, a: f64, _b: f64) -> f64 {
+        let (a0, _) = self.rest_state();
+        squash(a, a0, a0 * 0.5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every preset, as given and with its diffusion rates at the schema maximum
+    /// (where the dt cap has to do the work), stays finite for 50 frames.
+    fn stays_finite<M: RdModel + 'static>() {
+        for preset in M::PRESETS {
+            for max_diffusion in [false, true] {
+                let mut sim = ReactionDiffusion::<M>::init(24, 24).with_preset(preset.name);
+                if max_diffusion {
+                    for spec in M::SCHEMA.iter().filter(|spec| ["du", "dv", "da", "db"].contains(&spec.name)) {
+                        sim.model_mut().set(spec.name, spec.max);
+                    }
+                }
+                for _ in 0..50 {
+                    sim.step();
+                }
+                let (u, v) = sim.fields();
+                assert!(
+                    u.iter().chain(v).all(|x| x.is_finite()),
+                    "{} '{}' (max diffusion: {}) blew up",
+                    M::NAME,
+                    preset.name,
+                    max_diffusion
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn dt_cap_keeps_every_model_finite() {
+        stays_finite::<FitzHughNagumo>();
+        stays_finite::<Brusselator>();
+        stays_finite::<Schnakenberg>();
+        stays_finite::<Oregonator>();
+        stays_finite::<TuringMorphogens>();
+    }
+}
@@ -0,0 +1,277 @@
+//! Generic two-species reaction-diffusion on the same grid/stencil as Gray-Scott.
+//!
+//! A model only supplies its reaction terms, diffusion rates and parameter
+//! schema (`RdModel`); `ReactionDiffusion<M>` does the grid, boundaries,
+//! integration, rendering and the agent interface. Concrete models live in
+//! `rd_models`.
+
+use super::{Action, ActionSpace, Experimentable, Observation, ParamSpec, ParamValue, SimState, Simulation};
+use crate::observation::FIELD_SIDE;
+use crate::rng::SimRng;
+use crate::stencil::{laplacian, Boundary, RowTriple};
+
+/// A named parameter set for a model.
+#[derive(Clone, Copy, Debug)]
+pub struct RdPreset {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// (parameter name, value); anything not listed keeps the model default
+    pub values: &'static [(&'static str, f64)],
+}
+
+pub trait RdModel: Clone + Default {
+    const NAME: &'static str;
+
+    /// Every tunable parameter, including the diffusion rates.
+    const SCHEMA: &'static [ParamSpec];
+    const PRESETS: &'static [RdPreset];
+
+    fn get(&self, name: &str) -> Option<f64>;
+    /// Returns false for unknown names.
+    fn set(&mut self, name: &str, value: f64) -> bool;
+
+    /// (Du, Dv)
+    fn diffusion(&self) -> (f64, f64);
+    /// Reaction part of (du/dt, dv/dt)
+    fn reaction(&self, u: f64, v: f64) -> (f64, f64);
+    /// Homogeneous steady state the grid rests at (also used for Dirichlet edges).
+    fn rest_state(&self) -> (f64, f64);
+    /// (dt, integration steps per `Simulation::step`)
+    fn time_step(&self) -> (f64, usize);
+    /// Map a cell to 0..1 for rendering
+    fn display(&self, u: f64, v: f64) -> f64;
+
+    /// Initial condition. Default: rest state plus small noise, which is all a
+    /// Turing instability needs. Excitable models override this.
+    fn seed(&self, width: usize, height: usize, u: &mut [f64], v: &mut [f64], rng: &mut SimRng) {
+        let _ = (width, height);
+        let (u0, v0) = self.rest_state();
+        for (cu, cv) in u.iter_mut().zip(v.iter_mut()) {
+            *cu = u0 * (1.0 + 0.05 * (rng.next_f64() - 0.5));
+            *cv = v0 * (1.0 + 0.05 * (rng.next_f64() - 0.5));
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ReactionDiffusion<M: RdModel> {
+    width: usize,
+    height: usize,
+    u: Vec<f64>,
+    v: Vec<f64>,
+    next_u: Vec<f64>,
+    next_v: Vec<f64>,
+    model: M,
+    boundary: Boundary,
+    seed: u64,
+}
+
+impl<M: RdModel> ReactionDiffusion<M> {
+    pub fn init(width: usize, height: usize) -> Self {
+        // The stencil needs a real interior
+        let (width, height) = (width.max(3), height.max(3));
+        let size = width * height;
+        let mut sim = Self {
+            width,
+            height,
+            u: vec![0.0; size],
+            v: vec![0.0; size],
+            next_u: vec![0.0; size],
+            next_v: vec![0.0; size],
+            model: M::default(),
+            boundary: Boundary::Periodic,
+            seed: 0,
+        };
+        sim.reset();
+        sim
+    }
+
+    /// Start from a named preset (unknown names leave the defaults).
+    pub fn with_preset(mut self, name: &str) -> Self {
+        self.apply_preset(name);
+        self
+    }
+
+    pub fn with_boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Load a preset's parameters and restart. Returns false if there is no such preset.
+    pub fn apply_preset(&mut self, name: &str) -> bool {
+        let Some(preset) = M::PRESETS.iter().find(|p| p.name.eq_ignore_ascii_case(name)) else {
+            return false;
+        };
+        self.model = M::default();
+        for &(param, value) in preset.values {
+            self.model.set(param, value);
+        }
+        self.reset();
+        true
+    }
+
+    /// Re-seed from the model's initial condition.
+    pub fn reset(&mut self) {
+        let mut rng = SimRng::new(self.seed);
+        self.model.seed(self.width, self.height, &mut self.u, &mut self.v, &mut rng);
+    }
+
+    pub fn model(&self) -> &M {
+        &self.model
+    }
+
+    pub fn model_mut(&mut self) -> &mut M {
+        &mut self.model
+    }
+
+    pub fn fields(&self) -> (&[f64], &[f64]) {
+        (&self.u, &self.v)
+    }
+
+    fn euler_step(&mut self, dt: f64) {
+        let (w, h) = (self.width, self.height);
+        let (du, dv) = self.model.diffusion();
+        let boundary = match self.boundary {
+            // Dirichlet edges hold the model's own rest state
+            Boundary::Dirichlet { .. } => {
+                let (u, v) = self.model.rest_state();
+                Boundary::Dirichlet { u, v }
+            }
+            other => other,
+        };
+        let (ghost_u, ghost_v) = match boundary {
+            Boundary::Dirichlet { u, v } => (u, v),
+            _ => (0.0, 0.0),
+        };
+        let (ghost_row_u, ghost_row_v) = (vec![ghost_u; w], vec![ghost_v; w]);
+
+        let rows = self.next_u.chunks_mut(w).zip(self.next_v.chunks_mut(w));
+        for (y, (out_u, out_v)) in rows.enumerate() {
+            let ur = RowTriple::at(&self.u, &ghost_row_u, y, w, h, boundary);
+            let vr = RowTriple::at(&self.v, &ghost_row_v, y, w, h, boundary);
+            for x in 0..w {
+                let (nu, nv) = if x == 0 || x == w - 1 {
+                    (ur.edge_neighbourhood(x, boundary, ghost_u), vr.edge_neighbourhood(x, boundary, ghost_v))
+                } else {
+                    (ur.neighbourhood(x), vr.neighbourhood(x))
+                };
+                let (ru, rv) = self.model.reaction(nu[4], nv[4]);
+                out_u[x] = nu[4] + dt * (du * laplacian(nu) + ru);
+                out_v[x] = nv[4] + dt * (dv * laplacian(nv) + rv);
+            }
+        }
+
+        std::mem::swap(&mut self.u, &mut self.next_u);
+        std::mem::swap(&mut self.v, &mut self.next_v);
+    }
+
+    fn display_values(&self) -> Vec<f64> {
+        self.u
+            .iter()
+            .zip(&self.v)
+            .map(|(&u, &v)| {
+                let d = self.model.display(u, v);
+                if d.is_finite() { d.clamp(0.0, 1.0) } else { 0.0 }
+            })
+            .collect()
+    }
+}
+
+impl<M: RdModel> Simulation for ReactionDiffusion<M> {
+    fn new() -> Self {
+        Self::init(128, 128)
+    }
+
+    fn step(&mut self) {
+        let (dt, substeps) = self.model.time_step();
+        for _ in 0..substeps {
+            self.euler_step(dt);
+        }
+    }
+
+    fn get_state(&self) -> SimState {
+        SimState::FloatGrid {
+            width: self.width as u32,
+            height: self.height as u32,
+            values: self.display_values(),
+        }
+    }
+
+    fn set_param(&mut self, key: &str, value: ParamValue) {
+        match (key, value) {
+            ("preset", ParamValue::String(name)) => {
+                self.apply_preset(&name);
+            }
+            ("boundary", ParamValue::String(name)) => {
+                if let Some(boundary) = Boundary::from_name(&name) {
+                    self.boundary = boundary;
+                }
+            }
+            ("seed", ParamValue::Int(seed)) => {
+                self.seed = seed as u64;
+                self.reset();
+            }
+            (name, ParamValue::Float(v)) => {
+                self.model.set(name, v);
+            }
+            _ => {}
+        }
+    }
+
+    fn as_experimentable(&mut self) -> Option<&mut dyn Experimentable> {
+        Some(self)
+    }
+}
+
+impl<M: RdModel> Experimentable for ReactionDiffusion<M> {
+    fn apply_action(&mut self, action: Action) {
+        match action {
+            // Kick U in a small disc; delta picks the spot like Gray-Scott's injection
+            Action::Perturb { which, delta } => {
+                let cx = (self.width as f64 * (delta.abs() % 1.0)) as isize;
+                let cy = (self.height as f64 * ((delta * 10.0).abs() % 1.0)) as isize;
+                let field = if which == 0 { &mut self.u } else { &mut self.v };
+                let r = 4;
+                for dy in -r..=r {
+                    for dx in -r..=r {
+                        if dx * dx + dy * dy > r * r { continue; }
+                        let x = (cx + dx).rem_euclid(self.width as isize) as usize;
+                        let y = (cy + dy).rem_euclid(self.height as isize) as usize;
+                        field[y * self.width + x] *= 1.5;
+                    }
+                }
+            }
+            Action::SetParam { name, value } => {
+                self.model.set(&name, value);
+            }
+            _ => {}
+        }
+    }
+
+    fn action_space(&self) -> ActionSpace {
+        // Kick U (0) or V (1); delta picks the spot
+        ActionSpace::new().with_perturb("species", 2, -1.0, 1.0).with_params(M::SCHEMA)
+    }
+
+    fn observe(&self) -> Observation {
+        // Mean and contrast of the rendered field, mean U, and the field itself
+        let values = self.display_values();
+        let (mean, contrast) = mean_contrast(&values);
+        let mean_u = self.u.iter().sum::<f64>() / values.len().max(1) as f64;
+        Observation::named(&[("mean", mean), ("contrast", contrast), ("mean_u", mean_u)])
+            .with("field", Observation::field(self.width, self.height, &values, FIELD_SIDE))
+    }
+
+    fn reward(&self) -> f64 {
+        // Reward pattern: a flat field (contrast 0) is boring
+        mean_contrast(&self.display_values()).1 * 20.0
+    }
+}
+
+/// Mean and standard deviation
+fn mean_contrast(values: &[f64]) -> (f64, f64) {
+    let n = values.len().max(1) as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let var = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+    (mean, var.sqrt())
+}
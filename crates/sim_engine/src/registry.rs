@@ -0,0 +1,376 @@
+//! Every simulation the app and the headless runner can start, by key.
+//!
+//! One entry per simulation: how to build it, what to call it, which brain
+//! drives it by default and which parameters `set_param` takes. Adding a
+//! simulation means adding a line to `SIMULATIONS`; the tests check every
+//! schema default against what `build` starts from.
+
+use crate::boids::Boids;
+use crate::composite::Composite;
+use crate::elementary::ElementaryCA;
+use crate::gol::GameOfLife;
+use crate::gray_scott::GrayScott;
+use crate::lenia::Lenia;
+use crate::lennard_jones::LennardJones;
+use crate::nbody::NBody;
+use crate::ode::ODESim;
+use crate::particle_life::ParticleLife;
+use crate::rd_models::{Brusselator, FitzHughNagumo, Oregonator, Schnakenberg, TuringMorphogens};
+use crate::reaction_diffusion::{RdModel, ReactionDiffusion};
+use crate::smoothlife::SmoothLife;
+use crate::soup::SoupSearch;
+use crate::{gray_scott_presets, lenia, ParamSpec, Simulation};
+
+#[derive(Clone, Copy)]
+pub struct SimInfo {
+    /// Short id for menus, session types and the command line, e.g. "gray-scott"
+    pub key: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// Menu grouping
+    pub category: &'static str,
+    /// Name of the `inference_engine::BrainType` to pair it with ("qlearner", "dqn", "actor-critic", "gardener", "evolution", "mock")
+    pub default_brain: &'static str,
+    pub schema: &'static [ParamSpec],
+    build: fn() -> Box<dyn Simulation>,
+}
+
+impl SimInfo {
+    pub fn build(&self) -> Box<dyn Simulation> {
+        (self.build)()
+    }
+
+    pub fn param(&self, name: &str) -> Option<&'static ParamSpec> {
+        self.schema.iter().find(|spec| spec.name == name)
+    }
+}
+
+pub fn find(key: &str) -> Option<&'static SimInfo> {
+    let key = key.trim();
+    SIMULATIONS.iter().find(|info| info.key.eq_ignore_ascii_case(key))
+}
+
+pub fn build(key: &str) -> Option<Box<dyn Simulation>> {
+    find(key).map(SimInfo::build)
+}
+
+/// Categories in menu order (first appearance in `SIMULATIONS`).
+pub fn categories() -> Vec<&'static str> {
+    let mut seen = Vec::new();
+    for info in SIMULATIONS {
+        if !seen.contains(&info.category) {
+            seen.push(info.category);
+        }
+    }
+    seen
+}
+
+pub fn in_category(category: &str) -> impl Iterator<Item = &'static SimInfo> + '_ {
+    SIMULATIONS.iter().filter(move |info| info.category == category)
+}
+
+const fn rd<M: RdModel + 'static>(key: &'static str, description: &'static str) -> SimInfo {
+    SimInfo {
+        key,
+        name: M::NAME,
+        description,
+        category: "Reaction-diffusion",
+        default_brain: "gardener",
+        schema: M::SCHEMA,
+        build: || {
+            let preset = M::PRESETS.first().map_or("", |p| p.name);
+            Box::new(ReactionDiffusion::<M>::init(128, 128).with_preset(preset))
+        },
+    }
+}
+
+pub const SIMULATIONS: &[SimInfo] = &[
+    SimInfo {
+        key: "gol",
+        name: "Game of Life",
+        description: "Conway's Life on an unbounded HashLife plane",
+        category: "Classic",
+        default_brain: "gardener",
+        schema: GOL_SCHEMA,
+        build: || Box::new(GameOfLife::new()),
+    },
+    SimInfo {
+        key: "lorenz",
+        name: "Lorenz",
+        description: "Lorenz attractor (or Rössler, via the \"system\" param), RK4",
+        category: "Classic",
+        default_brain: "qlearner",
+        schema: LORENZ_SCHEMA,
+        build: || Box::new(ODESim::new()),
+    },
+    SimInfo {
+        key: "eca",
+        name: "1D CA",
+        description: "Elementary and totalistic 1D cellular automata as a spacetime diagram",
+        category: "Classic",
+        default_brain: "qlearner",
+        schema: ECA_SCHEMA,
+        build: || Box::new(ElementaryCA::new()),
+    },
+    SimInfo {
+        key: "soup",
+        name: "Soup Search",
+        description: "Random Life soups run to stabilisation, with a census of the ash",
+        category: "Classic",
+        default_brain: "mock",
+        schema: SOUP_SCHEMA,
+        build: || Box::new(SoupSearch::new()),
+    },
+    SimInfo {
+        key: "gray-scott",
+        name: "Gray-Scott",
+        description: "Gray-Scott reaction-diffusion with Pearson's presets",
+        category: "Reaction-diffusion",
+        default_brain: "gardener",
+        schema: GRAY_SCOTT_SCHEMA,
+        build: || Box::new(GrayScott::init(100, 100)),
+    },
+    SimInfo {
+        key: "gs-map",
+        name: "F/k Map",
+        description: "The whole Gray-Scott (f, k) plane at once: k across, f down",
+        category: "Reaction-diffusion",
+        default_brain: "mock",
+        schema: GS_MAP_SCHEMA,
+        build: || Box::new(GrayScott::parameter_map(128, 128, (0.0, 0.08), (0.03, 0.07))),
+    },
+    rd::<FitzHughNagumo>("fitzhugh-nagumo", "Excitable medium: spots, labyrinths and spirals"),
+    rd::<Brusselator>("brusselator", "Autocatalytic oscillator: Turing spots, stripes and waves"),
+    rd::<Schnakenberg>("schnakenberg", "Substrate-depletion Turing patterns"),
+    rd::<Oregonator>("oregonator", "Belousov-Zhabotinsky spirals and target waves"),
+    rd::<TuringMorphogens>("turing", "Turing's original two-morphogen model"),
+    SimInfo {
+        key: "lenia",
+        name: "Lenia",
+        description: "Continuous cellular automaton with smooth kernels (Orbium and friends)",
+        category: "Continuous CA",
+        default_brain: "evolution",
+        schema: LENIA_SCHEMA,
+        build: || Box::new(Lenia::init(128, 128)),
+    },
+    SimInfo {
+        key: "smoothlife",
+        name: "SmoothLife",
+        description: "Rafler's continuous Game of Life",
+        category: "Continuous CA",
+        default_brain: "evolution",
+        schema: SMOOTHLIFE_SCHEMA,
+        build: || Box::new(SmoothLife::init(128, 128)),
+    },
+    SimInfo {
+        key: "boids",
+        name: "Boids",
+        description: "Reynolds flocking with a per-species affinity matrix",
+        category: "Particles",
+        default_brain: "qlearner",
+        schema: BOIDS_SCHEMA,
+        build: || Box::new(Boids::new()),
+    },
+    SimInfo {
+        key: "particle-life",
+        name: "Particle Life",
+        description: "Species with an asymmetric attraction matrix",
+        category: "Particles",
+        default_brain: "qlearner",
+        schema: PARTICLE_LIFE_SCHEMA,
+        build: || Box::new(ParticleLife::new()),
+    },
+    SimInfo {
+        key: "lennard-jones",
+        name: "Lennard-Jones",
+        description: "2D Lennard-Jones fluid with a Berendsen thermostat",
+        category: "Particles",
+        default_brain: "qlearner",
+        schema: LENNARD_JONES_SCHEMA,
+        build: || Box::new(LennardJones::new()),
+    },
+    SimInfo {
+        key: "nbody",
+        name: "N-Body",
+        description: "Gravitational N-body with a Barnes-Hut tree and symplectic integrators",
+        category: "Particles",
+        default_brain: "qlearner",
+        schema: NBODY_SCHEMA,
+        build: || Box::new(NBody::new()),
+    },
+    SimInfo {
+        key: "lorenz-gray-scott",
+        name: "Lorenz → Gray-Scott",
+        description: "Lorenz x driving the Gray-Scott feed rate (add edges with the \"coupling\" param)",
+        category: "Composite",
+        default_brain: "gardener",
+        schema: COMPOSITE_SCHEMA,
+        build: || Box::new(Composite::new()),
+    },
+];
+
+// --- Schemas ---
+
+pub const GOL_SCHEMA: &[ParamSpec] = &[
+    ParamSpec::text("inject_pattern", "Drop a library pattern (\"glider\") at the origin", ""),
+];
+
+pub const LORENZ_SCHEMA: &[ParamSpec] = &[
+    ParamSpec::choice("system", "Which attractor (restarts it)", &["lorenz", "rossler"], "lorenz"),
+    ParamSpec::float("sigma", "Lorenz σ (Prandtl number)", 10.0, 0.0, 50.0),
+    ParamSpec::float("rho", "Lorenz ρ (Rayleigh number)", 28.0, 0.0, 100.0),
+    ParamSpec::float("beta", "Lorenz β", 8.0 / 3.0, 0.0, 10.0),
+    ParamSpec::float("a", "Rössler a", 0.2, 0.0, 1.0),
+    ParamSpec::float("b", "Rössler b", 0.2, 0.0, 2.0),
+    ParamSpec::float("c", "Rössler c", 5.7, 0.0, 20.0),
+    ParamSpec::float("dt", "RK4 time step", 0.01, 1e-4, 0.05),
+];
+
+pub const ECA_SCHEMA: &[ParamSpec] = &[
+    ParamSpec::int("rule", "Elementary rule number", 110, 0, 255),
+    ParamSpec::int("code", "Totalistic rule code", 0, 0, 1 << 20),
+    ParamSpec::int("colors", "Colours for totalistic rules", 3, 2, 4),
+    ParamSpec::float("density", "Initial fraction of non-zero cells", 0.5, 0.0, 1.0),
+    ParamSpec::int("seed", "Initial row seed", 0, 0, i64::MAX),
+    ParamSpec::int("width", "Cells per row", 250, 3, 4096),
+    ParamSpec::int("height", "Generations shown", 192, 2, 4096),
+    ParamSpec::choice("init", "First row: one live cell or random at `density`", &["single", "random"], "random"),
+];
+
+pub const SOUP_SCHEMA: &[ParamSpec] = &[
+    ParamSpec::int("seed", "Master seed for the soup sequence", 0, 0, i64::MAX),
+    ParamSpec::int("soup_size", "Side of each random soup", 16, 1, 256),
+    ParamSpec::float("density", "Fraction of soup cells alive", 0.5, 0.0, 1.0),
+    ParamSpec::int("max_generations", "Give up on soups that haven't settled by then", 4000, 1, 1_000_000),
+];
+
+pub const GRAY_SCOTT_SCHEMA: &[ParamSpec] = &[
+    // First, since it overwrites f and k
+    ParamSpec::text("preset", "Catalogue preset by name or Pearson class", gray_scott_presets::DEFAULT_PRESET),
+    ParamSpec::float("f", "Feed rate", 0.055, 0.0, 0.1),
+    ParamSpec::float("k", "Kill rate", 0.062, 0.0, 0.1),
+    ParamSpec::text("f_field", "Per-cell feed rate: \"0.05\", \"x:LO:HI\", \"y:LO:HI\" or an image", ""),
+    ParamSpec::text("k_field", "Per-cell kill rate: \"0.06\", \"x:LO:HI\", \"y:LO:HI\" or an image", ""),
+    ParamSpec::text("boundary", "\"periodic\", \"neumann\", \"dirichlet\" or \"dirichlet:U:V\"", "periodic"),
+    ParamSpec::text("init", "Initial condition: center, spots, noise, stripes or an image mask", ""),
+    ParamSpec::int("init_seed", "Seed for random initial conditions", 0, 0, i64::MAX),
+];
+
+/// Gray-Scott's schema minus the uniform rates and presets, which would flatten
+/// the map; the ramps default to the plane `GrayScott::parameter_map` draws.
+pub const GS_MAP_SCHEMA: &[ParamSpec] = &[
+    ParamSpec::text("f_field", "Feed rate down the map: \"y:LO:HI\", \"x:LO:HI\", \"0.05\" or an image", "y:0.0:0.08"),
+    ParamSpec::text("k_field", "Kill rate across the map: \"x:LO:HI\", \"y:LO:HI\", \"0.06\" or an image", "x:0.03:0.07"),
+    ParamSpec::text("boundary", "\"periodic\", \"neumann\", \"dirichlet\" or \"dirichlet:U:V\"", "periodic"),
+    ParamSpec::text("init", "Initial condition: center, spots, noise, stripes or an image mask", ""),
+    ParamSpec::int("init_seed", "Seed for random initial conditions", 0, 0, i64::MAX),
+];
+
+pub const LENIA_SCHEMA: &[ParamSpec] = &[
+    ParamSpec::text("creature", "Library creature (sets the rule and the seed pattern)", lenia::DEFAULT_CREATURE),
+    ParamSpec::float("dt", "Time step", 0.1, 0.001, 1.0),
+    ParamSpec::float("mu", "Growth centre", 0.15, 0.0, 1.0),
+    ParamSpec::float("sigma", "Growth width", 0.015, 1e-4, 0.5),
+    ParamSpec::float("radius", "Kernel radius in cells", 13.0, 1.0, 64.0),
+    ParamSpec::int("seed", "Seed for soups", 0, 0, i64::MAX),
+];
+
+pub const SMOOTHLIFE_SCHEMA: &[ParamSpec] = &[
+    ParamSpec::float("radius", "Outer radius", 12.0, 3.0, 48.0),
+    ParamSpec::float("b1", "Birth interval start", 0.278, 0.0, 1.0),
+    ParamSpec::float("b2", "Birth interval end", 0.365, 0.0, 1.0),
+    ParamSpec::float("d1", "Survival interval start", 0.267, 0.0, 1.0),
+    ParamSpec::float("d2", "Survival interval end", 0.445, 0.0, 1.0),
+    ParamSpec::float("alpha_n", "Sigmoid width for the neighbourhood", 0.028, 1e-4, 0.5),
+    ParamSpec::float("alpha_m", "Sigmoid width for the cell", 0.147, 1e-4, 0.5),
+    ParamSpec::float("dt", "Continuous time step (0 = discrete)", 0.0, 0.0, 1.0),
+    ParamSpec::int("seed", "Seed for the initial discs", 0, 0, i64::MAX),
+];
+
+pub const BOIDS_SCHEMA: &[ParamSpec] = &[
+    ParamSpec::int("count", "Number of boids", 400, 1, 20_000),
+    ParamSpec::int("species", "Number of species", 1, 1, 16),
+    ParamSpec::float("view_radius", "How far a boid sees", 20.0, 2.0, 85.0),
+    ParamSpec::float("separation_radius", "Steer away from anything closer", 6.0, 0.0, 40.0),
+    ParamSpec::float("separation", "Separation weight", 0.05, 0.0, 1.0),
+    ParamSpec::float("alignment", "Alignment weight", 0.05, 0.0, 1.0),
+    ParamSpec::float("cohesion", "Cohesion weight", 0.005, 0.0, 0.1),
+    ParamSpec::float("min_speed", "Minimum speed", 1.0, 0.0, 10.0),
+    ParamSpec::float("max_speed", "Maximum speed", 3.0, 0.0, 10.0),
+    ParamSpec::int("seed", "Initial scatter seed", 0, 0, i64::MAX),
+];
+
+pub const PARTICLE_LIFE_SCHEMA: &[ParamSpec] = &[
+    ParamSpec::int("count", "Number of particles", 800, 1, 20_000),
+    ParamSpec::int("species", "Number of species", 6, 1, 16),
+    ParamSpec::float("r_max", "Interaction radius", 24.0, 2.0, 85.0),
+    ParamSpec::float("beta", "Repulsive core, as a fraction of r_max", 0.3, 0.05, 0.95),
+    ParamSpec::float("force", "Force scale", 10.0, 0.0, 50.0),
+    ParamSpec::float("friction", "Velocity half-life", 0.04, 1e-3, 1.0),
+    ParamSpec::float("dt", "Time step", 0.02, 1e-4, 0.1),
+    ParamSpec::int("seed", "Seed for particles and the attraction matrix", 0, 0, i64::MAX),
+];
+
+pub const LENNARD_JONES_SCHEMA: &[ParamSpec] = &[
+    ParamSpec::int("count", "Number of particles", 400, 1, 20_000),
+    ParamSpec::int("species", "Number of species", 2, 1, 16),
+    ParamSpec::float("density", "Particles per unit area", 0.35, 0.01, 0.9),
+    ParamSpec::float("temperature", "Thermostat target", 0.45, 0.0, 5.0),
+    ParamSpec::float("thermostat_tau", "Berendsen coupling time (0 = constant energy)", 0.5, 0.0, 10.0),
+    ParamSpec::float("dt", "Verlet time step", 0.005, 1e-4, 0.01),
+    ParamSpec::int("seed", "Initial velocity seed", 0, 0, i64::MAX),
+];
+
+pub const NBODY_SCHEMA: &[ParamSpec] = &[
+    // First, since it resets dt and softening
+    ParamSpec::choice("preset", "Initial configuration", &["figure8", "binary", "solar", "collapse", "disk"], "disk"),
+    ParamSpec::choice("integrator", "Symplectic or not", &["leapfrog", "yoshida4", "rk4"], "leapfrog"),
+    ParamSpec::int("count", "Bodies in the many-body presets", 800, 2, 100_000),
+    ParamSpec::float("dt", "Integrator time step", 0.002, 1e-5, 0.05),
+    ParamSpec::float("theta", "Barnes-Hut opening angle (0 = direct sum)", 0.5, 0.0, 1.5),
+    ParamSpec::float("softening", "Plummer softening length", 0.02, 0.0, 1.0),
+    ParamSpec::int("seed", "Initial condition seed", 0, 0, i64::MAX),
+];
+
+pub const COMPOSITE_SCHEMA: &[ParamSpec] = &[
+    ParamSpec::text("coupling", "Add an edge, e.g. \"lorenz.x -> gs.f * 0.0006 + 0.045\"", ""),
+    ParamSpec::text("display", "Which child is drawn", ""),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimState;
+
+    /// Parameters that switch mode just by being set (any ECA code or colour
+    /// count selects a totalistic rule), so no value of theirs is neutral
+    const MODE_SWITCHES: &[(&str, &str)] = &[("eca", "code"), ("eca", "colors")];
+
+    fn run(mut sim: Box<dyn Simulation>) -> SimState {
+        for _ in 0..3 {
+            sim.step();
+        }
+        sim.get_state()
+    }
+
+    /// Setting any parameter to its schema default must leave a fresh build
+    /// unchanged. Simulations that don't build deterministically are skipped.
+    #[test]
+    fn schema_defaults_match_build() {
+        for info in SIMULATIONS {
+            let fresh = run(info.build());
+            if run(info.build()) != fresh {
+                continue;
+            }
+            for spec in info.schema {
+                let Some(value) = spec.default_value() else { continue };
+                if MODE_SWITCHES.contains(&(info.key, spec.name)) {
+                    continue;
+                }
+                let mut sim = info.build();
+                sim.set_param(spec.name, value);
+                assert!(run(sim) == fresh, "{}: schema default of {} differs from the build", info.key, spec.name);
+            }
+        }
+    }
+}
@@ -0,0 +1,584 @@
+//! Reward functions that can stand in for a simulation's built-in
+//! `Experimentable::reward`.
+//!
+//! Wrap any simulation in `Rewarded` and pick the reward through the param
+//! API: `set_param("reward", String("0.7*coverage:0.2 + 0.3*entropy"))`,
+//! then tune it with `set_param("reward.coverage.width", Float(0.05))`.
+//!
+//! Spec grammar: terms joined by `+`, each `[weight*]kind[:args]`, where args
+//! are `;`-separated `name=value` pairs or one bare value for the kind's main
+//! knob (`coverage:0.2` is `coverage:target=0.2`).
+
+use crate::composite::field_values;
+use crate::ode::ODESim;
+use crate::{Action, ActionSpace, Experimentable, Observation, ObservationSpace, ParamValue, SimState, Simulation};
+
+/// What a reward function gets to look at after each step.
+pub struct RewardContext<'a> {
+    pub state: &'a SimState,
+    pub observation: &'a Observation,
+    /// The simulation's own `reward()`
+    pub native: f64,
+}
+
+pub trait RewardFunction {
+    /// Short id used in specs and `reward.<kind>.<knob>` names
+    fn kind(&self) -> &'static str;
+
+    /// Called once per simulation step, in order, so stateful rewards can
+    /// compare against the previous step.
+    fn evaluate(&mut self, ctx: &RewardContext) -> f64;
+
+    /// Set a knob by name; false if there is no such knob.
+    fn set(&mut self, _name: &str, _value: f64) -> bool {
+        false
+    }
+
+    /// A spec `parse` turns back into an equivalent function.
+    fn spec(&self) -> String;
+}
+
+/// Built-ins for menus: (spec, label)
+pub const PRESETS: &[(&str, &str)] = &[
+    ("native", "Built-in"),
+    ("coverage:0.2", "Coverage 20%"),
+    ("entropy", "Entropy"),
+    ("complexity", "Spatial complexity"),
+    ("stability", "Population stability"),
+    ("0.5*coverage:0.2 + 0.5*complexity", "Coverage + complexity"),
+];
+
+// --- Built-ins ---
+
+/// The simulation's own reward, unchanged.
+pub struct Native;
+
+impl RewardFunction for Native {
+    fn kind(&self) -> &'static str {
+        "native"
+    }
+
+    fn evaluate(&mut self, ctx: &RewardContext) -> f64 {
+        ctx.native
+    }
+
+    fn spec(&self) -> String {
+        "native".into()
+    }
+}
+
+/// Gaussian around a target mean intensity (fraction of live cells for
+/// Life-likes). Gray-Scott's built-in is `coverage:target=0.2;width=0.1`, ×10.
+pub struct Coverage {
+    pub target: f64,
+    pub width: f64,
+}
+
+impl RewardFunction for Coverage {
+    fn kind(&self) -> &'static str {
+        "coverage"
+    }
+
+    fn evaluate(&mut self, ctx: &RewardContext) -> f64 {
+        let values = field_values(ctx.state);
+        if values.is_empty() {
+            return 0.0;
+        }
+        let coverage = values.iter().map(|v| v.clamp(0.0, 1.0)).sum::<f64>() / values.len() as f64;
+        (-((coverage - self.target) / self.width.max(1e-9)).powi(2)).exp()
+    }
+
+    fn set(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "target" => self.target = value,
+            "width" => self.width = value,
+            _ => return false,
+        }
+        true
+    }
+
+    fn spec(&self) -> String {
+        format!("coverage:target={};width={}", self.target, self.width)
+    }
+}
+
+/// Shannon entropy of the value histogram, scaled to [0, 1]. Grid values are
+/// binned over [0, 1]; points and particles over their own min..max.
+pub struct Entropy {
+    pub bins: usize,
+}
+
+impl RewardFunction for Entropy {
+    fn kind(&self) -> &'static str {
+        "entropy"
+    }
+
+    fn evaluate(&mut self, ctx: &RewardContext) -> f64 {
+        let values = field_values(ctx.state);
+        let bins = self.bins.max(2);
+        if values.is_empty() {
+            return 0.0;
+        }
+        let (lo, hi) = match ctx.state {
+            SimState::FloatGrid { .. } | SimState::Grid { .. } => (0.0, 1.0),
+            _ => values.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v))),
+        };
+        let span = (hi - lo).max(1e-12);
+        let mut counts = vec![0usize; bins];
+        for v in &values {
+            let bin = (((v - lo) / span) * bins as f64).floor().clamp(0.0, (bins - 1) as f64) as usize;
+            counts[bin] += 1;
+        }
+        let n = values.len() as f64;
+        let h: f64 = counts.iter().filter(|&&c| c > 0).map(|&c| c as f64 / n).map(|p| -p * p.ln()).sum();
+        h / (bins as f64).ln()
+    }
+
+    fn set(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "bins" => self.bins = value.round().max(2.0) as usize,
+            _ => return false,
+        }
+        true
+    }
+
+    fn spec(&self) -> String {
+        format!("entropy:bins={}", self.bins)
+    }
+}
+
+/// Edge density `e` (mean |difference| between neighbouring cells) scored as
+/// `4e(1 - e)`: zero for flat fields and for salt-and-pepper noise, highest
+/// for patterns with structure. Grids only.
+pub struct Complexity;
+
+impl RewardFunction for Complexity {
+    fn kind(&self) -> &'static str {
+        "complexity"
+    }
+
+    fn evaluate(&mut self, ctx: &RewardContext) -> f64 {
+        let (w, h) = match ctx.state {
+            SimState::FloatGrid { width, height, .. } | SimState::Grid { width, height, .. } => {
+                (*width as usize, *height as usize)
+            }
+            _ => return 0.0,
+        };
+        let values = field_values(ctx.state);
+        if w < 2 || h < 2 || values.len() != w * h {
+            return 0.0;
+        }
+        let mut total = 0.0;
+        for y in 0..h {
+            for x in 0..w {
+                let v = values[y * w + x];
+                if x + 1 < w { total += (v - values[y * w + x + 1]).abs().min(1.0); }
+                if y + 1 < h { total += (v - values[(y + 1) * w + x]).abs().min(1.0); }
+            }
+        }
+        let edges = ((w - 1) * h + w * (h - 1)) as f64;
+        let e = total / edges;
+        4.0 * e * (1.0 - e)
+    }
+
+    fn spec(&self) -> String {
+        "complexity".into()
+    }
+}
+
+/// `exp(-distance / scale)` from named observation features to target
+/// values, e.g. `target:x=0;y=0;z=25` for the Lorenz fixed point.
+pub struct Target {
+    pub features: Vec<(String, f64)>,
+    pub scale: f64,
+}
+
+impl RewardFunction for Target {
+    fn kind(&self) -> &'static str {
+        "target"
+    }
+
+    fn evaluate(&mut self, ctx: &RewardContext) -> f64 {
+        let mut sum = 0.0;
+        for (name, target) in &self.features {
+            // A feature the world doesn't report counts as infinitely far off
+            let Some(value) = ctx.observation.get(name).and_then(Observation::as_scalar) else { return 0.0 };
+            sum += (value - target).powi(2);
+        }
+        (-sum.sqrt() / self.scale.max(1e-9)).exp()
+    }
+
+    /// `scale`, or any other name as a feature target
+    fn set(&mut self, name: &str, value: f64) -> bool {
+        if name == "scale" {
+            self.scale = value;
+        } else if let Some(entry) = self.features.iter_mut().find(|(n, _)| n == name) {
+            entry.1 = value;
+        } else {
+            self.features.push((name.into(), value));
+        }
+        true
+    }
+
+    fn spec(&self) -> String {
+        let mut args: Vec<String> = self.features.iter().map(|(n, v)| format!("{}={}", n, v)).collect();
+        args.push(format!("scale={}", self.scale));
+        format!("target:{}", args.join(";"))
+    }
+}
+
+/// `exp(-relative change / tolerance)` of the population (sum of the field,
+/// or of particle speeds) between consecutive steps.
+pub struct Stability {
+    pub tolerance: f64,
+    previous: Option<f64>,
+}
+
+impl Stability {
+    pub fn new(tolerance: f64) -> Self {
+        Self { tolerance, previous: None }
+    }
+}
+
+impl RewardFunction for Stability {
+    fn kind(&self) -> &'static str {
+        "stability"
+    }
+
+    fn evaluate(&mut self, ctx: &RewardContext) -> f64 {
+        let population: f64 = field_values(ctx.state).iter().sum();
+        let change = match self.previous.replace(population) {
+            Some(previous) => (population - previous).abs() / previous.abs().max(1e-9),
+            None => 0.0,
+        };
+        if population == 0.0 {
+            return 0.0; // Dead is very stable, but not what anyone wants
+        }
+        (-change / self.tolerance.max(1e-12)).exp()
+    }
+
+    fn set(&mut self, name: &str, value: f64) -> bool {
+        match name {
+            "tolerance" => self.tolerance = value,
+            _ => return false,
+        }
+        true
+    }
+
+    fn spec(&self) -> String {
+        format!("stability:tolerance={}", self.tolerance)
+    }
+}
+
+/// Weighted sum of other reward functions.
+pub struct Weighted {
+    pub terms: Vec<(f64, Box<dyn RewardFunction>)>,
+}
+
+impl RewardFunction for Weighted {
+    fn kind(&self) -> &'static str {
+        "weighted"
+    }
+
+    fn evaluate(&mut self, ctx: &RewardContext) -> f64 {
+        self.terms.iter_mut().map(|(weight, f)| *weight * f.evaluate(ctx)).sum()
+    }
+
+    /// `<kind>.<knob>` or `<kind>.weight`, for the first term of that kind
+    fn set(&mut self, name: &str, value: f64) -> bool {
+        let Some((kind, knob)) = name.split_once('.') else { return false };
+        let Some((weight, f)) = self.terms.iter_mut().find(|(_, f)| f.kind() == kind) else { return false };
+        if knob == "weight" {
+            *weight = value;
+            true
+        } else {
+            f.set(knob, value)
+        }
+    }
+
+    fn spec(&self) -> String {
+        let terms: Vec<String> = self.terms.iter().map(|(w, f)| format!("{}*{}", w, f.spec())).collect();
+        terms.join(" + ")
+    }
+}
+
+// --- Parsing ---
+
+/// Build a reward function from a spec (see the module docs).
+pub fn parse(spec: &str) -> Result<Box<dyn RewardFunction>, String> {
+    let mut terms = Vec::new();
+    for term in split_terms(spec).into_iter().map(str::trim) {
+        let (weight, body) = match term.split_once('*') {
+            Some((w, body)) => (w.trim().parse::<f64>().map_err(|_| format!("bad weight in '{}'", term))?, body.trim()),
+            None => (1.0, term),
+        };
+        terms.push((weight, parse_term(body)?));
+    }
+    if terms.len() == 1 && terms[0].0 == 1.0 {
+        return Ok(terms.pop().unwrap().1);
+    }
+    Ok(Box::new(Weighted { terms }))
+}
+
+/// `spec` cut at each `+` that joins terms, leaving exponents like `1e+3` whole
+fn split_terms(spec: &str) -> Vec<&str> {
+    let bytes = spec.as_bytes();
+    let exponent = |i: usize| {
+        i >= 2 && matches!(bytes[i - 1], b'e' | b'E') && (bytes[i - 2].is_ascii_digit() || bytes[i - 2] == b'.')
+    };
+    let mut terms = Vec::new();
+    let mut start = 0;
+    for (i, _) in spec.match_indices('+') {
+        if !exponent(i) {
+            terms.push(&spec[start..i]);
+            start = i + 1;
+        }
+    }
+    terms.push(&spec[start..]);
+    terms
+}
+
+fn parse_term(term: &str) -> Result<Box<dyn RewardFunction>, String> {
+    let (kind, args) = term.split_once(':').unwrap_or((term, ""));
+    let kind = kind.trim();
+    let (mut f, main): (Box<dyn RewardFunction>, Option<&str>) = match kind {
+        "native" => (Box::new(Native), None),
+        "coverage" => (Box::new(Coverage { target: 0.2, width: 0.1 }), Some("target")),
+        "entropy" => (Box::new(Entropy { bins: 16 }), Some("bins")),
+        "complexity" => (Box::new(Complexity), None),
+        "target" => (Box::new(Target { features: Vec::new(), scale: 1.0 }), None),
+        "stability" => (Box::new(Stability::new(0.01)), Some("tolerance")),
+        "" => return Err("empty reward term".into()),
+        other => {
+            return Err(format!(
+                "unknown reward '{}' (native, coverage, entropy, complexity, target, stability)",
+                other
+            ))
+        }
+    };
+    for arg in args.split(';').map(str::trim).filter(|a| !a.is_empty()) {
+        let (name, raw) = match arg.split_once('=') {
+            Some((name, raw)) => (name.trim(), raw.trim()),
+            None => (main.ok_or_else(|| format!("{} takes name=value arguments", kind))?, arg),
+        };
+        let value: f64 = raw.parse().map_err(|_| format!("bad value for {}.{}: '{}'", kind, name, raw))?;
+        if !f.set(name, value) {
+            return Err(format!("{} has no parameter '{}'", kind, name));
+        }
+    }
+    Ok(f)
+}
+
+// --- Wrapper ---
+
+/// Any simulation with a swappable reward. Everything but `reward` passes
+/// straight through; `reward` and `reward.<knob>` params configure it.
+pub struct Rewarded {
+    sim: Box<dyn Simulation>,
+    reward: Box<dyn RewardFunction>,
+    /// Last error from a bad `reward` spec, for UIs to show
+    pub error: Option<String>,
+    // Cached after every change, since Experimentable's getters take &self
+    observation: Observation,
+    observation_space: ObservationSpace,
+    actions: ActionSpace,
+    native: f64,
+    value: f64,
+    experimentable: bool,
+}
+
+impl Rewarded {
+    pub fn wrap(sim: Box<dyn Simulation>) -> Self {
+        let mut rewarded = Self {
+            sim,
+            reward: Box::new(Native),
+            error: None,
+            observation: Observation::None,
+            observation_space: ObservationSpace::None,
+            actions: ActionSpace::new(),
+            native: 0.0,
+            value: 0.0,
+            experimentable: false,
+        };
+        rewarded.refresh();
+        rewarded.evaluate();
+        rewarded
+    }
+
+    pub fn with_reward(mut self, reward: Box<dyn RewardFunction>) -> Self {
+        self.set_reward(reward);
+        self
+    }
+
+    pub fn set_reward(&mut self, reward: Box<dyn RewardFunction>) {
+        self.reward = reward;
+        self.evaluate();
+    }
+
+    /// Parse and install `spec`; on error the current reward stays.
+    pub fn set_reward_spec(&mut self, spec: &str) -> Result<(), String> {
+        self.set_reward(parse(spec)?);
+        Ok(())
+    }
+
+    pub fn reward_spec(&self) -> String {
+        self.reward.spec()
+    }
+
+    pub fn inner(&self) -> &dyn Simulation {
+        self.sim.as_ref()
+    }
+
+    pub fn inner_mut(&mut self) -> &mut dyn Simulation {
+        self.sim.as_mut()
+    }
+
+    fn refresh(&mut self) {
+        match self.sim.as_experimentable() {
+            Some(exp) => {
+                self.observation = exp.observe();
+                self.observation_space = exp.observation_space();
+                self.actions = exp.action_space();
+                self.native = exp.reward();
+                self.experimentable = true;
+            }
+            None => self.experimentable = false,
+        }
+    }
+
+    fn evaluate(&mut self) {
+        if self.reward.kind() == "native" {
+            return; // No need to copy the world out
+        }
+        let state = self.sim.get_state();
+        let ctx = RewardContext { state: &state, observation: &self.observation, native: self.native };
+        self.value = self.reward.evaluate(&ctx);
+    }
+}
+
+impl Simulation for Rewarded {
+    /// Lorenz, with its own reward (the wrapper is normally built with `wrap`).
+    fn new() -> Self {
+        Self::wrap(Box::new(ODESim::new()))
+    }
+
+    fn step(&mut self) {
+        self.sim.step();
+        self.refresh();
+        self.evaluate();
+    }
+
+    fn get_state(&self) -> SimState {
+        self.sim.get_state()
+    }
+
+    fn set_param(&mut self, key: &str, value: ParamValue) {
+        match (key, value) {
+            ("reward", ParamValue::String(spec)) => {
+                self.error = self.set_reward_spec(&spec).err();
+            }
+            (knob, value) if knob.starts_with("reward.") => {
+                let knob = &knob["reward.".len()..];
+                let value = match value {
+                    ParamValue::Float(v) => Some(v),
+                    ParamValue::Int(v) => Some(v as f64),
+                    ParamValue::Bool(v) => Some(if v { 1.0 } else { 0.0 }),
+                    _ => None,
+                };
+                // A single-term reward also answers to "<kind>.<knob>"
+                let knob = knob.strip_prefix(self.reward.kind()).and_then(|k| k.strip_prefix('.')).unwrap_or(knob);
+                self.error = match value {
+                    Some(v) if self.reward.set(knob, v) => None,
+                    _ => Some(format!("{} has no parameter '{}'", self.reward.kind(), knob)),
+                };
+            }
+            (key, value) => {
+                self.sim.set_param(key, value);
+                self.refresh();
+            }
+        }
+    }
+
+    fn as_experimentable(&mut self) -> Option<&mut dyn Experimentable> {
+        if self.experimentable { Some(self) } else { None }
+    }
+}
+
+impl Experimentable for Rewarded {
+    fn apply_action(&mut self, action: Action) {
+        if let Some(exp) = self.sim.as_experimentable() {
+            exp.apply_action(action);
+        }
+        self.refresh();
+    }
+
+    fn observe(&self) -> Observation {
+        self.observation.clone()
+    }
+
+    /// The chosen reward as of the last step (the native one stays live)
+    fn reward(&self) -> f64 {
+        if self.reward.kind() == "native" { self.native } else { self.value }
+    }
+
+    fn action_space(&self) -> ActionSpace {
+        self.actions.clone()
+    }
+
+    fn observation_space(&self) -> ObservationSpace {
+        self.observation_space.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_valid_specs() {
+        let cases = [
+            ("native", "native"),
+            ("coverage:0.2", "coverage:target=0.2;width=0.1"),
+            ("coverage:target=0.3;width=0.05", "coverage:target=0.3;width=0.05"),
+            (" entropy:bins=8 ", "entropy:bins=8"),
+            ("stability:0.02", "stability:tolerance=0.02"),
+            ("target:x=1;scale=2", "target:x=1;scale=2"),
+            ("0.5*coverage:0.2 + 0.5*complexity", "0.5*coverage:target=0.2;width=0.1 + 0.5*complexity"),
+            ("1e+3*complexity", "1000*complexity"),
+            ("2*native+1E+2*entropy", "2*native + 100*entropy:bins=16"),
+            ("coverage:target=1.5e-1", "coverage:target=0.15;width=0.1"),
+        ];
+        for (spec, expected) in cases {
+            match parse(spec) {
+                Ok(f) => assert_eq!(f.spec(), expected, "spec '{}'", spec),
+                Err(err) => panic!("'{}' should parse: {}", spec, err),
+            }
+        }
+    }
+
+    #[test]
+    fn parse_rejects_invalid_specs() {
+        let cases = [
+            "",
+            "bogus",
+            "coverage + ",
+            "x*coverage",
+            "coverage:width",
+            "coverage:target=high",
+            "complexity:0.5",
+            "entropy:size=4",
+            "1e+*coverage",
+        ];
+        for spec in cases {
+            assert!(parse(spec).is_err(), "'{}' should not parse", spec);
+        }
+    }
+
+    #[test]
+    fn parsed_specs_round_trip() {
+        for (spec, _) in PRESETS {
+            let f = parse(spec).unwrap();
+            assert_eq!(parse(&f.spec()).unwrap().spec(), f.spec(), "preset '{}'", spec);
+        }
+    }
+}
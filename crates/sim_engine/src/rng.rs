@@ -0,0 +1,64 @@
+//! Small seedable PRNG (SplitMix64) so experiments are reproducible in WASM and natively
+
+/// Deterministic random source. Same seed -> same sequence on every platform.
+#[derive(Clone, Debug)]
+pub struct SimRng {
+    state: u64,
+}
+
+impl SimRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Seed from the OS / browser entropy source (via `getrandom`).
+    pub fn from_entropy() -> Self {
+        let mut buf = [0u8; 8];
+        // If entropy is unavailable we still want *something* to run.
+        if getrandom::getrandom(&mut buf).is_err() {
+            return Self::new(0x9E37_79B9_7F4A_7C15);
+        }
+        Self::new(u64::from_le_bytes(buf))
+    }
+
+    /// Derive an independent stream, e.g. one per soup / episode.
+    pub fn derive(seed: u64, index: u64) -> Self {
+        let mut mixer = Self::new(seed ^ index.wrapping_mul(0xD1B5_4A32_D192_ED03));
+        Self::new(mixer.next_u64())
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Uniform in [lo, hi)
+    pub fn range_f64(&mut self, lo: f64, hi: f64) -> f64 {
+        lo + (hi - lo) * self.next_f64()
+    }
+
+    /// Uniform in [0, n). Returns 0 when n == 0.
+    pub fn below(&mut self, n: usize) -> usize {
+        if n == 0 { return 0; }
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.next_f64() < p
+    }
+
+    /// Standard normal sample (Box-Muller)
+    pub fn gaussian(&mut self) -> f64 {
+        let u1 = self.next_f64().max(f64::MIN_POSITIVE);
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+    }
+}
@@ -0,0 +1,224 @@
+//! SmoothLife (Rafler, 2011): Game of Life on a continuum. A cell is an inner
+//! disc (filling m) with an annulus around it (filling n); the Life rule becomes
+//! smooth birth/death intervals over (n, m).
+
+use super::{param_value, Action, ActionSpace, Experimentable, Observation, ParamValue, SimState, Simulation};
+use crate::registry::SMOOTHLIFE_SCHEMA;
+use crate::observation::FIELD_SIDE;
+use crate::convolution::{Convolution, Fft2d};
+use crate::rng::SimRng;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SmoothLifeRule {
+    /// Outer radius; the inner disc is a third of it
+    pub outer_radius: f64,
+    /// Birth interval on n
+    pub b1: f64,
+    pub b2: f64,
+    /// Survival interval on n
+    pub d1: f64,
+    pub d2: f64,
+    /// Sigmoid widths for n and m
+    pub alpha_n: f64,
+    pub alpha_m: f64,
+    /// None = discrete time (A ← s(n, m)); Some(dt) = A += dt·(2s - 1)
+    pub dt: Option<f64>,
+}
+
+impl Default for SmoothLifeRule {
+    // Rafler's gliders
+    fn default() -> Self {
+        Self {
+            outer_radius: 12.0,
+            b1: 0.278,
+            b2: 0.365,
+            d1: 0.267,
+            d2: 0.445,
+            alpha_n: 0.028,
+            alpha_m: 0.147,
+            dt: None,
+        }
+    }
+}
+
+impl SmoothLifeRule {
+    /// Transition function s(n, m)
+    pub fn transition(&self, n: f64, m: f64) -> f64 {
+        let sigma1 = |x: f64, a: f64, alpha: f64| 1.0 / (1.0 + (-(x - a) * 4.0 / alpha).exp());
+        let alive = sigma1(m, 0.5, self.alpha_m);
+        let lo = self.b1 * (1.0 - alive) + self.d1 * alive;
+        let hi = self.b2 * (1.0 - alive) + self.d2 * alive;
+        sigma1(n, lo, self.alpha_n) * (1.0 - sigma1(n, hi, self.alpha_n))
+    }
+}
+
+/// Disc / annulus membership with a one-cell anti-aliased edge
+fn smooth_disc(r: f64, radius: f64) -> f64 {
+    (radius + 0.5 - r).clamp(0.0, 1.0)
+}
+
+#[derive(Clone)]
+pub struct SmoothLife {
+    width: usize,
+    height: usize,
+    cells: Vec<f64>,
+    rule: SmoothLifeRule,
+    fft: Fft2d,
+    inner: Convolution,
+    outer: Convolution,
+    seed: u64,
+}
+
+impl SmoothLife {
+    pub fn init(width: usize, height: usize) -> Self {
+        let fft = Fft2d::new(width, height);
+        let rule = SmoothLifeRule::default();
+        let (inner, outer) = Self::kernels(&rule, &fft);
+        let mut sim = Self { width, height, cells: vec![0.0; width * height], rule, fft, inner, outer, seed: 0 };
+        sim.reset();
+        sim
+    }
+
+    pub fn with_rule(mut self, rule: SmoothLifeRule) -> Self {
+        self.set_rule(rule);
+        self
+    }
+
+    pub fn set_rule(&mut self, rule: SmoothLifeRule) {
+        let rebuild = rule.outer_radius != self.rule.outer_radius;
+        self.rule = rule;
+        if rebuild {
+            (self.inner, self.outer) = Self::kernels(&self.rule, &self.fft);
+        }
+    }
+
+    pub fn rule(&self) -> &SmoothLifeRule {
+        &self.rule
+    }
+
+    fn kernels(rule: &SmoothLifeRule, fft: &Fft2d) -> (Convolution, Convolution) {
+        let ra = rule.outer_radius.max(3.0);
+        let ri = ra / 3.0;
+        let reach = ra.ceil() as usize + 1;
+        let inner = Convolution::new(reach, fft, |dx, dy| smooth_disc(dx.hypot(dy), ri));
+        let outer = Convolution::new(reach, fft, |dx, dy| {
+            let r = dx.hypot(dy);
+            smooth_disc(r, ra) * (1.0 - smooth_disc(r, ri))
+        });
+        (inner, outer)
+    }
+
+    /// Scatter random filled discs (one to two inner radii) across the grid; most
+    /// seeds settle into a few gliders.
+    pub fn reset(&mut self) {
+        self.cells.fill(0.0);
+        let mut rng = SimRng::new(self.seed);
+        let r = self.rule.outer_radius / 3.0;
+        let count = (self.width * self.height) as f64 / (self.rule.outer_radius * self.rule.outer_radius * 2.0);
+        for _ in 0..count as usize {
+            let cx = rng.below(self.width) as f64;
+            let cy = rng.below(self.height) as f64;
+            let radius = r * rng.range_f64(1.0, 2.0);
+            let reach = radius.ceil() as isize + 1;
+            for dy in -reach..=reach {
+                for dx in -reach..=reach {
+                    let coverage = smooth_disc((dx as f64).hypot(dy as f64), radius);
+                    if coverage <= 0.0 { continue; }
+                    let x = (cx as isize + dx).rem_euclid(self.width as isize) as usize;
+                    let y = (cy as isize + dy).rem_euclid(self.height as isize) as usize;
+                    let cell = &mut self.cells[y * self.width + x];
+                    *cell = cell.max(coverage);
+                }
+            }
+        }
+    }
+
+    pub fn cells(&self) -> &[f64] {
+        &self.cells
+    }
+
+    fn density(&self) -> f64 {
+        self.cells.iter().sum::<f64>() / self.cells.len().max(1) as f64
+    }
+}
+
+impl Simulation for SmoothLife {
+    fn new() -> Self {
+        Self::init(128, 128)
+    }
+
+    fn step(&mut self) {
+        // Both kernels read the same field: share the forward FFT
+        let spectrum = (self.inner.uses_fft() || self.outer.uses_fft()).then(|| self.fft.forward(&self.cells));
+        let m = self.inner.apply(&self.cells, spectrum.as_deref(), &self.fft);
+        let n = self.outer.apply(&self.cells, spectrum.as_deref(), &self.fft);
+        for ((cell, n), m) in self.cells.iter_mut().zip(n).zip(m) {
+            let s = self.rule.transition(n, m);
+            *cell = match self.rule.dt {
+                None => s,
+                Some(dt) => (*cell + dt * (2.0 * s - 1.0)).clamp(0.0, 1.0),
+            };
+        }
+    }
+
+    fn get_state(&self) -> SimState {
+        SimState::FloatGrid { width: self.width as u32, height: self.height as u32, values: self.cells.clone() }
+    }
+
+    fn set_param(&mut self, key: &str, value: ParamValue) {
+        let mut rule = self.rule;
+        match (key, value) {
+            ("seed", ParamValue::Int(seed)) => {
+                self.seed = seed as u64;
+                self.reset();
+                return;
+            }
+            ("radius", ParamValue::Float(v)) => rule.outer_radius = v.max(3.0),
+            ("b1", ParamValue::Float(v)) => rule.b1 = v,
+            ("b2", ParamValue::Float(v)) => rule.b2 = v,
+            ("d1", ParamValue::Float(v)) => rule.d1 = v,
+            ("d2", ParamValue::Float(v)) => rule.d2 = v,
+            ("alpha_n", ParamValue::Float(v)) => rule.alpha_n = v.max(1e-4),
+            ("alpha_m", ParamValue::Float(v)) => rule.alpha_m = v.max(1e-4),
+            // dt <= 0 switches back to discrete time
+            ("dt", ParamValue::Float(v)) => rule.dt = (v > 0.0).then_some(v.min(1.0)),
+            _ => return,
+        }
+        self.set_rule(rule);
+    }
+
+    fn as_experimentable(&mut self) -> Option<&mut dyn Experimentable> {
+        Some(self)
+    }
+}
+
+impl Experimentable for SmoothLife {
+    fn apply_action(&mut self, action: Action) {
+        match action {
+            Action::SetParam { name, value } => self.set_param(&name, param_value(SMOOTHLIFE_SCHEMA, &name, value)),
+            Action::Perturb { .. } => {
+                self.seed = self.seed.wrapping_add(1);
+                self.reset();
+            }
+            _ => {}
+        }
+    }
+
+    fn action_space(&self) -> ActionSpace {
+        // Any perturb reseeds the world
+        ActionSpace::new().with_perturb("reseed", 1, -1.0, 1.0).with_params(SMOOTHLIFE_SCHEMA)
+    }
+
+    fn observe(&self) -> Observation {
+        let density = self.density();
+        let var = self.cells.iter().map(|c| (c - density).powi(2)).sum::<f64>() / self.cells.len().max(1) as f64;
+        Observation::named(&[("density", density), ("contrast", var.sqrt()), ("radius", self.rule.outer_radius)])
+            .with("field", Observation::field(self.width, self.height, &self.cells, FIELD_SIDE))
+    }
+
+    fn reward(&self) -> f64 {
+        // Neither extinct nor solid
+        let density = self.density();
+        if (1e-3..=0.9).contains(&density) { 1.0 } else { -1.0 }
+    }
+}
@@ -0,0 +1,542 @@
+//! Soup search for Game of Life: seed random N×N soups, run them to stabilisation,
+//! census the ash and keep a running tally of everything found (apgsearch, scaled down).
+
+use super::{ParamValue, SimState, Simulation};
+use crate::gol::GameOfLife;
+use crate::rng::SimRng;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// Common objects, so the census can print names instead of raw keys.
+/// Rows are separated by `$`, `o` = alive, `.` = dead. Any orientation/phase works:
+/// census keys are the same for every phase of an oscillator.
+const KNOWN_OBJECTS: &[(&str, &str)] = &[
+    ("block", "oo$oo"),
+    ("beehive", ".oo.$o..o$.oo."),
+    ("loaf", ".oo.$o..o$.o.o$..o."),
+    ("boat", "oo.$o.o$.o."),
+    ("ship", "oo.$o.o$.oo"),
+    ("tub", ".o.$o.o$.o."),
+    ("pond", ".oo.$o..o$o..o$.oo."),
+    ("long boat", "oo..$o.o.$.o.o$..o."),
+    ("blinker", "ooo"),
+    ("toad", ".ooo$ooo."),
+    ("beacon", "oo..$oo..$..oo$..oo"),
+    ("glider", ".o.$..o$ooo"),
+];
+
+#[derive(Clone, Debug)]
+pub struct SoupConfig {
+    /// Side length N of the random soup
+    pub soup_size: u32,
+    /// Probability of each soup cell starting alive
+    pub density: f64,
+    /// Give up on soups that haven't settled by this generation
+    pub max_generations: u64,
+    /// Master seed; soup `i` is fully determined by (seed, i)
+    pub seed: u64,
+}
+
+impl Default for SoupConfig {
+    fn default() -> Self {
+        Self { soup_size: 16, density: 0.5, max_generations: 4000, seed: 0 }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CensusEntry {
+    pub name: Option<String>,
+    pub population: usize,
+    pub count: u64,
+    /// Seed of the first soup that produced this object (replay with `run_soup`)
+    pub first_soup_seed: u64,
+}
+
+/// Persistent tally of everything the search has found.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SoupCensus {
+    pub soups: u64,
+    pub timeouts: u64,
+    pub objects: HashMap<String, CensusEntry>,
+    /// Soups already counted per search ("seed/size/density" -> n means soups 0..n),
+    /// so a rerun with the same seed carries on instead of counting them twice
+    #[serde(default)]
+    pub searched: HashMap<String, u64>,
+}
+
+impl SoupCensus {
+    /// How many soups of this search are already in the census.
+    pub fn counted(&self, config: &SoupConfig) -> u64 {
+        self.searched.get(&search_key(config)).copied().unwrap_or(0)
+    }
+
+    /// Count one occurrence. Returns true if the object has never been seen before.
+    pub fn record(&mut self, key: &str, population: usize, soup_seed: u64) -> bool {
+        if let Some(entry) = self.objects.get_mut(key) {
+            entry.count += 1;
+            return false;
+        }
+        self.objects.insert(key.to_string(), CensusEntry {
+            name: known_name(key).map(String::from),
+            population,
+            count: 1,
+            first_soup_seed: soup_seed,
+        });
+        true
+    }
+
+    /// Objects seen at most `max_count` times, rarest (then largest) first.
+    pub fn rare(&self, max_count: u64) -> Vec<(&String, &CensusEntry)> {
+        let mut found: Vec<_> = self.objects.iter().filter(|(_, e)| e.count <= max_count).collect();
+        found.sort_by(|a, b| a.1.count.cmp(&b.1.count).then(b.1.population.cmp(&a.1.population)));
+        found
+    }
+
+    /// Fold another census (e.g. from a batch run) into this one.
+    pub fn merge(&mut self, other: &SoupCensus) {
+        self.soups += other.soups;
+        self.timeouts += other.timeouts;
+        for (key, theirs) in &other.objects {
+            self.objects
+                .entry(key.clone())
+                .and_modify(|ours| ours.count += theirs.count)
+                .or_insert_with(|| theirs.clone());
+        }
+        for (key, &count) in &other.searched {
+            let ours = self.searched.entry(key.clone()).or_default();
+            *ours = (*ours).max(count);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SoupResult {
+    pub soup_seed: u64,
+    pub generations: u64,
+    /// None if the soup hit `max_generations` without settling
+    pub period: Option<u64>,
+    /// Census keys of every object left behind (with repeats)
+    pub objects: Vec<String>,
+    /// Keys this soup added to the census for the first time
+    pub new_objects: Vec<String>,
+}
+
+type SoupCallback = Box<dyn FnMut(&SoupResult, &SoupCensus)>;
+
+pub struct SoupSearch {
+    config: SoupConfig,
+    life: GameOfLife,
+    soup_index: u64,
+    soup_seed: u64,
+    // Window hash -> generation it was seen at (cycle detection)
+    seen: HashMap<u64, u64>,
+    census: SoupCensus,
+    on_soup_done: Option<SoupCallback>,
+}
+
+impl SoupSearch {
+    pub fn with_config(config: SoupConfig) -> Self {
+        let mut search = Self {
+            config,
+            life: GameOfLife::new(),
+            soup_index: 0,
+            soup_seed: 0,
+            seen: HashMap::new(),
+            census: SoupCensus::default(),
+            on_soup_done: None,
+        };
+        search.start_soup();
+        search
+    }
+
+    /// Continue an existing tally (e.g. loaded from disk / localStorage),
+    /// skipping soups of this search it has already counted.
+    pub fn with_census(mut self, census: SoupCensus) -> Self {
+        self.census = census;
+        self.restart();
+        self
+    }
+
+    /// Called every time a soup settles; the frontend uses this to persist the census.
+    pub fn on_soup_complete(mut self, callback: impl FnMut(&SoupResult, &SoupCensus) + 'static) -> Self {
+        self.on_soup_done = Some(Box::new(callback));
+        self
+    }
+
+    pub fn census(&self) -> &SoupCensus {
+        &self.census
+    }
+
+    pub fn config(&self) -> &SoupConfig {
+        &self.config
+    }
+
+    /// Headless batch mode: run `count` whole soups back to back.
+    pub fn run_batch(&mut self, count: u64) -> Vec<SoupResult> {
+        let mut results = Vec::with_capacity(count as usize);
+        while (results.len() as u64) < count {
+            if let Some(result) = self.advance() {
+                results.push(result);
+            }
+        }
+        results
+    }
+
+    fn start_soup(&mut self) {
+        self.soup_seed = soup_seed(self.config.seed, self.soup_index);
+        self.soup_index += 1;
+        let mut rng = SimRng::new(self.soup_seed);
+        self.life.seed_soup(self.config.soup_size, self.config.density, &mut rng);
+        self.seen.clear();
+    }
+
+    fn restart(&mut self) {
+        self.soup_index = self.census.counted(&self.config);
+        self.start_soup();
+    }
+
+    /// Step the current soup once. Returns the result when it finishes.
+    fn advance(&mut self) -> Option<SoupResult> {
+        self.life.step();
+        let generation = self.life.generation();
+        let window = census_window(self.config.soup_size);
+        let cells = self.life.render_window(-window / 2, -window / 2, window as u32, window as u32);
+
+        let mut hasher = DefaultHasher::new();
+        cells.hash(&mut hasher);
+        let period = self.seen.insert(hasher.finish(), generation).map(|prev| generation - prev);
+
+        if period.is_none() && generation < self.config.max_generations {
+            return None;
+        }
+
+        let mut result = SoupResult {
+            soup_seed: self.soup_seed,
+            generations: generation,
+            period,
+            objects: Vec::new(),
+            new_objects: Vec::new(),
+        };
+
+        self.census.soups += 1;
+        self.census.searched.insert(search_key(&self.config), self.soup_index);
+        if let Some(period) = period {
+            let phases = self.phases(cells, period, window);
+            for (key, population) in census_objects(&phases, window as usize) {
+                if self.census.record(&key, population, self.soup_seed) {
+                    result.new_objects.push(key.clone());
+                }
+                result.objects.push(key);
+            }
+        } else {
+            self.census.timeouts += 1;
+        }
+
+        if let Some(callback) = self.on_soup_done.as_mut() {
+            callback(&result, &self.census);
+        }
+        self.start_soup();
+        Some(result)
+    }
+
+    /// Every phase of a settled window: `cells` and the `period - 1` generations after it.
+    fn phases(&mut self, cells: Vec<bool>, period: u64, window: i64) -> Vec<Vec<bool>> {
+        let mut phases = vec![cells];
+        for _ in 1..period {
+            self.life.step();
+            phases.push(self.life.render_window(-window / 2, -window / 2, window as u32, window as u32));
+        }
+        phases
+    }
+}
+
+impl Simulation for SoupSearch {
+    fn new() -> Self {
+        let seed = SimRng::from_entropy().next_u64();
+        Self::with_config(SoupConfig { seed, ..SoupConfig::default() })
+    }
+
+    fn step(&mut self) {
+        self.advance();
+    }
+
+    fn get_state(&self) -> SimState {
+        let window = census_window(self.config.soup_size);
+        SimState::Grid {
+            offset_x: -window / 2,
+            offset_y: -window / 2,
+            width: window as u32,
+            height: window as u32,
+            cells: self.life.render_window(-window / 2, -window / 2, window as u32, window as u32),
+        }
+    }
+
+    fn set_param(&mut self, key: &str, value: ParamValue) {
+        match (key, value) {
+            ("seed", ParamValue::Int(seed)) => self.config.seed = seed as u64,
+            ("soup_size", ParamValue::Int(size)) if size > 0 => self.config.soup_size = size as u32,
+            ("density", ParamValue::Float(d)) => self.config.density = d.clamp(0.0, 1.0),
+            ("max_generations", ParamValue::Int(g)) if g > 0 => {
+                self.config.max_generations = g as u64;
+                return;
+            }
+            _ => return,
+        }
+        // Seed / soup shape changed: start that search's sequence over (after any
+        // soups the census already has) so results stay reproducible
+        self.restart();
+    }
+}
+
+/// Seed of soup number `index` under master seed `seed`.
+pub fn soup_seed(seed: u64, index: u64) -> u64 {
+    SimRng::derive(seed, index).next_u64()
+}
+
+/// Replay a single soup from its recorded seed (no census side effects).
+pub fn run_soup(config: &SoupConfig, soup_seed: u64) -> SoupResult {
+    let mut search = SoupSearch::with_config(config.clone());
+    search.soup_seed = soup_seed;
+    let mut rng = SimRng::new(soup_seed);
+    search.life.seed_soup(config.soup_size, config.density, &mut rng);
+    search.seen.clear();
+    loop {
+        if let Some(result) = search.advance() {
+            return result;
+        }
+    }
+}
+
+/// Name of a census key, if it's one of the common objects.
+pub fn known_name(key: &str) -> Option<&'static str> {
+    KNOWN_OBJECTS
+        .iter()
+        .find(|(_, rows)| object_key(&parse_rows(rows)) == key)
+        .map(|(name, _)| *name)
+}
+
+/// Census key of an isolated pattern: stepped on its own until it repeats,
+/// so every phase of an oscillator (or spaceship) gets the same key.
+pub fn object_key(cells: &[(i64, i64)]) -> String {
+    let start = encode(cells);
+    let mut key = canonical(cells);
+    let mut cells = cells.to_vec();
+    for _ in 0..MAX_OBJECT_PERIOD {
+        cells = life_step(&cells);
+        if cells.is_empty() || encode(&cells) == start {
+            break;
+        }
+        key = key.min(canonical(&cells));
+    }
+    key
+}
+
+/// Longest period `object_key` follows an isolated pattern for
+const MAX_OBJECT_PERIOD: usize = 64;
+
+/// One Life generation of a small unbounded pattern.
+fn life_step(cells: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let alive: HashSet<(i64, i64)> = cells.iter().copied().collect();
+    let mut neighbours: HashMap<(i64, i64), u8> = HashMap::new();
+    for &(x, y) in cells {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                if (dx, dy) != (0, 0) {
+                    *neighbours.entry((x + dx, y + dy)).or_default() += 1;
+                }
+            }
+        }
+    }
+    let mut next: Vec<_> = neighbours
+        .into_iter()
+        .filter(|(cell, n)| *n == 3 || (*n == 2 && alive.contains(cell)))
+        .map(|(cell, _)| cell)
+        .collect();
+    next.sort_unstable();
+    next
+}
+
+/// Soups of a search are fully determined by these
+fn search_key(config: &SoupConfig) -> String {
+    format!("{}/{}/{}", config.seed, config.soup_size, config.density)
+}
+
+// Debris spreads well past the initial soup; escaping gliders leave the window and
+// stop affecting the hash, which is what lets those soups "settle".
+fn census_window(soup_size: u32) -> i64 {
+    (soup_size as i64 * 4).max(64)
+}
+
+/// Split a settled window into objects: 8-connected groups of the cells alive
+/// in any phase, so an oscillator whose phases fall apart (beacon) stays one
+/// object. Each gets the smallest key over its phases. Objects touching the
+/// edge are partial (usually escaping spaceships) and are skipped.
+fn census_objects(phases: &[Vec<bool>], side: usize) -> Vec<(String, usize)> {
+    let Some(first) = phases.first() else { return Vec::new() };
+    let cells: Vec<bool> = (0..first.len()).map(|i| phases.iter().any(|phase| phase[i])).collect();
+    let mut visited = vec![false; cells.len()];
+    let mut objects = Vec::new();
+
+    for start in 0..cells.len() {
+        if !cells[start] || visited[start] {
+            continue;
+        }
+        let mut stack = vec![start];
+        let mut component = Vec::new();
+        let mut touches_edge = false;
+        visited[start] = true;
+
+        while let Some(i) = stack.pop() {
+            let (x, y) = ((i % side) as i64, (i / side) as i64);
+            component.push((x, y));
+            if x == 0 || y == 0 || x == side as i64 - 1 || y == side as i64 - 1 {
+                touches_edge = true;
+            }
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let (nx, ny) = (x + dx, y + dy);
+                    if nx < 0 || ny < 0 || nx >= side as i64 || ny >= side as i64 {
+                        continue;
+                    }
+                    let ni = ny as usize * side + nx as usize;
+                    if cells[ni] && !visited[ni] {
+                        visited[ni] = true;
+                        stack.push(ni);
+                    }
+                }
+            }
+        }
+
+        if !touches_edge {
+            let in_phase = |phase: &Vec<bool>| -> Vec<(i64, i64)> {
+                component.iter().copied().filter(|&(x, y)| phase[y as usize * side + x as usize]).collect()
+            };
+            let population = in_phase(first).len();
+            let key = phases.iter().map(in_phase).filter(|cells| !cells.is_empty()).map(|cells| canonical(&cells)).min();
+            if let Some(key) = key {
+                objects.push((key, population));
+            }
+        }
+    }
+    objects
+}
+
+type Symmetry = fn(i64, i64) -> (i64, i64);
+
+/// Orientation-independent key: the smallest encoding over all 8 symmetries.
+fn canonical(cells: &[(i64, i64)]) -> String {
+    let symmetries: [Symmetry; 8] = [
+        |x, y| (x, y), |x, y| (-x, y), |x, y| (x, -y), |x, y| (-x, -y),
+        |x, y| (y, x), |x, y| (-y, x), |x, y| (y, -x), |x, y| (-y, -x),
+    ];
+    symmetries
+        .iter()
+        .map(|t| encode(&cells.iter().map(|&(x, y)| t(x, y)).collect::<Vec<_>>()))
+        .min()
+        .unwrap_or_default()
+}
+
+fn encode(cells: &[(i64, i64)]) -> String {
+    if cells.is_empty() {
+        return String::new();
+    }
+    let min_x = cells.iter().map(|c| c.0).min().unwrap_or(0);
+    let min_y = cells.iter().map(|c| c.1).min().unwrap_or(0);
+    let w = (cells.iter().map(|c| c.0).max().unwrap_or(0) - min_x + 1) as usize;
+    let h = (cells.iter().map(|c| c.1).max().unwrap_or(0) - min_y + 1) as usize;
+
+    let mut grid = vec![vec!['.'; w]; h];
+    for &(x, y) in cells {
+        grid[(y - min_y) as usize][(x - min_x) as usize] = 'o';
+    }
+    grid.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("$")
+}
+
+fn parse_rows(rows: &str) -> Vec<(i64, i64)> {
+    rows.split('$')
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|(_, ch)| *ch == 'o')
+                .map(move |(x, _)| (x as i64, y as i64))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `side`×`side` window with `rows` drawn at (x, y)
+    fn window(side: usize, x: usize, y: usize, rows: &str) -> Vec<bool> {
+        let mut cells = vec![false; side * side];
+        for (dx, dy) in parse_rows(rows) {
+            cells[(y + dy as usize) * side + x + dx as usize] = true;
+        }
+        cells
+    }
+
+    #[test]
+    fn keys_ignore_orientation_and_position() {
+        let boat = parse_rows("oo.$o.o$.o.");
+        let turned: Vec<_> = boat.iter().map(|&(x, y)| (10 - y, x + 3)).collect();
+        assert_eq!(object_key(&boat), object_key(&turned));
+        assert_eq!(known_name(&object_key(&turned)), Some("boat"));
+        assert_ne!(object_key(&boat), object_key(&parse_rows("oo.$o.o$.oo")));
+    }
+
+    #[test]
+    fn oscillator_phases_share_a_key() {
+        for (name, a, b) in [
+            ("blinker", "ooo", "o$o$o"),
+            ("toad", ".ooo$ooo.", "..o.$o..o$o..o$.o.."),
+            ("beacon", "oo..$oo..$..oo$..oo", "oo..$o...$...o$..oo"),
+            ("glider", ".o.$..o$ooo", "o.o$.oo$.o."),
+        ] {
+            let (a, b) = (object_key(&parse_rows(a)), object_key(&parse_rows(b)));
+            assert_eq!(a, b, "{}", name);
+            assert_eq!(known_name(&a), Some(name));
+        }
+    }
+
+    #[test]
+    fn census_joins_split_phases() {
+        // Beacon's second phase is two separate corners; with both phases it is one object
+        let phases = [window(12, 4, 4, "oo..$oo..$..oo$..oo"), window(12, 4, 4, "oo..$o...$...o$..oo")];
+        let objects = census_objects(&phases, 12);
+        assert_eq!(objects.len(), 1);
+        assert_eq!(known_name(&objects[0].0), Some("beacon"));
+        assert_eq!(objects[0].1, 8);
+        assert_eq!(census_objects(&phases[1..], 12).len(), 2);
+    }
+
+    #[test]
+    fn census_skips_objects_on_the_edge() {
+        let phases = [window(8, 0, 3, "oo$oo"), window(8, 5, 5, "oo$oo")];
+        assert_eq!(census_objects(&phases[..1], 8).len(), 0);
+        assert_eq!(census_objects(&phases[1..], 8), vec![(object_key(&parse_rows("oo$oo")), 4)]);
+    }
+
+    #[test]
+    fn rerun_with_a_saved_census_skips_counted_soups() {
+        let config = SoupConfig { soup_size: 8, max_generations: 500, seed: 7, ..SoupConfig::default() };
+        let mut first = SoupSearch::with_config(config.clone());
+        let first_seeds: Vec<u64> = first.run_batch(3).iter().map(|r| r.soup_seed).collect();
+        assert_eq!(first.census().counted(&config), 3);
+
+        let mut second = SoupSearch::with_config(config.clone()).with_census(first.census().clone());
+        let second_seeds: Vec<u64> = second.run_batch(3).iter().map(|r| r.soup_seed).collect();
+        assert_eq!(second_seeds, (3..6).map(|i| soup_seed(7, i)).collect::<Vec<_>>());
+        assert!(first_seeds.iter().all(|seed| !second_seeds.contains(seed)));
+        assert_eq!(second.census().soups, 6);
+        assert_eq!(second.census().counted(&config), 6);
+
+        // A different seed starts from its own first soup
+        let other = SoupConfig { seed: 8, ..config };
+        let mut third = SoupSearch::with_config(other).with_census(second.census().clone());
+        assert_eq!(third.run_batch(1)[0].soup_seed, soup_seed(8, 0));
+    }
+}
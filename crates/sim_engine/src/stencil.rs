@@ -0,0 +1,128 @@
+//! Shared 3x3 stencil machinery for grid PDEs (Gray-Scott and the other reaction-diffusion models)
+
+use std::ops::{Add, Mul, Sub};
+
+/// What the Laplacian sees past the grid edge.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Boundary {
+    /// Toroidal wrap-around
+    Periodic,
+    /// No-flux: the edge value is mirrored outward (zero gradient)
+    Neumann,
+    /// Fixed concentrations outside the grid
+    Dirichlet { u: f64, v: f64 },
+}
+
+impl Boundary {
+    /// "periodic", "neumann", "dirichlet" or "dirichlet:U:V" (fixed values outside)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let parts: Vec<&str> = name.split(':').map(str::trim).collect();
+        match parts.as_slice() {
+            ["periodic"] => Some(Self::Periodic),
+            ["neumann"] => Some(Self::Neumann),
+            // Clamp to the homogeneous state by default
+            ["dirichlet"] => Some(Self::Dirichlet { u: 1.0, v: 0.0 }),
+            ["dirichlet", u, v] => Some(Self::Dirichlet { u: u.parse().ok()?, v: v.parse().ok()? }),
+            _ => None,
+        }
+    }
+}
+
+/// Float type a stencil kernel computes in: f64 everywhere, or f32 for
+/// Gray-Scott's single-precision kernel.
+pub(crate) trait Real: Copy + Send + Sync + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+    fn of(x: f64) -> Self;
+    fn clamp_unit(self) -> Self;
+}
+
+impl Real for f64 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+    #[inline(always)]
+    fn of(x: f64) -> Self {
+        x
+    }
+    #[inline(always)]
+    fn clamp_unit(self) -> Self {
+        self.clamp(0.0, 1.0)
+    }
+}
+
+impl Real for f32 {
+    const ZERO: Self = 0.0;
+    const ONE: Self = 1.0;
+    #[inline(always)]
+    fn of(x: f64) -> Self {
+        x as f32
+    }
+    #[inline(always)]
+    fn clamp_unit(self) -> Self {
+        self.clamp(0.0, 1.0)
+    }
+}
+
+/// Rows y-1, y, y+1 of one field, with the vertical boundary already applied.
+pub(crate) struct RowTriple<'a, T = f64> {
+    pub up: &'a [T],
+    pub mid: &'a [T],
+    pub down: &'a [T],
+}
+
+impl<'a, T: Copy> RowTriple<'a, T> {
+    pub fn at(field: &'a [T], ghost: &'a [T], y: usize, w: usize, h: usize, boundary: Boundary) -> Self {
+        let row = |r: usize| &field[r * w..(r + 1) * w];
+        let (up, down) = match boundary {
+            Boundary::Periodic => (row((y + h - 1) % h), row((y + 1) % h)),
+            Boundary::Neumann => (row(y.saturating_sub(1)), row((y + 1).min(h - 1))),
+            Boundary::Dirichlet { .. } => (
+                if y == 0 { ghost } else { row(y - 1) },
+                if y + 1 == h { ghost } else { row(y + 1) },
+            ),
+        };
+        Self { up, mid: row(y), down }
+    }
+
+    /// The 3x3 neighbourhood of column x (edge columns only; honours the boundary).
+    #[inline(always)]
+    pub fn edge_neighbourhood(&self, x: usize, boundary: Boundary, ghost: T) -> [T; 9] {
+        let w = self.mid.len() as isize;
+        let pick = |row: &[T], x: isize| -> T {
+            match boundary {
+                Boundary::Periodic => row[((x + w) % w) as usize],
+                Boundary::Neumann => row[x.clamp(0, w - 1) as usize],
+                Boundary::Dirichlet { .. } => {
+                    if x < 0 || x >= w { ghost } else { row[x as usize] }
+                }
+            }
+        };
+        let x = x as isize;
+        [
+            pick(self.up, x - 1), pick(self.up, x), pick(self.up, x + 1),
+            pick(self.mid, x - 1), pick(self.mid, x), pick(self.mid, x + 1),
+            pick(self.down, x - 1), pick(self.down, x), pick(self.down, x + 1),
+        ]
+    }
+
+    /// The 3x3 neighbourhood of an interior column (1..w-1), no wrapping needed.
+    #[inline(always)]
+    pub fn neighbourhood(&self, x: usize) -> [T; 9] {
+        [
+            self.up[x - 1], self.up[x], self.up[x + 1],
+            self.mid[x - 1], self.mid[x], self.mid[x + 1],
+            self.down[x - 1], self.down[x], self.down[x + 1],
+        ]
+    }
+}
+
+/// 9-point isotropic Laplacian (adjacent 0.2, diagonal 0.05, centre -1).
+/// The accumulation order is fixed: Gray-Scott's kernel relies on it being
+/// bit-identical to its reference implementation in f64.
+#[inline(always)]
+pub(crate) fn laplacian<T: Real>(n: [T; 9]) -> T {
+    let (adjacent, diagonal) = (T::of(0.2), T::of(0.05));
+    T::ZERO + n[0] * diagonal + n[1] * adjacent + n[2] * diagonal
+        + n[3] * adjacent - n[4] + n[5] * adjacent
+        + n[6] * diagonal + n[7] * adjacent + n[8] * diagonal
+}
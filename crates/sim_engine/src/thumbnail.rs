@@ -0,0 +1,124 @@
+//! Headless rendering of `SimState`s to PNG (for preset galleries, archives, etc.)
+//!
+//! The encoder writes uncompressed ("stored") deflate blocks, which keeps it
+//! dependency-free; thumbnails are small enough that the size doesn't matter.
+
+use crate::SimState;
+
+/// Same Blue -> Cyan ramp the frontend heatmap uses.
+pub fn heat_rgb(v: f64) -> [u8; 3] {
+    let v = v.clamp(0.0, 1.0);
+    [0, (v * 200.0) as u8, (v * 255.0) as u8]
+}
+
+/// Distinct colours for particle species (cycled past 8).
+pub fn species_rgb(species: u8) -> [u8; 3] {
+    const PALETTE: [[u8; 3]; 8] = [
+        [0, 170, 255], [255, 85, 85], [120, 220, 80], [255, 200, 40],
+        [200, 100, 255], [40, 220, 200], [255, 140, 0], [230, 230, 230],
+    ];
+    PALETTE[species as usize % PALETTE.len()]
+}
+
+/// Longest side of a rasterised particle snapshot
+const PARTICLE_RASTER: f64 = 128.0;
+
+/// RGB8 pixels for a state, one pixel per cell (particles are rasterised).
+/// Returns (width, height, pixels).
+pub fn render_rgb(state: &SimState) -> Option<(u32, u32, Vec<u8>)> {
+    match state {
+        SimState::FloatGrid { width, height, values } => {
+            let pixels = values.iter().flat_map(|&v| heat_rgb(v)).collect();
+            Some((*width, *height, pixels))
+        }
+        SimState::Grid { width, height, cells, .. } => {
+            let pixels = cells
+                .iter()
+                .flat_map(|&alive| if alive { [0, 255, 0] } else { [0, 0, 0] })
+                .collect();
+            Some((*width, *height, pixels))
+        }
+        SimState::Particles { width, height, positions, species, .. } => {
+            let scale = PARTICLE_RASTER / width.max(*height).max(1e-9);
+            let (w, h) = (((width * scale).ceil() as u32).max(1), ((height * scale).ceil() as u32).max(1));
+            let mut pixels = vec![0; (w * h * 3) as usize];
+            for (&(x, y), &s) in positions.iter().zip(species) {
+                let px = ((x * scale) as u32).min(w - 1);
+                let py = ((y * scale) as u32).min(h - 1);
+                let i = ((py * w + px) * 3) as usize;
+                pixels[i..i + 3].copy_from_slice(&species_rgb(s));
+            }
+            Some((w, h, pixels))
+        }
+        _ => None,
+    }
+}
+
+/// Encode RGB8 pixels as a PNG file.
+pub fn encode_png(width: u32, height: u32, rgb: &[u8]) -> Vec<u8> {
+    // Scanlines, each prefixed by filter type 0 (None)
+    let stride = width as usize * 3;
+    let mut raw = Vec::with_capacity((stride + 1) * height as usize);
+    for row in rgb.chunks(stride).take(height as usize) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&width.to_be_bytes());
+    ihdr.extend_from_slice(&height.to_be_bytes());
+    ihdr.extend_from_slice(&[8, 2, 0, 0, 0]); // 8-bit, truecolour, deflate, no filter, no interlace
+    write_chunk(&mut png, b"IHDR", &ihdr);
+    write_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(65535).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(last as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}